@@ -30,13 +30,22 @@ use std::rc::Rc;
 ///     Maximum number of iterations for cross association. Defaults to 50.
 /// tol_cross_assoc : float
 ///     Tolerance for convergence of cross association. Defaults to 1e-10.
+/// born : bool, optional
+///     Include the Born contribution for charged segments. Defaults to False.
+/// coulomb_cutoff : float, optional
+///     Cutoff radius (in Angstrom) of the mean-field Coulomb interaction of
+///     charged segments, which should be equal to the width of planar
+///     profiles. Without a cutoff radius, the contribution is not included.
+/// permittivity : Permittivity, optional
+///     The model for the relative permittivity in the electrostatic
+///     contributions. Defaults to the constant permittivity of water.
 ///
 /// Returns
 /// -------
 /// GcPcSaftFunctional
 #[pyclass(name = "GcPcSaftFunctional", unsendable)]
 #[pyo3(
-    text_signature = "(parameters, fmt_version, max_eta, max_iter_cross_assoc, tol_cross_assoc)"
+    text_signature = "(parameters, fmt_version, max_eta, max_iter_cross_assoc, tol_cross_assoc, born, coulomb_cutoff, permittivity)"
 )]
 #[derive(Clone)]
 pub struct PyGcPcSaftFunctional(pub Rc<DFT<GcPcSaftFunctional>>);
@@ -48,7 +57,8 @@ impl PyGcPcSaftFunctional {
         fmt_version = "FMTVersion::WhiteBear",
         max_eta = "0.5",
         max_iter_cross_assoc = "50",
-        tol_cross_assoc = "1e-10"
+        tol_cross_assoc = "1e-10",
+        born = "false"
     )]
     fn new(
         parameters: PyGcPcSaftFunctionalParameters,
@@ -56,17 +66,23 @@ impl PyGcPcSaftFunctional {
         max_eta: f64,
         max_iter_cross_assoc: usize,
         tol_cross_assoc: f64,
-    ) -> Self {
+        born: bool,
+        coulomb_cutoff: Option<f64>,
+        permittivity: Option<PyPermittivity>,
+    ) -> PyResult<Self> {
         let options = GcPcSaftOptions {
             max_eta,
             max_iter_cross_assoc,
             tol_cross_assoc,
+            born,
+            coulomb_cutoff,
         };
-        Self(Rc::new(GcPcSaftFunctional::with_options(
+        Ok(Self(Rc::new(GcPcSaftFunctional::with_permittivity(
             parameters.0,
             fmt_version,
             options,
-        )))
+            permittivity.map_or_else(Default::default, |p| p.0),
+        )?)))
     }
 }
 
@@ -104,5 +120,6 @@ pub fn dft(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PySolvationProfile>()?;
     m.add_class::<FMTVersion>()?;
     m.add_class::<PyMicelleProfile>()?;
+    m.add_class::<PyMicelleSizeDistribution>()?;
     Ok(())
 }
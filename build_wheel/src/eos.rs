@@ -1,5 +1,5 @@
 use feos_core::*;
-use feos_gc_pcsaft::python::PyGcPcSaftEosParameters;
+use feos_gc_pcsaft::python::{PyGcPcSaftEosParameters, PyPermittivity};
 use feos_gc_pcsaft::{GcPcSaft, GcPcSaftOptions};
 use numpy::convert::ToPyArray;
 use numpy::{PyArray1, PyArray2};
@@ -22,6 +22,11 @@ use std::rc::Rc;
 ///     Maximum number of iterations for cross association. Defaults to 50.
 /// tol_cross_assoc : float
 ///     Tolerance for convergence of cross association. Defaults to 1e-10.
+/// born : bool, optional
+///     Include the Born contribution for charged segments. Defaults to False.
+/// permittivity : Permittivity, optional
+///     The model for the relative permittivity in the electrostatic
+///     contributions. Defaults to the constant permittivity of water.
 ///
 /// Returns
 /// -------
@@ -29,7 +34,9 @@ use std::rc::Rc;
 ///     The gc-PC-SAFT equation of state that can be used to compute thermodynamic
 ///     states.
 #[pyclass(name = "GcPcSaft", unsendable)]
-#[pyo3(text_signature = "(parameters, max_eta, max_iter_cross_assoc, tol_cross_assoc)")]
+#[pyo3(
+    text_signature = "(parameters, max_eta, max_iter_cross_assoc, tol_cross_assoc, born, permittivity)"
+)]
 #[derive(Clone)]
 pub struct PyGcPcSaft(pub Rc<GcPcSaft>);
 
@@ -39,20 +46,27 @@ impl PyGcPcSaft {
     #[args(
         max_eta = "0.5",
         max_iter_cross_assoc = "50",
-        tol_cross_assoc = "1e-10"
+        tol_cross_assoc = "1e-10",
+        born = "false"
     )]
     fn new(
         parameters: PyGcPcSaftEosParameters,
         max_eta: f64,
         max_iter_cross_assoc: usize,
         tol_cross_assoc: f64,
-    ) -> Self {
+        born: bool,
+        permittivity: Option<PyPermittivity>,
+    ) -> PyResult<Self> {
         let options = GcPcSaftOptions {
             max_eta,
             max_iter_cross_assoc,
             tol_cross_assoc,
+            born,
+            ..Default::default()
         };
-        Self(Rc::new(GcPcSaft::with_options(parameters.0, options)))
+        let eos = GcPcSaft::with_options(parameters.0, options)
+            .with_permittivity(permittivity.map_or_else(Default::default, |p| p.0))?;
+        Ok(Self(Rc::new(eos)))
     }
 }
 
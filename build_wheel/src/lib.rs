@@ -20,11 +20,15 @@ pub fn feos_gc_pcsaft(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyJobackRecord>()?;
 
     m.add_class::<PyGcPcSaftRecord>()?;
+    m.add_class::<PyAssociationRecord>()?;
     m.add_class::<PySegmentRecord>()?;
     m.add_class::<PyBinaryRecord>()?;
     m.add_class::<PyBinarySegmentRecord>()?;
+    m.add_class::<PyGcPcSaftBinaryRecord>()?;
+    m.add_class::<PyGcPcSaftBinarySegmentRecord>()?;
     m.add_class::<PyGcPcSaftEosParameters>()?;
     m.add_class::<PyGcPcSaftFunctionalParameters>()?;
+    m.add_class::<PyPermittivity>()?;
 
     m.add_wrapped(wrap_pymodule!(eos))?;
     m.add_wrapped(wrap_pymodule!(dft))?;
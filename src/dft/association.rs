@@ -1,6 +1,7 @@
 use super::parameter::GcPcSaftFunctionalParameters;
 use crate::eos::association::{
     assoc_site_frac_a, assoc_site_frac_ab, helmholtz_energy_density_cross_association,
+    single_segment_association,
 };
 use feos_core::EosError;
 use feos_dft::{
@@ -11,19 +12,19 @@ use num_dual::DualNum;
 use std::f64::consts::PI;
 use std::fmt;
 use std::ops::MulAssign;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const N0_CUTOFF: f64 = 1e-9;
 
 #[derive(Clone)]
 pub struct AssociationFunctional {
-    parameters: Rc<GcPcSaftFunctionalParameters>,
+    parameters: Arc<GcPcSaftFunctionalParameters>,
     max_iter: usize,
     tol: f64,
 }
 
 impl AssociationFunctional {
-    pub fn new(parameters: &Rc<GcPcSaftFunctionalParameters>, max_iter: usize, tol: f64) -> Self {
+    pub fn new(parameters: &Arc<GcPcSaftFunctionalParameters>, max_iter: usize, tol: f64) -> Self {
         Self {
             parameters: parameters.clone(),
             max_iter,
@@ -72,7 +73,7 @@ where
         // number of segments
         let segments = p.m.len();
 
-        // number of associating segments
+        // number of association site types
         let nassoc = p.assoc_segment.len();
 
         // number of dimensions
@@ -133,31 +134,32 @@ where
         // auxiliary variables
         let n3i = n3.mapv(|n3| (-n3 + 1.0).recip());
 
-        // only one associating component
-        if nassoc == 1 {
+        // only one associating segment
+        if single_segment_association(&p.assoc_segment, &p.sigma3_kappa_aibj) {
             // association strength
+            let b = nassoc - 1;
             let k = &n2 * &n3i * diameter[p.assoc_segment[0]] * 0.5;
             let deltarho = (((&k / 18.0 + 0.5) * &k * xi + 1.0) * n3i)
-                * ((temperature.recip() * p.epsilon_k_aibj[(0, 0)]).exp_m1()
-                    * p.sigma3_kappa_aibj[(0, 0)])
+                * ((temperature.recip() * p.epsilon_k_aibj[(0, b)]).exp_m1()
+                    * p.sigma3_kappa_aibj[(0, b)])
                 * rho0.index_axis(Axis(0), 0);
 
-            let na = p.na[0];
-            let nb = p.nb[0];
+            let na = p.n_sites[0];
             let f = |x: N| x.ln() - x * 0.5 + 0.5;
-            if nb > 0.0 {
-                // no cross association, two association sites
+            let phi = if b == 0 {
+                // a single site type that associates with itself
+                let xa = deltarho.mapv(|d| assoc_site_frac_a(d, na));
+                xa.mapv(f) * na
+            } else {
+                // two site types that associate with each other
+                let nb = p.n_sites[1];
                 let xa = deltarho.mapv(|d| assoc_site_frac_ab(d, na, nb));
                 let xb = (&xa - 1.0) * (na / nb) + 1.0;
-                Ok((xa.mapv(f) * na + xb.mapv(f) * nb) * rho0.index_axis(Axis(0), 0))
-            } else {
-                // no cross association, one association site
-                let xa = deltarho.mapv(|d| assoc_site_frac_a(d, na));
-
-                Ok(xa.mapv(f) * na * rho0.index_axis(Axis(0), 0))
-            }
+                xa.mapv(f) * na + xb.mapv(f) * nb
+            };
+            Ok(phi * rho0.index_axis(Axis(0), 0))
         } else {
-            let mut x: Array1<f64> = Array::from_elem(2 * nassoc, 0.2);
+            let mut x: Array1<f64> = Array::from_elem(nassoc, 0.2);
             Ok(rho0
                 .view()
                 .into_shape([nassoc, rho0.len() / nassoc])
@@ -171,8 +173,7 @@ where
                         &p.assoc_segment,
                         &p.sigma3_kappa_aibj,
                         &p.epsilon_k_aibj,
-                        &p.na,
-                        &p.nb,
+                        &p.n_sites,
                         temperature,
                         &rho0,
                         &diameter,
@@ -8,15 +8,15 @@ use ndarray::*;
 use num_dual::DualNum;
 use std::f64::consts::{FRAC_PI_6, PI};
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AttractiveFunctional {
-    parameters: Rc<GcPcSaftFunctionalParameters>,
+    parameters: Arc<GcPcSaftFunctionalParameters>,
 }
 
 impl AttractiveFunctional {
-    pub fn new(parameters: &Rc<GcPcSaftFunctionalParameters>) -> Self {
+    pub fn new(parameters: &Arc<GcPcSaftFunctionalParameters>) -> Self {
         Self {
             parameters: parameters.clone(),
         }
@@ -49,7 +49,7 @@ impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for Attracti
         // packing fraction
         let eta = density
             .outer_iter()
-            .zip((&d * &d * &d * &p.m * FRAC_PI_6).into_iter())
+            .zip(&d * &d * &d * &p.m * FRAC_PI_6)
             .map(|(rho, d3m)| &rho * d3m)
             .reduce(|a, b| a + b)
             .unwrap();
@@ -75,12 +75,15 @@ impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for Attracti
             }
         });
 
+        // temperature dependent dispersion energy
+        let epsilon_k_ij = p.epsilon_k_ij(temperature);
+
         // mixture densities, crosswise interactions of all segments on all chains
         let mut rho1mix: Array1<N> = Array::zeros(eta.raw_dim());
         let mut rho2mix: Array1<N> = Array::zeros(eta.raw_dim());
         for i in 0..n {
             for j in 0..n {
-                let eps_ij = temperature.recip() * p.epsilon_k_ij[(i, j)];
+                let eps_ij = temperature.recip() * epsilon_k_ij[(i, j)];
                 let sigma_ij = p.sigma_ij[(i, j)].powi(3);
                 rho1mix = rho1mix
                     + (&density.index_axis(Axis(0), i) * &density.index_axis(Axis(0), j))
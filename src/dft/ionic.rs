@@ -0,0 +1,269 @@
+use super::parameter::GcPcSaftFunctionalParameters;
+use crate::eos::ionic::{
+    born_helmholtz_energy_density, debye_hueckel_helmholtz_energy_density, Permittivity,
+    PermittivityWeights, BJERRUM_LENGTH_VACUUM,
+};
+use feos_core::EosError;
+use feos_dft::{
+    FunctionalContributionDual, WeightFunction, WeightFunctionInfo, WeightFunctionShape,
+};
+use ndarray::*;
+use num_dual::DualNum;
+use std::fmt;
+use std::sync::Arc;
+
+/// Local densities of all components (averaged over the segments of each
+/// component) and of the charged segments together with the relative
+/// permittivity at every grid point.
+fn ionic_local_densities<N: DualNum<f64> + ScalarOperand>(
+    p: &GcPcSaftFunctionalParameters,
+    permittivity: &Permittivity,
+    density: ArrayView2<N>,
+) -> (Array1<N>, Array2<N>) {
+    let n = p.m.len();
+
+    // local component densities
+    let mut s_i: Array1<f64> = Array::zeros(p.component_index[n - 1] + 1);
+    for &c in p.component_index.iter() {
+        s_i[c] += 1.0;
+    }
+    let weights = PermittivityWeights::new(&p.component_index, &p.ion_segment, s_i.len());
+    let epsilon_r = density
+        .axis_iter(Axis(1))
+        .map(|rho| {
+            let mut rho_comp: Array1<N> = Array::zeros(s_i.len());
+            for (&r, &c) in rho.iter().zip(p.component_index.iter()) {
+                rho_comp[c] += r / s_i[c];
+            }
+            permittivity.relative_permittivity(&weights, &p.molarweight, &rho_comp)
+        })
+        .collect();
+
+    // local densities of the charged segments
+    let rho_ion = Array::from_shape_fn((p.ion_segment.len(), density.shape()[1]), |(k, i)| {
+        density[(p.ion_segment[k], i)]
+    });
+
+    (epsilon_r, rho_ion)
+}
+
+/// Debye-Hückel functional of the charged segments in a local density
+/// approximation, consistent with the equation of state in the bulk.
+#[derive(Clone)]
+pub struct DebyeHueckelFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    permittivity: Permittivity,
+}
+
+impl DebyeHueckelFunctional {
+    pub fn new(
+        parameters: &Arc<GcPcSaftFunctionalParameters>,
+        permittivity: &Permittivity,
+    ) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            permittivity: permittivity.clone(),
+        }
+    }
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for DebyeHueckelFunctional {
+    fn weight_functions(&self, _: N) -> WeightFunctionInfo<N> {
+        WeightFunctionInfo::new(self.parameters.component_index.clone(), true)
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        density: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let p = &self.parameters;
+        let diameter = p.ion_segment.mapv(|s| p.sigma[s]);
+        let (epsilon_r, rho_ion) = ionic_local_densities(p, &self.permittivity, density);
+        Ok(rho_ion
+            .axis_iter(Axis(1))
+            .zip(epsilon_r.iter())
+            .map(|(rho, &epsilon_r)| {
+                debye_hueckel_helmholtz_energy_density(
+                    temperature,
+                    epsilon_r,
+                    &rho.to_owned(),
+                    &p.z,
+                    &diameter,
+                )
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for DebyeHueckelFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Debye-Hückel functional (GC)")
+    }
+}
+
+/// Born functional of the charged segments in a local density approximation.
+#[derive(Clone)]
+pub struct BornFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    permittivity: Permittivity,
+}
+
+impl BornFunctional {
+    pub fn new(
+        parameters: &Arc<GcPcSaftFunctionalParameters>,
+        permittivity: &Permittivity,
+    ) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            permittivity: permittivity.clone(),
+        }
+    }
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for BornFunctional {
+    fn weight_functions(&self, _: N) -> WeightFunctionInfo<N> {
+        WeightFunctionInfo::new(self.parameters.component_index.clone(), true)
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        density: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let p = &self.parameters;
+        let diameter = p.ion_segment.mapv(|s| p.sigma[s]);
+        let (epsilon_r, rho_ion) = ionic_local_densities(p, &self.permittivity, density);
+        Ok(rho_ion
+            .axis_iter(Axis(1))
+            .zip(epsilon_r.iter())
+            .map(|(rho, &epsilon_r)| {
+                born_helmholtz_energy_density(
+                    temperature,
+                    epsilon_r,
+                    &rho.to_owned(),
+                    &p.z,
+                    &diameter,
+                )
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for BornFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Born functional (GC)")
+    }
+}
+
+/// Width of the shells (in Angstrom) used to discretize the Coulomb kernel.
+const COULOMB_SHELL_WIDTH: f64 = 0.5;
+
+/// Mean-field Coulomb interaction of the charged segments
+/// $\beta F=\frac{1}{2}\int\int q(\mathbf{r})\,l_\mathrm{B}\left(\frac{1}{|\mathbf{r}-\mathbf{r}'|}-\frac{1}{r_\mathrm{c}}\right)q(\mathbf{r}')\,\mathrm{d}\mathbf{r}'\mathrm{d}\mathbf{r}$
+/// with the charge density $q$, which is truncated at the cutoff radius $r_\mathrm{c}$.
+///
+/// The kernel is approximated by a sum of step functions on shells of equal
+/// width, each of which yields a weighted charge density, so that the
+/// volume integral of the kernel is exact. The Bjerrum length $l_\mathrm{B}$
+/// is evaluated with the local permittivity. The contribution vanishes for
+/// electroneutral bulk phases.
+///
+/// For planar profiles, the reflective boundaries make the system periodic
+/// with twice its width. If the cutoff radius equals the width, the kernel is
+/// the Green's function of this periodic system relative to a uniform
+/// background charge. For spherical and cylindrical profiles, the cutoff
+/// radius should exceed twice the width of the system.
+#[derive(Clone)]
+pub struct CoulombFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    permittivity: Permittivity,
+    /// Radius and coefficient of the step functions
+    shells: Vec<(f64, f64)>,
+}
+
+impl CoulombFunctional {
+    pub fn new(
+        parameters: &Arc<GcPcSaftFunctionalParameters>,
+        permittivity: &Permittivity,
+        cutoff: f64,
+    ) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            permittivity: permittivity.clone(),
+            shells: coulomb_shells(cutoff),
+        }
+    }
+}
+
+/// Radii $R_j$ and coefficients $c_j$ of the step functions with
+/// $\sum_jc_j\Theta(R_j-r)\approx\frac{1}{r}-\frac{1}{r_\mathrm{c}}$, where
+/// the value on every shell is the volume average of the kernel.
+fn coulomb_shells(cutoff: f64) -> Vec<(f64, f64)> {
+    let n = (cutoff / COULOMB_SHELL_WIDTH).ceil().max(1.0) as usize;
+    let radius: Vec<_> = (1..=n).map(|j| cutoff * j as f64 / n as f64).collect();
+    let mut kernel: Vec<_> = (0..n)
+        .map(|j| {
+            let (r0, r1) = (if j == 0 { 0.0 } else { radius[j - 1] }, radius[j]);
+            1.5 * (r1 * r1 - r0 * r0) / (r1.powi(3) - r0.powi(3)) - 1.0 / cutoff
+        })
+        .collect();
+    kernel.push(0.0);
+    (0..n)
+        .map(|j| (radius[j], kernel[j] - kernel[j + 1]))
+        .collect()
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for CoulombFunctional {
+    fn weight_functions(&self, _: N) -> WeightFunctionInfo<N> {
+        let p = &self.parameters;
+        let mut z = Array1::zeros(p.m.len());
+        for (&s, &z_s) in p.ion_segment.iter().zip(p.z.iter()) {
+            z[s] = z_s;
+        }
+        self.shells.iter().fold(
+            WeightFunctionInfo::new(p.component_index.clone(), true),
+            |info, &(radius, _)| {
+                info.add(
+                    WeightFunction {
+                        prefactor: z.mapv(N::from),
+                        kernel_radius: Array1::from_elem(z.len(), N::from(radius)),
+                        shape: WeightFunctionShape::Theta,
+                    },
+                    true,
+                )
+            },
+        )
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        weighted_densities: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let p = &self.parameters;
+        let segments = p.m.len();
+        let (epsilon_r, rho_ion) = ionic_local_densities(p, &self.permittivity, weighted_densities);
+
+        // local charge density
+        let mut q: Array1<N> = Array::zeros(epsilon_r.len());
+        for (rho, &z) in rho_ion.outer_iter().zip(p.z.iter()) {
+            q += &(&rho * z);
+        }
+
+        // mean-field potential per Bjerrum length
+        let mut psi: Array1<N> = Array::zeros(epsilon_r.len());
+        for (j, &(_, c)) in self.shells.iter().enumerate() {
+            psi += &(&weighted_densities.index_axis(Axis(0), segments + j) * c);
+        }
+
+        let bjerrum_length = epsilon_r.mapv(|e| (temperature * e).recip() * BJERRUM_LENGTH_VACUUM);
+        Ok(q * psi * bjerrum_length * 0.5)
+    }
+}
+
+impl fmt::Display for CoulombFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mean-field Coulomb functional (GC)")
+    }
+}
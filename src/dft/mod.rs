@@ -1,5 +1,5 @@
-use crate::eos::GcPcSaftOptions;
-use feos_core::MolarWeight;
+use crate::eos::{GcPcSaftOptions, Permittivity};
+use feos_core::{EosResult, MolarWeight};
 use feos_dft::adsorption::FluidParameters;
 use feos_dft::fundamental_measure_theory::{FMTContribution, FMTProperties, FMTVersion};
 use feos_dft::{FunctionalContribution, HelmholtzEnergyFunctional, MoleculeShape, DFT};
@@ -9,26 +9,32 @@ use petgraph::graph::UnGraph;
 use quantity::si::{SIArray1, SIUnit, GRAM, MOL};
 use std::f64::consts::FRAC_PI_6;
 use std::rc::Rc;
+use std::sync::Arc;
 
 mod association;
 mod dispersion;
 mod hard_chain;
+mod ionic;
 mod parameter;
+mod polar;
 use association::AssociationFunctional;
 use dispersion::AttractiveFunctional;
 use hard_chain::ChainFunctional;
+use ionic::{BornFunctional, CoulombFunctional, DebyeHueckelFunctional};
 pub use parameter::GcPcSaftFunctionalParameters;
+use polar::{DipoleFunctional, DipoleQuadrupoleFunctional, QuadrupoleFunctional};
 
 /// gc-PC-SAFT Helmholtz energy functional.
 pub struct GcPcSaftFunctional {
-    pub parameters: Rc<GcPcSaftFunctionalParameters>,
+    pub parameters: Arc<GcPcSaftFunctionalParameters>,
     fmt_version: FMTVersion,
     options: GcPcSaftOptions,
     contributions: Vec<Box<dyn FunctionalContribution>>,
+    permittivity: Permittivity,
 }
 
 impl GcPcSaftFunctional {
-    pub fn new(parameters: Rc<GcPcSaftFunctionalParameters>) -> DFT<Self> {
+    pub fn new(parameters: Arc<GcPcSaftFunctionalParameters>) -> DFT<Self> {
         Self::with_options(
             parameters,
             FMTVersion::WhiteBear,
@@ -37,14 +43,33 @@ impl GcPcSaftFunctional {
     }
 
     pub fn with_options(
-        parameters: Rc<GcPcSaftFunctionalParameters>,
+        parameters: Arc<GcPcSaftFunctionalParameters>,
         fmt_version: FMTVersion,
         saft_options: GcPcSaftOptions,
     ) -> DFT<Self> {
-        let mut contributions: Vec<Box<dyn FunctionalContribution>> = Vec::with_capacity(4);
+        Self::with_permittivity(
+            parameters,
+            fmt_version,
+            saft_options,
+            Permittivity::default(),
+        )
+        .unwrap()
+    }
+
+    /// Create the functional with the given model for the relative
+    /// permittivity in the electrostatic contributions.
+    pub fn with_permittivity(
+        parameters: Arc<GcPcSaftFunctionalParameters>,
+        fmt_version: FMTVersion,
+        saft_options: GcPcSaftOptions,
+        permittivity: Permittivity,
+    ) -> EosResult<DFT<Self>> {
+        permittivity.check_components(parameters.m_mix.len())?;
+
+        let mut contributions: Vec<Box<dyn FunctionalContribution>> = Vec::with_capacity(9);
 
         // Hard sphere contribution
-        let hs = FMTContribution::new(&parameters, fmt_version);
+        let hs = FMTContribution::new(&Rc::new(FMTParameters(parameters.clone())), fmt_version);
         contributions.push(Box::new(hs));
 
         // Hard chains
@@ -65,27 +90,62 @@ impl GcPcSaftFunctional {
             contributions.push(Box::new(assoc));
         }
 
-        (Self {
+        // Dipoles
+        if !parameters.dipole_comp.is_empty() {
+            let dipole = DipoleFunctional::new(&parameters);
+            contributions.push(Box::new(dipole));
+        }
+
+        // Quadrupoles
+        if !parameters.quadrupole_comp.is_empty() {
+            let quadrupole = QuadrupoleFunctional::new(&parameters);
+            contributions.push(Box::new(quadrupole));
+        }
+
+        // Dipole-quadrupole cross interactions
+        if !parameters.dipole_comp.is_empty() && !parameters.quadrupole_comp.is_empty() {
+            let dipole_quadrupole = DipoleQuadrupoleFunctional::new(&parameters);
+            contributions.push(Box::new(dipole_quadrupole));
+        }
+
+        // Charged segments
+        if !parameters.ion_segment.is_empty() {
+            let debye_hueckel = DebyeHueckelFunctional::new(&parameters, &permittivity);
+            contributions.push(Box::new(debye_hueckel));
+            if saft_options.born {
+                let born = BornFunctional::new(&parameters, &permittivity);
+                contributions.push(Box::new(born));
+            }
+            if let Some(cutoff) = saft_options.coulomb_cutoff {
+                let coulomb = CoulombFunctional::new(&parameters, &permittivity, cutoff);
+                contributions.push(Box::new(coulomb));
+            }
+        }
+
+        Ok((Self {
             parameters,
             fmt_version,
             options: saft_options,
             contributions,
+            permittivity,
         })
-        .into()
+        .into())
     }
 }
 
 impl HelmholtzEnergyFunctional for GcPcSaftFunctional {
-    fn molecule_shape(&self) -> MoleculeShape {
+    fn molecule_shape(&self) -> MoleculeShape<'_> {
         MoleculeShape::Heterosegmented(&self.parameters.component_index)
     }
 
     fn subset(&self, component_list: &[usize]) -> DFT<Self> {
-        Self::with_options(
-            Rc::new(self.parameters.subset(component_list)),
+        Self::with_permittivity(
+            Arc::new(self.parameters.subset(component_list)),
             self.fmt_version,
             self.options,
+            self.permittivity.subset(component_list),
         )
+        .unwrap()
     }
 
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
@@ -115,6 +175,16 @@ impl HelmholtzEnergyFunctional for GcPcSaftFunctional {
     }
 }
 
+// `GcPcSaftFunctional` itself is neither `Send` nor `Sync`, because `feos-dft`
+// stores the contributions as trait objects without these bounds. Threads
+// share the parameters and models instead and construct their own functional.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<GcPcSaftFunctionalParameters>();
+    assert_send_sync::<GcPcSaftOptions>();
+    assert_send_sync::<Permittivity>();
+};
+
 impl MolarWeight<SIUnit> for GcPcSaftFunctional {
     fn molar_weight(&self) -> SIArray1 {
         self.parameters.molarweight.clone() * GRAM / MOL
@@ -135,6 +205,51 @@ impl FMTProperties for GcPcSaftFunctionalParameters {
     }
 }
 
+/// Shared parameter set used in the FMT contribution, which expects
+/// its properties in an `Rc`.
+struct FMTParameters(Arc<GcPcSaftFunctionalParameters>);
+
+impl FMTProperties for FMTParameters {
+    fn component_index(&self) -> Array1<usize> {
+        self.0.component_index()
+    }
+
+    fn chain_length(&self) -> Array1<f64> {
+        self.0.chain_length()
+    }
+
+    fn hs_diameter<D: DualNum<f64>>(&self, temperature: D) -> Array1<D> {
+        self.0.hs_diameter(temperature)
+    }
+}
+
+#[cfg(feature = "micelles")]
+impl crate::micelles::Electrostatics for GcPcSaftFunctional {
+    fn segment_charges(&self) -> Array1<f64> {
+        let p = &self.parameters;
+        let mut z = Array1::zeros(p.m.len());
+        for (&s, &z_s) in p.ion_segment.iter().zip(p.z.iter()) {
+            z[s] = z_s;
+        }
+        z
+    }
+
+    fn mean_field_coulomb(&self) -> bool {
+        self.options.coulomb_cutoff.is_some() && !self.parameters.ion_segment.is_empty()
+    }
+
+    fn relative_permittivity(&self, partial_density: &Array1<f64>) -> f64 {
+        let p = &self.parameters;
+        let weights = crate::eos::ionic::PermittivityWeights::new(
+            &p.component_index,
+            &p.ion_segment,
+            partial_density.len(),
+        );
+        self.permittivity
+            .relative_permittivity(&weights, &p.molarweight, partial_density)
+    }
+}
+
 impl FluidParameters for GcPcSaftFunctional {
     fn epsilon_k_ff(&self) -> Array1<f64> {
         self.parameters.epsilon_k.clone()
@@ -1,4 +1,6 @@
-use crate::record::GcPcSaftRecord;
+use crate::eos::parameter::{association_markdown, association_parameters};
+use crate::record::{GcPcSaftBinaryRecord, GcPcSaftRecord};
+use crate::second_order::{self, SecondOrderRecord};
 use feos_core::joback::JobackRecord;
 use feos_core::parameter::{
     BinaryRecord, ChemicalRecord, IdentifierOption, ParameterError, SegmentRecord,
@@ -8,6 +10,7 @@ use ndarray::{Array1, Array2};
 use num_dual::DualNum;
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::{Graph, UnGraph};
+use quantity::si::{JOULE, KB, KELVIN};
 use std::fmt::Write;
 use std::fs::File;
 use std::io::BufReader;
@@ -26,26 +29,58 @@ pub struct GcPcSaftFunctionalParameters {
     pub epsilon_k: Array1<f64>,
     pub bonds: UnGraph<(), ()>,
     pub assoc_segment: Array1<usize>,
-    kappa_ab: Array1<f64>,
-    epsilon_k_ab: Array1<f64>,
-    pub na: Array1<f64>,
-    pub nb: Array1<f64>,
+    pub site_types: Vec<String>,
+    pub n_sites: Array1<f64>,
     pub psi_dft: Array1<f64>,
+    pub dipole_comp: Array1<usize>,
+    mu: Array1<f64>,
+    pub mu2: Array1<f64>,
+    pub quadrupole_comp: Array1<usize>,
+    q: Array1<f64>,
+    pub q2: Array1<f64>,
+    pub ion_segment: Array1<usize>,
+    pub z: Array1<f64>,
+    pub m_mix: Array1<f64>,
+    pub sigma_mix: Array1<f64>,
+    pub s_ij: Array2<f64>,
+    pub e_k_ij: Array2<f64>,
     pub k_ij: Array2<f64>,
+    pub k_ij_t: Array2<f64>,
+    pub k_ij_ln_t: Array2<f64>,
+    pub l_ij: Array2<f64>,
     pub sigma_ij: Array2<f64>,
-    pub epsilon_k_ij: Array2<f64>,
     pub sigma3_kappa_aibj: Array2<f64>,
     pub epsilon_k_aibj: Array2<f64>,
     chemical_records: Vec<ChemicalRecord>,
     segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
-    binary_segment_records: Option<Vec<BinaryRecord<String, f64>>>,
+    binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+    second_order_records: Vec<SecondOrderRecord>,
 }
 
 impl GcPcSaftFunctionalParameters {
     pub fn from_segments(
         chemical_records: Vec<ChemicalRecord>,
         segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
-        binary_segment_records: Option<Vec<BinaryRecord<String, f64>>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+    ) -> Result<Self, ParameterError> {
+        Self::from_segments_with_second_order(
+            chemical_records,
+            segment_records,
+            binary_segment_records,
+            vec![],
+        )
+    }
+
+    /// Creates parameters from segments with second-order group corrections.
+    ///
+    /// Second-order groups are detected in the bond graphs of the chemical
+    /// records and correct the parameters of the first segment of every
+    /// occurrence.
+    pub fn from_segments_with_second_order(
+        chemical_records: Vec<ChemicalRecord>,
+        segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+        second_order_records: Vec<SecondOrderRecord>,
     ) -> Result<Self, ParameterError> {
         let segment_map: IndexMap<_, _> = segment_records
             .iter()
@@ -60,12 +95,23 @@ impl GcPcSaftFunctionalParameters {
         let mut epsilon_k = Vec::new();
         let mut bonds = Graph::default();
         let mut assoc_segment = Vec::new();
-        let mut kappa_ab = Vec::new();
-        let mut epsilon_k_ab = Vec::new();
-        let mut na = Vec::new();
-        let mut nb = Vec::new();
+        let mut site_types = Vec::new();
+        let mut n_sites = Vec::new();
+        let mut site_records = Vec::new();
         let mut psi_dft = Vec::new();
 
+        let mut dipole_comp = Vec::new();
+        let mut mu = Vec::new();
+        let mut mu2 = Vec::new();
+        let mut quadrupole_comp = Vec::new();
+        let mut q = Vec::new();
+        let mut q2 = Vec::new();
+        let mut ion_segment = Vec::new();
+        let mut z = Vec::new();
+        let mut m_mix = Vec::new();
+        let mut sigma_mix = Vec::new();
+        let mut epsilon_k_mix = Vec::new();
+
         let mut segment_index = 0;
         for (i, chemical_record) in chemical_records.iter().enumerate() {
             let (segment_list, bond_list) = chemical_record.segment_and_bond_list()?;
@@ -78,10 +124,21 @@ impl GcPcSaftFunctionalParameters {
                 )
             }));
 
-            for id in segment_list {
+            let mut m_i = 0.0;
+            let mut sigma_i = 0.0;
+            let mut epsilon_k_i = 0.0;
+            let mut mu2_i = 0.0;
+            let mut q2_i = 0.0;
+            let mut z_i = 0.0;
+
+            let corrections =
+                second_order::segment_corrections(segment_list, bond_list, &second_order_records);
+            for (id, corrections) in segment_list.iter().zip(corrections) {
                 let segment = segment_map
                     .get(id)
                     .ok_or_else(|| ParameterError::ComponentsNotFound(id.to_string()))?;
+                let segment =
+                    &second_order::corrected_segment(segment, &corrections, &second_order_records);
                 molarweight[i] += segment.molarweight;
                 component_index.push(i);
                 identifiers.push(id.clone());
@@ -89,25 +146,67 @@ impl GcPcSaftFunctionalParameters {
                 sigma.push(segment.model_record.sigma);
                 epsilon_k.push(segment.model_record.epsilon_k);
 
-                if let (Some(k), Some(e)) = (
-                    segment.model_record.kappa_ab,
-                    segment.model_record.epsilon_k_ab,
-                ) {
-                    assoc_segment.push(segment_index);
-                    kappa_ab.push(k);
-                    epsilon_k_ab.push(e);
-                    na.push(segment.model_record.na.unwrap_or(1.0));
-                    nb.push(segment.model_record.nb.unwrap_or(1.0));
+                if let Some(record) = segment.model_record.association_record() {
+                    for site in &record.sites {
+                        assoc_segment.push(segment_index);
+                        site_types.push(site.id.clone());
+                        n_sites.push(site.n);
+                        site_records.push(record.clone());
+                    }
+                }
+
+                if let Some(z_s) = segment.model_record.z.filter(|&z| z != 0.0) {
+                    ion_segment.push(segment_index);
+                    z.push(z_s);
+                    z_i += z_s;
                 }
 
                 psi_dft.push(segment.model_record.psi_dft.unwrap_or(PSI_GC_DFT));
 
+                m_i += segment.model_record.m;
+                sigma_i += segment.model_record.m * segment.model_record.sigma.powi(3);
+                epsilon_k_i += segment.model_record.m * segment.model_record.epsilon_k;
+                if let Some(mu) = segment.model_record.mu {
+                    mu2_i += mu.powi(2);
+                }
+                if let Some(q) = segment.model_record.q {
+                    q2_i += q.powi(2);
+                }
+
                 segment_index += 1;
             }
+
+            if z_i.abs() > 1e-10 {
+                let identifier = chemical_record.identifier();
+                return Err(ParameterError::IncompatibleParameters(format!(
+                    "component {} is not electroneutral (net charge {})",
+                    identifier.name.as_ref().unwrap_or(&identifier.cas),
+                    z_i
+                )));
+            }
+
+            m_mix.push(m_i);
+            sigma_mix.push((sigma_i / m_i).cbrt());
+            epsilon_k_mix.push(epsilon_k_i / m_i);
+
+            if mu2_i > 0.0 {
+                dipole_comp.push(i);
+                mu.push(mu2_i.sqrt());
+                mu2.push(mu2_i / m_i * (1e-19 * (JOULE / KELVIN / KB).into_value().unwrap()));
+            }
+
+            if q2_i > 0.0 {
+                quadrupole_comp.push(i);
+                q.push(q2_i.sqrt());
+                q2.push(q2_i / m_i * (1e-19 * (JOULE / KELVIN / KB).into_value().unwrap()));
+            }
         }
 
         // Binary interaction parameter
         let mut k_ij = Array2::zeros([epsilon_k.len(); 2]);
+        let mut k_ij_t = Array2::zeros([epsilon_k.len(); 2]);
+        let mut k_ij_ln_t = Array2::zeros([epsilon_k.len(); 2]);
+        let mut l_ij = Array2::zeros([epsilon_k.len(); 2]);
         if let Some(binary_segment_records) = binary_segment_records.as_ref() {
             let mut binary_segment_records_map = IndexMap::new();
             for binary_record in binary_segment_records {
@@ -123,8 +222,11 @@ impl GcPcSaftFunctionalParameters {
             for (i, id1) in identifiers.iter().enumerate() {
                 for (j, id2) in identifiers.iter().cloned().enumerate() {
                     if component_index[i] != component_index[j] {
-                        if let Some(k) = binary_segment_records_map.get(&(id1.clone(), id2)) {
-                            k_ij[(i, j)] = *k;
+                        if let Some(b) = binary_segment_records_map.get(&(id1.clone(), id2)) {
+                            k_ij[(i, j)] = b.k_ij;
+                            k_ij_t[(i, j)] = b.k_ij_t;
+                            k_ij_ln_t[(i, j)] = b.k_ij_ln_t;
+                            l_ij[(i, j)] = b.l_ij;
                         }
                     }
                 }
@@ -132,21 +234,28 @@ impl GcPcSaftFunctionalParameters {
         }
 
         // Combining rules dispersion
-        let sigma_ij =
-            Array2::from_shape_fn([sigma.len(); 2], |(i, j)| 0.5 * (sigma[i] + sigma[j]));
-        let epsilon_k_ij = Array2::from_shape_fn([epsilon_k.len(); 2], |(i, j)| {
-            (epsilon_k[i] * epsilon_k[j]).sqrt() * (1.0 - k_ij[(i, j)])
+        let sigma_ij = Array2::from_shape_fn([sigma.len(); 2], |(i, j)| {
+            0.5 * (sigma[i] + sigma[j]) * (1.0 - l_ij[(i, j)])
         });
 
-        // Association
-        let sigma3_kappa_aibj = Array2::from_shape_fn([kappa_ab.len(); 2], |(i, j)| {
-            (sigma[assoc_segment[i]] * sigma[assoc_segment[j]]).powf(1.5)
-                * (kappa_ab[i] * kappa_ab[j]).sqrt()
+        // Combining rules polar
+        let s_ij = Array2::from_shape_fn([m_mix.len(); 2], |(i, j)| {
+            0.5 * (sigma_mix[i] + sigma_mix[j])
         });
-        let epsilon_k_aibj = Array2::from_shape_fn([epsilon_k_ab.len(); 2], |(i, j)| {
-            0.5 * (epsilon_k_ab[i] + epsilon_k_ab[j])
+        let e_k_ij = Array2::from_shape_fn([m_mix.len(); 2], |(i, j)| {
+            (epsilon_k_mix[i] * epsilon_k_mix[j]).sqrt()
         });
 
+        // Association
+        let (sigma3_kappa_aibj, epsilon_k_aibj) = association_parameters(
+            &assoc_segment,
+            &site_types,
+            &site_records,
+            &sigma,
+            &identifiers,
+            binary_segment_records.as_deref(),
+        );
+
         Ok(Self {
             molarweight,
             component_index: Array1::from_vec(component_index),
@@ -156,19 +265,32 @@ impl GcPcSaftFunctionalParameters {
             epsilon_k: Array1::from_vec(epsilon_k),
             bonds,
             assoc_segment: Array1::from_vec(assoc_segment),
-            kappa_ab: Array1::from_vec(kappa_ab),
-            epsilon_k_ab: Array1::from_vec(epsilon_k_ab),
-            na: Array1::from_vec(na),
-            nb: Array1::from_vec(nb),
+            site_types,
+            n_sites: Array1::from_vec(n_sites),
             psi_dft: Array1::from_vec(psi_dft),
+            dipole_comp: Array1::from_vec(dipole_comp),
+            mu: Array1::from_vec(mu),
+            mu2: Array1::from_vec(mu2),
+            quadrupole_comp: Array1::from_vec(quadrupole_comp),
+            q: Array1::from_vec(q),
+            q2: Array1::from_vec(q2),
+            ion_segment: Array1::from_vec(ion_segment),
+            z: Array1::from_vec(z),
+            m_mix: Array1::from_vec(m_mix),
+            sigma_mix: Array1::from_vec(sigma_mix),
+            s_ij,
+            e_k_ij,
             k_ij,
+            k_ij_t,
+            k_ij_ln_t,
+            l_ij,
             sigma_ij,
-            epsilon_k_ij,
             sigma3_kappa_aibj,
             epsilon_k_aibj,
             chemical_records,
             segment_records,
             binary_segment_records,
+            second_order_records,
         })
     }
 
@@ -223,8 +345,10 @@ impl GcPcSaftFunctionalParameters {
         let binary_records = file_binary
             .map(|file_binary| {
                 let reader = BufReader::new(File::open(file_binary)?);
-                let binary_records: Result<Vec<BinaryRecord<String, f64>>, ParameterError> =
-                    Ok(serde_json::from_reader(reader)?);
+                let binary_records: Result<
+                    Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>,
+                    ParameterError,
+                > = Ok(serde_json::from_reader(reader)?);
                 binary_records
             })
             .transpose()?;
@@ -237,10 +361,11 @@ impl GcPcSaftFunctionalParameters {
             .iter()
             .map(|&i| self.chemical_records[i].clone())
             .collect();
-        Self::from_segments(
+        Self::from_segments_with_second_order(
             chemical_records,
             self.segment_records.clone(),
             self.binary_segment_records.clone(),
+            self.second_order_records.clone(),
         )
         .unwrap()
     }
@@ -252,12 +377,23 @@ impl GcPcSaftFunctionalParameters {
         })
     }
 
+    /// Dispersion energy parameter of all segment pairs including the
+    /// (temperature dependent) binary interaction parameter.
+    pub fn epsilon_k_ij<D: DualNum<f64>>(&self, temperature: D) -> Array2<D> {
+        let ti = temperature.recip();
+        let ln_t = temperature.ln();
+        Array2::from_shape_fn(self.k_ij.raw_dim(), |(i, j)| {
+            let k_ij = ti * self.k_ij_t[(i, j)] + ln_t * self.k_ij_ln_t[(i, j)] + self.k_ij[(i, j)];
+            -(k_ij - 1.0) * (self.epsilon_k[i] * self.epsilon_k[j]).sqrt()
+        })
+    }
+
     pub fn to_markdown(&self) -> String {
         let mut output = String::new();
         let o = &mut output;
         write!(
             o,
-            "|component|molarweight|segment|$m$|$\\sigma$|$\\varepsilon$|$\\kappa_{{AB}}$|$\\varepsilon_{{AB}}$|$N_A$|$N_B$|\n|-|-|-|-|-|-|-|-|-|-|"
+            "|component|molarweight|segment|$m$|$\\sigma$|$\\varepsilon$|sites|$\\kappa_{{AB}}$|$\\varepsilon_{{AB}}$|\n|-|-|-|-|-|-|-|-|-|"
         )
         .unwrap();
         for i in 0..self.m.len() {
@@ -271,14 +407,15 @@ impl GcPcSaftFunctionalParameters {
                     self.molarweight[self.component_index[i]]
                 )
             };
-            let association = if let Some(a) = self.assoc_segment.iter().position(|&a| a == i) {
-                format!(
-                    "{}|{}|{}|{}",
-                    self.kappa_ab[a], self.epsilon_k_ab[a], self.na[a], self.nb[a]
-                )
-            } else {
-                "|||".to_string()
-            };
+            let association = association_markdown(
+                i,
+                self.assoc_segment.as_slice().unwrap(),
+                &self.site_types,
+                self.n_sites.as_slice().unwrap(),
+                self.sigma[i],
+                &self.sigma3_kappa_aibj,
+                &self.epsilon_k_aibj,
+            );
             write!(
                 o,
                 "\n|{}|{}|{}|{}|{}|{}|||",
@@ -314,10 +451,20 @@ impl std::fmt::Display for GcPcSaftFunctionalParameters {
         write!(f, "\n\tbonds={:?}", self.bonds)?;
         if !self.assoc_segment.is_empty() {
             write!(f, "\n\tassoc_segment={}", self.assoc_segment)?;
-            write!(f, "\n\tkappa_ab={}", self.kappa_ab)?;
-            write!(f, "\n\tepsilon_k_ab={}", self.epsilon_k_ab)?;
-            write!(f, "\n\tna={}", self.na)?;
-            write!(f, "\n\tnb={}", self.nb)?;
+            write!(f, "\n\tsite_types={:?}", self.site_types)?;
+            write!(f, "\n\tn_sites={}", self.n_sites)?;
+        }
+        if !self.dipole_comp.is_empty() {
+            write!(f, "\n\tdipole_comp={}", self.dipole_comp)?;
+            write!(f, "\n\tmu={}", self.mu)?;
+        }
+        if !self.quadrupole_comp.is_empty() {
+            write!(f, "\n\tquadrupole_comp={}", self.quadrupole_comp)?;
+            write!(f, "\n\tq={}", self.q)?;
+        }
+        if !self.ion_segment.is_empty() {
+            write!(f, "\n\tion_segment={}", self.ion_segment)?;
+            write!(f, "\n\tz={}", self.z)?;
         }
         write!(f, "\n)")
     }
@@ -0,0 +1,206 @@
+use super::parameter::GcPcSaftFunctionalParameters;
+use crate::eos::polar::{DipoleQuadrupoleTerms, DipoleTerms, QuadrupoleTerms};
+use feos_core::EosError;
+use feos_dft::{
+    FunctionalContributionDual, WeightFunction, WeightFunctionInfo, WeightFunctionShape,
+};
+use ndarray::*;
+use num_dual::DualNum;
+use std::f64::consts::FRAC_PI_6;
+use std::fmt;
+use std::sync::Arc;
+
+fn polar_weight_functions<N: DualNum<f64> + ScalarOperand>(
+    p: &GcPcSaftFunctionalParameters,
+    temperature: N,
+) -> WeightFunctionInfo<N> {
+    let d = p.hs_diameter(temperature);
+    WeightFunctionInfo::new(p.component_index.clone(), false).add(
+        WeightFunction::new_scaled(d * &p.psi_dft, WeightFunctionShape::Theta),
+        false,
+    )
+}
+
+/// Calculate the packing fraction and the weighted densities of all components
+/// (averaged over the segments of each component) from the weighted segment densities.
+fn polar_weighted_densities<N: DualNum<f64> + ScalarOperand>(
+    p: &GcPcSaftFunctionalParameters,
+    temperature: N,
+    density: ArrayView2<N>,
+) -> (Array1<N>, Array2<N>) {
+    let n = p.m.len();
+
+    // temperature dependent segment diameter
+    let d = p.hs_diameter(temperature);
+
+    // packing fraction
+    let eta = density
+        .outer_iter()
+        .zip(&d * &d * &d * &p.m * FRAC_PI_6)
+        .map(|(rho, d3m)| &rho * d3m)
+        .reduce(|a, b| a + b)
+        .unwrap();
+
+    // weighted component densities
+    let mut s_i: Array1<f64> = Array::zeros(p.component_index[n - 1] + 1);
+    for &c in p.component_index.iter() {
+        s_i[c] += 1.0;
+    }
+    let mut rho_comp: Array2<N> = Array::zeros((s_i.len(), eta.len()));
+    for (rho, &c) in density.outer_iter().zip(p.component_index.iter()) {
+        let mut rho_c = rho_comp.index_axis_mut(Axis(0), c);
+        rho_c += &(&rho / s_i[c]);
+    }
+
+    (eta, rho_comp)
+}
+
+#[derive(Clone)]
+pub struct DipoleFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    dipole: DipoleTerms,
+}
+
+impl DipoleFunctional {
+    pub fn new(parameters: &Arc<GcPcSaftFunctionalParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            dipole: DipoleTerms::new(
+                &parameters.dipole_comp,
+                &parameters.mu2,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for DipoleFunctional {
+    fn weight_functions(&self, temperature: N) -> WeightFunctionInfo<N> {
+        polar_weight_functions(&self.parameters, temperature)
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        density: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let (eta, rho) = polar_weighted_densities(&self.parameters, temperature, density);
+        Ok(rho
+            .axis_iter(Axis(1))
+            .zip(eta.iter())
+            .map(|(rho, &eta)| {
+                self.dipole
+                    .helmholtz_energy_density(temperature, &rho.to_owned(), eta)
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for DipoleFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dipole functional (GC)")
+    }
+}
+
+#[derive(Clone)]
+pub struct QuadrupoleFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    quadrupole: QuadrupoleTerms,
+}
+
+impl QuadrupoleFunctional {
+    pub fn new(parameters: &Arc<GcPcSaftFunctionalParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            quadrupole: QuadrupoleTerms::new(
+                &parameters.quadrupole_comp,
+                &parameters.q2,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for QuadrupoleFunctional {
+    fn weight_functions(&self, temperature: N) -> WeightFunctionInfo<N> {
+        polar_weight_functions(&self.parameters, temperature)
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        density: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let (eta, rho) = polar_weighted_densities(&self.parameters, temperature, density);
+        Ok(rho
+            .axis_iter(Axis(1))
+            .zip(eta.iter())
+            .map(|(rho, &eta)| {
+                self.quadrupole
+                    .helmholtz_energy_density(temperature, &rho.to_owned(), eta)
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for QuadrupoleFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Quadrupole functional (GC)")
+    }
+}
+
+#[derive(Clone)]
+pub struct DipoleQuadrupoleFunctional {
+    parameters: Arc<GcPcSaftFunctionalParameters>,
+    dipole_quadrupole: DipoleQuadrupoleTerms,
+}
+
+impl DipoleQuadrupoleFunctional {
+    pub fn new(parameters: &Arc<GcPcSaftFunctionalParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            dipole_quadrupole: DipoleQuadrupoleTerms::new(
+                &parameters.dipole_comp,
+                &parameters.mu2,
+                &parameters.quadrupole_comp,
+                &parameters.q2,
+                &parameters.sigma_mix,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<N: DualNum<f64> + ScalarOperand> FunctionalContributionDual<N> for DipoleQuadrupoleFunctional {
+    fn weight_functions(&self, temperature: N) -> WeightFunctionInfo<N> {
+        polar_weight_functions(&self.parameters, temperature)
+    }
+
+    fn calculate_helmholtz_energy_density(
+        &self,
+        temperature: N,
+        density: ArrayView2<N>,
+    ) -> Result<Array1<N>, EosError> {
+        let (eta, rho) = polar_weighted_densities(&self.parameters, temperature, density);
+        Ok(rho
+            .axis_iter(Axis(1))
+            .zip(eta.iter())
+            .map(|(rho, &eta)| {
+                self.dipole_quadrupole
+                    .helmholtz_energy_density(temperature, &rho.to_owned(), eta)
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for DipoleQuadrupoleFunctional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dipole-Quadrupole functional (GC)")
+    }
+}
@@ -5,16 +5,16 @@ use ndarray::*;
 use num_dual::linalg::{norm, LU};
 use num_dual::*;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Association {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
 }
 
 #[derive(Clone)]
 pub struct CrossAssociation {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
     pub max_iter: usize,
     pub tol: f64,
 }
@@ -39,6 +39,28 @@ fn association_strength<D: DualNum<f64>>(
         * (temperature.recip() * epsilon_k_aibj[(i, j)]).exp_m1()
 }
 
+/// Whether the association can be calculated analytically, i.e., all
+/// association sites belong to a single segment and are either of a single
+/// type that associates with itself or of two types that only associate
+/// with each other.
+pub(crate) fn single_segment_association(
+    assoc_segment: &Array1<usize>,
+    sigma3_kappa_aibj: &Array2<f64>,
+) -> bool {
+    if assoc_segment.iter().any(|&a| a != assoc_segment[0]) {
+        return false;
+    }
+    match assoc_segment.len() {
+        1 => sigma3_kappa_aibj[(0, 0)] > 0.0,
+        2 => {
+            sigma3_kappa_aibj[(0, 0)] == 0.0
+                && sigma3_kappa_aibj[(1, 1)] == 0.0
+                && sigma3_kappa_aibj[(0, 1)] > 0.0
+        }
+        _ => false,
+    }
+}
+
 impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Association {
     fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
         let p = &self.parameters;
@@ -53,6 +75,7 @@ impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Association {
         let n3i = (-n3 + 1.0).recip();
 
         // association strength
+        let b = p.n_sites.len() - 1;
         let deltarho = association_strength(
             &p.assoc_segment,
             &p.sigma3_kappa_aibj,
@@ -63,24 +86,23 @@ impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Association {
             n3i,
             D::one(),
             0,
-            0,
+            b,
         ) * state.partial_density[c];
 
-        let na = p.na[0];
-        let nb = p.nb[0];
-        if nb > 0.0 {
-            // no cross association, two association sites
+        let na = p.n_sites[0];
+        let f = |x: D| x.ln() - x * 0.5 + 0.5;
+        let phi = if b == 0 {
+            // a single site type that associates with itself
+            let xa = assoc_site_frac_a(deltarho, na);
+            f(xa) * na
+        } else {
+            // two site types that associate with each other
+            let nb = p.n_sites[1];
             let xa = assoc_site_frac_ab(deltarho, na, nb);
             let xb = (xa - 1.0) * (na / nb) + 1.0;
-            state.moles[c]
-                * p.n[0]
-                * ((xa.ln() - xa * 0.5 + 0.5) * na + (xb.ln() - xb * 0.5 + 0.5) * nb)
-        } else {
-            // no cross association, one association site
-            let xa = assoc_site_frac_a(deltarho, na);
-
-            state.moles[c] * p.n[0] * (xa.ln() - xa * 0.5 + 0.5) * na
-        }
+            f(xa) * na + f(xb) * nb
+        };
+        state.moles[c] * p.n[0] * phi
     }
 }
 
@@ -102,7 +124,7 @@ pub(crate) fn assoc_site_frac_ab<D: DualNum<f64>>(deltarho: D, na: f64, nb: f64)
 
 pub(crate) fn assoc_site_frac_a<D: DualNum<f64>>(deltarho: D, na: f64) -> D {
     if deltarho.re() > f64::EPSILON.sqrt() {
-        ((deltarho * na * 4.0 + 1.0).powi(2) - 1.0).sqrt() / (deltarho * na * 2.0)
+        ((deltarho * na * 4.0 + 1.0).sqrt() - 1.0) / (deltarho * na * 2.0)
     } else {
         D::one() + deltarho * na * (deltarho * na * 2.0 - 1.0)
     }
@@ -130,8 +152,7 @@ impl<D: DualNum<f64> + ScalarOperand> HelmholtzEnergyDual<D> for CrossAssociatio
             &p.assoc_segment,
             &p.sigma3_kappa_aibj,
             &p.epsilon_k_aibj,
-            &p.na,
-            &p.nb,
+            &p.n_sites,
             state.temperature,
             &rho_assoc,
             &diameter,
@@ -142,7 +163,7 @@ impl<D: DualNum<f64> + ScalarOperand> HelmholtzEnergyDual<D> for CrossAssociatio
             self.tol,
             None,
         )
-        .unwrap_or_else(|_| D::from(std::f64::NAN))
+        .unwrap_or_else(|_| D::from(f64::NAN))
             * state.volume
     }
 }
@@ -154,12 +175,10 @@ impl fmt::Display for CrossAssociation {
 }
 
 pub fn helmholtz_energy_density_cross_association<S, D: DualNum<f64> + ScalarOperand>(
-    // p: &GcPcSaftParameters<B>,
     assoc_segment: &Array1<usize>,
     sigma3_kappa_aibj: &Array2<f64>,
     epsilon_k_aibj: &Array2<f64>,
-    na: &Array1<f64>,
-    nb: &Array1<f64>,
+    n_sites: &Array1<f64>,
     temperature: D,
     density: &ArrayBase<S, Ix1>,
     diameter: &Array1<D>,
@@ -181,11 +200,11 @@ where
         return Ok(D::zero());
     }
 
-    // number of associating segments
-    let nassoc = assoc_segment.len();
+    // number of association site types
+    let nsites = assoc_segment.len();
 
     // association strength
-    let delta = Array::from_shape_fn([nassoc; 2], |(i, j)| {
+    let delta = Array::from_shape_fn([nsites; 2], |(i, j)| {
         association_strength(
             assoc_segment,
             sigma3_kappa_aibj,
@@ -204,30 +223,42 @@ where
     // initialize monomer fraction
     let mut x = match &x0 {
         Some(x0) => (*x0).clone(),
-        None => Array::from_elem(2 * nassoc, 0.2),
+        None => Array::from_elem(nsites, 0.2),
     };
 
-    for k in 0..max_iter {
-        if newton_step_cross_association::<_, f64>(
-            &mut x,
-            nassoc,
-            &delta.map(D::re),
-            na,
-            nb,
-            &density.map(D::re),
-            tol,
-        )? {
+    // solve for the monomer fractions using only the real parts
+    let delta_re = delta.map(D::re);
+    let density_re = density.map(D::re);
+    let mut converged = false;
+    for _ in 0..max_iter {
+        if newton_step_cross_association(&mut x, &delta_re, n_sites, &density_re, tol)? {
+            converged = true;
             break;
         }
-        if k == max_iter - 1 {
-            return Err(EosError::NotConverged("Cross association".into()));
-        }
+    }
+    if !converged {
+        return Err(EosError::NotConverged("Cross association".into()));
     }
 
-    // calculate derivatives
+    // calculate derivatives using the implicit function theorem: with the
+    // (real) Jacobian at the solution, every Newton step on the dual numbers
+    // increases the order of correct derivatives by one
+    let h_inv = LU::new(hessian_cross_association(
+        &x,
+        &delta_re,
+        n_sites,
+        &density_re,
+    ))?
+    .inverse();
     let mut x_dual = x.mapv(D::from);
     for _ in 0..D::NDERIV {
-        newton_step_cross_association(&mut x_dual, nassoc, &delta, na, nb, density, tol)?;
+        let g = gradient_cross_association(&x_dual, &delta, n_sites, density);
+        let dx = Array1::from_shape_fn(x_dual.len(), |i| {
+            g.iter()
+                .zip(h_inv.index_axis(Axis(0), i))
+                .fold(D::zero(), |acc, (&g, &h)| acc + g * h)
+        });
+        x_dual -= &dx;
     }
 
     // save monomer fraction
@@ -236,59 +267,56 @@ where
     }
 
     // Helmholtz energy density
-    let xa = x_dual.slice(s![..nassoc]);
-    let xb = x_dual.slice(s![nassoc..]);
     let f = |x: D| x.ln() - x * 0.5 + 0.5;
-    Ok((density * (xa.mapv(f) * na + xb.mapv(f) * nb)).sum())
+    Ok((density * x_dual.mapv(f) * n_sites).sum())
 }
 
-fn newton_step_cross_association<S, D: DualNum<f64> + ScalarOperand>(
-    x: &mut Array1<D>,
-    nassoc: usize,
-    delta: &Array2<D>,
-    na: &Array1<f64>,
-    nb: &Array1<f64>,
-    rho: &ArrayBase<S, Ix1>,
+fn newton_step_cross_association(
+    x: &mut Array1<f64>,
+    delta: &Array2<f64>,
+    n_sites: &Array1<f64>,
+    rho: &Array1<f64>,
     tol: f64,
-) -> Result<bool, EosError>
-where
-    S: Data<Elem = D>,
-{
-    // gradient
-    let mut g: Array1<D> = Array::zeros(2 * nassoc);
-    // Hessian
-    let mut h: Array2<D> = Array::zeros((2 * nassoc, 2 * nassoc));
-
-    // slice arrays
-    let (xa, xb) = x.multi_slice_mut((s![..nassoc], s![nassoc..]));
-    let (mut ga, mut gb) = g.multi_slice_mut((s![..nassoc], s![nassoc..]));
-    let (mut haa, mut hab, mut hba, mut hbb) = h.multi_slice_mut((
-        s![..nassoc, ..nassoc],
-        s![..nassoc, nassoc..],
-        s![nassoc.., ..nassoc],
-        s![nassoc.., nassoc..],
-    ));
-
-    // calculate gradients and approximate Hessian
-    for i in 0..nassoc {
-        let d = &delta.index_axis(Axis(0), i) * rho;
-
-        let dnx = (&xb * nb * &d).sum() + 1.0;
-        ga[i] = xa[i].recip() - dnx;
-        hab.index_axis_mut(Axis(0), i).assign(&(&d * &(-nb)));
-        haa[(i, i)] = -dnx / xa[i];
-
-        let dnx = (&xa * na * &d).sum() + 1.0;
-        gb[i] = xb[i].recip() - dnx;
-        hba.index_axis_mut(Axis(0), i).assign(&(&d * &(-na)));
-        hbb[(i, i)] = -dnx / xb[i];
-    }
+) -> Result<bool, EosError> {
+    let g = gradient_cross_association(x, delta, n_sites, rho);
+    let h = hessian_cross_association(x, delta, n_sites, rho);
 
     // Newton step
     x.assign(&(&*x - &LU::new(h)?.solve(&g)));
 
     // check convergence
-    Ok(norm(&g.map(D::re)) < tol)
+    Ok(norm(&g) < tol)
+}
+
+fn gradient_cross_association<S, D: DualNum<f64> + ScalarOperand>(
+    x: &Array1<D>,
+    delta: &Array2<D>,
+    n_sites: &Array1<f64>,
+    rho: &ArrayBase<S, Ix1>,
+) -> Array1<D>
+where
+    S: Data<Elem = D>,
+{
+    let rho_x = x * rho * n_sites;
+    Array1::from_shape_fn(x.len(), |i| {
+        x[i].recip() - ((&delta.index_axis(Axis(0), i) * &rho_x).sum() + 1.0)
+    })
+}
+
+fn hessian_cross_association(
+    x: &Array1<f64>,
+    delta: &Array2<f64>,
+    n_sites: &Array1<f64>,
+    rho: &Array1<f64>,
+) -> Array2<f64> {
+    // approximate Hessian according to Michelsen2006
+    let rho_n = rho * n_sites;
+    let mut h = -delta * &rho_n;
+    let rho_x = x * &rho_n;
+    for i in 0..x.len() {
+        h[(i, i)] -= ((&delta.index_axis(Axis(0), i) * &rho_x).sum() + 1.0) / x[i];
+    }
+    h
 }
 
 #[cfg(test)]
@@ -298,14 +326,14 @@ mod test {
     use approx::assert_relative_eq;
     use feos_core::EosUnit;
     use ndarray::arr1;
-    use num_dual::Dual64;
+    use num_dual::{Dual3_64, Dual64, HyperDual64};
     use quantity::si::{METER, MOL, PASCAL};
 
     #[test]
     fn test_assoc_propanol() {
         let parameters = propanol();
         let contrib = Association {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -327,7 +355,7 @@ mod test {
     fn test_cross_assoc_propanol() {
         let parameters = propanol();
         let contrib = CrossAssociation {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
             max_iter: 50,
             tol: 1e-10,
         };
@@ -351,7 +379,7 @@ mod test {
     fn test_cross_assoc_ethanol_propanol() {
         let parameters = ethanol_propanol(false);
         let contrib = CrossAssociation {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
             max_iter: 50,
             tol: 1e-10,
         };
@@ -372,4 +400,198 @@ mod test {
             -contrib.helmholtz_energy(&state).eps[0] * temperature * EosUnit::reference_pressure();
         assert_relative_eq!(pressure, -26.105606376765632 * PASCAL, max_relative = 1e-10);
     }
+
+    #[test]
+    fn test_induced_assoc_ethanol_acetone() {
+        let temperature = 300.0;
+        let volume = METER
+            .powi(3)
+            .to_reduced(EosUnit::reference_volume())
+            .unwrap();
+        let moles = (arr1(&[1.5, 2.5]) * MOL)
+            .to_reduced(EosUnit::reference_moles())
+            .unwrap();
+        let state = StateHD::new(temperature, volume, moles.clone());
+
+        let assoc = Association {
+            parameters: Arc::new(ethanol_acetone(false)),
+        };
+        let induced = CrossAssociation {
+            parameters: Arc::new(ethanol_acetone(true)),
+            max_iter: 50,
+            tol: 1e-10,
+        };
+        let a_assoc = assoc.helmholtz_energy(&state);
+        let a_induced = induced.helmholtz_energy(&state);
+        assert!(a_induced < a_assoc);
+
+        // without a self-associating segment, there is no association
+        let acetone = CrossAssociation {
+            parameters: Arc::new(ethanol_acetone(true).subset(&[1])),
+            max_iter: 50,
+            tol: 1e-10,
+        };
+        let state = StateHD::new(temperature, volume, arr1(&[moles[1]]));
+        assert_eq!(acetone.helmholtz_energy(&state), 0.0);
+    }
+
+    #[test]
+    fn test_assoc_propanoic_acid() {
+        let temperature = 300.0;
+        let volume = METER
+            .powi(3)
+            .to_reduced(EosUnit::reference_volume())
+            .unwrap();
+        let moles = (1.5 * MOL).to_reduced(EosUnit::reference_moles()).unwrap();
+        let state = StateHD::new(
+            Dual64::from_re(temperature),
+            Dual64::from_re(volume).derive(),
+            arr1(&[Dual64::from_re(moles)]),
+        );
+        let assoc = Association {
+            parameters: Arc::new(propanoic_acid()),
+        };
+        let cross_assoc = CrossAssociation {
+            parameters: Arc::new(propanoic_acid()),
+            max_iter: 50,
+            tol: 1e-10,
+        };
+        let a = assoc.helmholtz_energy(&state);
+        let a_cross = cross_assoc.helmholtz_energy(&state);
+        assert!(a.re < 0.0);
+        assert_relative_eq!(a.re, a_cross.re, max_relative = 1e-10);
+        assert_relative_eq!(a.eps[0], a_cross.eps[0], max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_assoc_single_site_type() {
+        let temperature = 300.0;
+        let volume = METER
+            .powi(3)
+            .to_reduced(EosUnit::reference_volume())
+            .unwrap();
+        let moles = (1500.0 * MOL)
+            .to_reduced(EosUnit::reference_moles())
+            .unwrap();
+        let state = StateHD::new(
+            Dual64::from_re(temperature),
+            Dual64::from_re(volume).derive(),
+            arr1(&[Dual64::from_re(moles)]),
+        );
+        for (na, nb) in [(1.0, 0.0), (0.0, 1.0)] {
+            let parameters = Arc::new(propanol_single_site_type(na, nb));
+            assert!(single_segment_association(
+                &parameters.assoc_segment,
+                &parameters.sigma3_kappa_aibj
+            ));
+            let assoc = Association {
+                parameters: parameters.clone(),
+            };
+            let cross_assoc = CrossAssociation {
+                parameters: parameters.clone(),
+                max_iter: 50,
+                tol: 1e-10,
+            };
+            let a = assoc.helmholtz_energy(&state);
+            let a_cross = cross_assoc.helmholtz_energy(&state);
+            assert!(a.re < 0.0);
+            assert_relative_eq!(a.re, a_cross.re, max_relative = 1e-10);
+            assert_relative_eq!(a.eps[0], a_cross.eps[0], max_relative = 1e-10);
+
+            // the fraction of non-bonded sites solves X = 1 / (1 + rho Delta X)
+            let p = &parameters;
+            let diameter = p.hs_diameter(temperature);
+            let density = moles / volume;
+            let n2 = zeta(p, &diameter, &arr1(&[density]), [2])[0] * 6.0;
+            let n3 = zeta(p, &diameter, &arr1(&[density]), [3])[0];
+            let deltarho = association_strength(
+                &p.assoc_segment,
+                &p.sigma3_kappa_aibj,
+                &p.epsilon_k_aibj,
+                temperature,
+                &diameter,
+                n2,
+                1.0 / (1.0 - n3),
+                1.0,
+                0,
+                0,
+            ) * density;
+            let x = assoc_site_frac_a(deltarho, 1.0);
+            assert_relative_eq!(x * (1.0 + deltarho * x), 1.0, max_relative = 1e-12);
+            assert_relative_eq!(a.re, moles * (x.ln() - 0.5 * x + 0.5), max_relative = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_cross_assoc_derivatives() {
+        let assoc = Association {
+            parameters: Arc::new(propanol()),
+        };
+        let cross_assoc = CrossAssociation {
+            parameters: Arc::new(propanol()),
+            max_iter: 50,
+            tol: 1e-10,
+        };
+        let temperature = 300.0;
+        let volume = METER
+            .powi(3)
+            .to_reduced(EosUnit::reference_volume())
+            .unwrap();
+        let moles = (1500.0 * MOL)
+            .to_reduced(EosUnit::reference_moles())
+            .unwrap();
+
+        // second derivatives with respect to temperature and volume
+        let state = StateHD::new(
+            HyperDual64::from_re(temperature).derive1(),
+            HyperDual64::from_re(volume).derive2(),
+            arr1(&[HyperDual64::from_re(moles)]),
+        );
+        let a = assoc.helmholtz_energy(&state);
+        let a_cross = cross_assoc.helmholtz_energy(&state);
+        assert_relative_eq!(a.eps1[0], a_cross.eps1[0], max_relative = 1e-8);
+        assert_relative_eq!(a.eps2[0], a_cross.eps2[0], max_relative = 1e-8);
+        assert_relative_eq!(
+            a.eps1eps2[(0, 0)],
+            a_cross.eps1eps2[(0, 0)],
+            max_relative = 1e-8
+        );
+
+        // third derivative with respect to volume
+        let state = StateHD::new(
+            Dual3_64::from_re(temperature),
+            Dual3_64::from_re(volume).derive(),
+            arr1(&[Dual3_64::from_re(moles)]),
+        );
+        let a = assoc.helmholtz_energy(&state);
+        let a_cross = cross_assoc.helmholtz_energy(&state);
+        assert_relative_eq!(a.v1, a_cross.v1, max_relative = 1e-8);
+        assert_relative_eq!(a.v2, a_cross.v2, max_relative = 1e-8);
+        assert_relative_eq!(a.v3, a_cross.v3, max_relative = 1e-8);
+    }
+
+    #[test]
+    fn test_cross_assoc_not_converged() {
+        let p = ethanol_propanol(false);
+        let temperature = 300.0;
+        let density = arr1(&[0.005, 0.005]);
+        let diameter = p.hs_diameter(temperature);
+        let rho_assoc = p.assoc_segment.mapv(|a| density[p.component_index[a]]) * &p.n;
+        let result = helmholtz_energy_density_cross_association(
+            &p.assoc_segment,
+            &p.sigma3_kappa_aibj,
+            &p.epsilon_k_aibj,
+            &p.n_sites,
+            temperature,
+            &rho_assoc,
+            &diameter,
+            0.5,
+            2.0,
+            1.0,
+            2,
+            1e-10,
+            None,
+        );
+        assert!(matches!(result, Err(EosError::NotConverged(_))));
+    }
 }
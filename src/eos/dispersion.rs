@@ -1,10 +1,11 @@
 use super::hard_sphere::zeta;
 use super::GcPcSaftEosParameters;
 use feos_core::{HelmholtzEnergyDual, StateHD};
+use ndarray::Array2;
 use num_dual::DualNum;
 use std::f64::consts::PI;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const A0: [f64; 7] = [
     0.91056314451539,
@@ -61,9 +62,22 @@ pub const B2: [f64; 7] = [
     -29.66690558514725,
 ];
 
+impl GcPcSaftEosParameters {
+    /// Dispersion energy parameter of all segment pairs including the
+    /// (temperature dependent) binary interaction parameter.
+    pub fn epsilon_k_ij<D: DualNum<f64>>(&self, temperature: D) -> Array2<D> {
+        let ti = temperature.recip();
+        let ln_t = temperature.ln();
+        Array2::from_shape_fn(self.k_ij.raw_dim(), |(i, j)| {
+            let k_ij = ti * self.k_ij_t[(i, j)] + ln_t * self.k_ij_ln_t[(i, j)] + self.k_ij[(i, j)];
+            -(k_ij - 1.0) * (self.epsilon_k[i] * self.epsilon_k[j]).sqrt()
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct Dispersion {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
 }
 
 impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Dispersion {
@@ -77,19 +91,28 @@ impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Dispersion {
         let diameter = p.hs_diameter(state.temperature);
         let eta = zeta(p, &diameter, &state.partial_density, [3])[0];
 
-        // mean segment number
-        let m =
-            p.m.iter()
-                .zip(p.component_index.iter())
-                .map(|(&m, &i)| state.molefracs[i] * m)
-                .sum::<D>();
+        // mean segment number (the density of molecules is summed over all
+        // segments weighted by their share of the chain length, which is
+        // equivalent for all components but also valid for segment-resolved
+        // evaluations)
+        let (rho_m, rho_mol) = p
+            .m
+            .iter()
+            .zip(p.component_index.iter())
+            .fold((D::zero(), D::zero()), |(rho_m, rho_mol), (&m, &i)| {
+                (rho_m + rho[i] * m, rho_mol + rho[i] * (m / p.m_mix[i]))
+            });
+        let m = rho_m / rho_mol;
+
+        // temperature dependent dispersion energy
+        let epsilon_k_ij = p.epsilon_k_ij(state.temperature);
 
         // mixture densities, crosswise interactions of all segments on all chains
         let mut rho1mix = D::zero();
         let mut rho2mix = D::zero();
         for i in 0..n {
             for j in 0..n {
-                let eps_ij = state.temperature.recip() * self.parameters.epsilon_k_ij[(i, j)];
+                let eps_ij = state.temperature.recip() * epsilon_k_ij[(i, j)];
                 let sigma_ij = self.parameters.sigma_ij[(i, j)].powi(3);
                 let rho1 = rho[p.component_index[i]]
                     * rho[p.component_index[j]]
@@ -142,7 +165,7 @@ mod test {
     fn test_dispersion_propane() {
         let parameters = propane();
         let contrib = Dispersion {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -164,7 +187,7 @@ mod test {
     fn test_dispersion_propanol() {
         let parameters = propanol();
         let contrib = Dispersion {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -0,0 +1,228 @@
+use super::{GcPcSaft, GcPcSaftEosParameters};
+use crate::record::{GcPcSaftRecord, PcSaftRecord};
+use feos_core::joback::JobackRecord;
+use feos_core::parameter::{ChemicalRecord, Identifier, ParameterError, PureRecord, SegmentRecord};
+use feos_core::{EosResult, PhaseEquilibrium, SolverOptions, State};
+use ndarray::Array1;
+use quantity::si::{SIArray1, SINumber};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Vapor pressures of a component calculated with gc-PC-SAFT and with
+/// the equivalent PC-SAFT parameters.
+#[derive(Clone, Debug)]
+pub struct VaporPressureComparison {
+    /// Identifier of the component
+    pub identifier: Identifier,
+    /// Critical temperature calculated with gc-PC-SAFT
+    pub critical_temperature: SINumber,
+    /// Temperatures at which both vapor pressures could be calculated
+    pub temperature: SIArray1,
+    /// Vapor pressures calculated with gc-PC-SAFT
+    pub vapor_pressure: SIArray1,
+    /// Vapor pressures calculated with the equivalent PC-SAFT parameters
+    pub vapor_pressure_pcsaft: SIArray1,
+}
+
+impl VaporPressureComparison {
+    /// Relative deviations of the equivalent PC-SAFT vapor pressures.
+    pub fn relative_deviation(&self) -> Array1<f64> {
+        Array1::from_shape_fn(self.temperature.len(), |i| {
+            (self.vapor_pressure_pcsaft.get(i) / self.vapor_pressure.get(i))
+                .into_value()
+                .unwrap()
+                - 1.0
+        })
+    }
+
+    /// Average absolute relative deviation of the equivalent PC-SAFT vapor pressures.
+    pub fn aard(&self) -> f64 {
+        self.relative_deviation()
+            .mapv(f64::abs)
+            .mean()
+            .unwrap_or(f64::NAN)
+    }
+}
+
+/// Equivalent PC-SAFT parameters together with a comparison of the
+/// vapor pressures for every component.
+#[derive(Clone, Debug)]
+pub struct EquivalentPcSaft {
+    /// Pure records of the equivalent PC-SAFT parameters
+    pub records: Vec<PureRecord<PcSaftRecord, JobackRecord>>,
+    /// Comparison of the vapor pressures in the order of the records
+    pub report: Vec<VaporPressureComparison>,
+}
+
+impl EquivalentPcSaft {
+    /// Write the pure records to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, file: P) -> Result<(), ParameterError> {
+        let writer = BufWriter::new(File::create(file)?);
+        serde_json::to_writer_pretty(writer, &self.records)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for EquivalentPcSaft {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EquivalentPcSaft(")?;
+        for (record, report) in self.records.iter().zip(&self.report) {
+            let identifier = &record.identifier;
+            write!(
+                f,
+                "\n\t{}: {}, points={}, aard={:.3}%",
+                identifier.name.as_ref().unwrap_or(&identifier.cas),
+                record.model_record,
+                report.temperature.len(),
+                report.aard() * 100.0
+            )?;
+        }
+        write!(f, "\n)")
+    }
+}
+
+impl GcPcSaftEosParameters {
+    /// Equivalent PC-SAFT parameters of every component (see
+    /// [GcPcSaftEosParameters::equivalent_pcsaft_records]) and a comparison
+    /// of the vapor pressures at the given temperatures relative to the
+    /// critical temperature of the component calculated with gc-PC-SAFT.
+    ///
+    /// Temperatures at which either model does not yield a vapor pressure
+    /// are omitted from the comparison.
+    pub fn equivalent_pcsaft(&self, reduced_temperatures: &[f64]) -> EosResult<EquivalentPcSaft> {
+        let records = self.equivalent_pcsaft_records();
+        let report = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                let gc = Rc::new(GcPcSaft::new(Arc::new(self.subset(&[i]))));
+                let pcsaft = Rc::new(GcPcSaft::new(Arc::new(pcsaft_parameters(record)?)));
+                let critical_temperature =
+                    State::critical_point_pure(&gc, None, SolverOptions::default())?[0].temperature;
+
+                let mut temperature = Vec::new();
+                let mut vapor_pressure = Vec::new();
+                let mut vapor_pressure_pcsaft = Vec::new();
+                for &t in reduced_temperatures {
+                    let t = t * critical_temperature;
+                    if let (Some(p), Some(p_pcsaft)) = (
+                        PhaseEquilibrium::vapor_pressure(&gc, t)[0],
+                        PhaseEquilibrium::vapor_pressure(&pcsaft, t)[0],
+                    ) {
+                        temperature.push(t);
+                        vapor_pressure.push(p);
+                        vapor_pressure_pcsaft.push(p_pcsaft);
+                    }
+                }
+                Ok(VaporPressureComparison {
+                    identifier: record.identifier.clone(),
+                    critical_temperature,
+                    temperature: SIArray1::from_vec(temperature),
+                    vapor_pressure: SIArray1::from_vec(vapor_pressure),
+                    vapor_pressure_pcsaft: SIArray1::from_vec(vapor_pressure_pcsaft),
+                })
+            })
+            .collect::<EosResult<_>>()?;
+        Ok(EquivalentPcSaft { records, report })
+    }
+}
+
+/// gc-PC-SAFT parameters that reproduce PC-SAFT for a single pure record,
+/// using a single segment with $m-1$ bonds.
+fn pcsaft_parameters(
+    record: &PureRecord<PcSaftRecord, JobackRecord>,
+) -> Result<GcPcSaftEosParameters, ParameterError> {
+    let r = &record.model_record;
+    let identifier = record
+        .identifier
+        .name
+        .as_ref()
+        .unwrap_or(&record.identifier.cas)
+        .clone();
+    let segment = SegmentRecord::new(
+        identifier.clone(),
+        record.molarweight,
+        GcPcSaftRecord {
+            m: r.m,
+            sigma: r.sigma,
+            epsilon_k: r.epsilon_k,
+            mu: r.mu,
+            q: r.q,
+            kappa_ab: r.kappa_ab,
+            epsilon_k_ab: r.epsilon_k_ab,
+            na: r.na,
+            nb: r.nb,
+            ..Default::default()
+        },
+        None,
+    );
+    let segments = HashMap::from([(identifier.clone(), 1.0)]);
+    let bonds = HashMap::from([([identifier.clone(), identifier], r.m - 1.0)]);
+    let chemical_record =
+        ChemicalRecord::new_count(record.identifier.clone(), segments, Some(bonds));
+    GcPcSaftEosParameters::from_segments(vec![chemical_record], vec![segment], None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parameter::test::*;
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_equivalent_records() {
+        let params = ethanol_propanol(true);
+        let records = params.equivalent_pcsaft_records();
+        assert_eq!(records.len(), 2);
+        let r = &records[1].model_record;
+        assert_eq!(records[1].identifier.name.as_deref(), Some("1-propanol"));
+        assert_eq!(r.m, params.m_mix[1]);
+        assert_eq!(r.sigma, params.sigma_mix[1]);
+        assert_eq!(r.epsilon_k, params.epsilon_k_mix[1]);
+        assert_relative_eq!(
+            r.kappa_ab.unwrap(),
+            0.009583 * (2.7702 / params.sigma_mix[1]).powi(3),
+            max_relative = 1e-14
+        );
+        assert_eq!(r.epsilon_k_ab, Some(2575.9));
+        assert_eq!((r.na, r.nb), (Some(1.0), Some(1.0)));
+        assert!(r.mu.is_none());
+    }
+
+    #[test]
+    fn test_equivalent_pcsaft() -> Result<(), Box<dyn std::error::Error>> {
+        let reduced_temperatures = [0.6, 0.7, 0.8];
+
+        // the homosegmented model is reproduced exactly
+        let equivalent = ethanol_propanol(true)
+            .homosegmented()
+            .equivalent_pcsaft(&reduced_temperatures)?;
+        for report in equivalent.report.iter() {
+            assert_eq!(report.temperature.len(), 3);
+            assert!(report.aard() < 1e-8);
+        }
+
+        // the heterosegmented model is only approximated
+        let equivalent = propane_co2().equivalent_pcsaft(&reduced_temperatures)?;
+        let aard: Vec<_> = equivalent.report.iter().map(|r| r.aard()).collect();
+        assert!(aard.iter().all(|&a| a > 1e-3), "{:?}", aard);
+        assert_eq!(equivalent.records[1].model_record.q, Some(4.4));
+
+        let path = std::env::temp_dir().join("gc_pcsaft_test_equivalent_pcsaft.json");
+        equivalent.to_json(&path)?;
+        let records: Vec<PureRecord<PcSaftRecord, JobackRecord>> =
+            serde_json::from_reader(BufReader::new(File::open(&path)?))?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].model_record.m,
+            equivalent.records[0].model_record.m
+        );
+        Ok(())
+    }
+}
@@ -3,11 +3,11 @@ use super::GcPcSaftEosParameters;
 use feos_core::{HelmholtzEnergyDual, StateHD};
 use num_dual::*;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct HardChain {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
 }
 
 impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for HardChain {
@@ -28,7 +28,11 @@ impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for HardChain {
                 let (di, dj) = (diameter[*i], diameter[*j]);
                 let cdij = c * di * dj / (di + dj);
                 let g = frac_1mz3 + cdij * 3.0 - cdij * cdij * (zeta3 - 1.0) * 2.0;
-                -state.moles[self.parameters.component_index[*i]] * *count * g.ln()
+                // both segments belong to the same component, the average only
+                // matters for segment-resolved evaluations
+                let ci = self.parameters.component_index[*i];
+                let cj = self.parameters.component_index[*j];
+                -(state.moles[ci] + state.moles[cj]) * 0.5 * *count * g.ln()
             })
             .sum()
     }
@@ -54,7 +58,7 @@ mod test {
     fn test_hc_propane() {
         let parameters = propane();
         let contrib = HardChain {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -80,7 +84,7 @@ mod test {
     fn test_hc_propanol() {
         let parameters = propanol();
         let contrib = HardChain {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
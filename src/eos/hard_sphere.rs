@@ -4,7 +4,7 @@ use ndarray::*;
 use num_dual::DualNum;
 use std::f64::consts::FRAC_PI_6;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 impl GcPcSaftEosParameters {
     pub fn hs_diameter<D: DualNum<f64>>(&self, temperature: D) -> Array1<D> {
@@ -17,7 +17,7 @@ impl GcPcSaftEosParameters {
 
 #[derive(Clone)]
 pub struct HardSphere {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
 }
 
 impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for HardSphere {
@@ -92,7 +92,7 @@ mod test {
     fn test_hs_propane() {
         let parameters = propane();
         let contrib = HardSphere {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -114,7 +114,7 @@ mod test {
     fn test_hs_propanol() {
         let parameters = propanol();
         let contrib = HardSphere {
-            parameters: Rc::new(parameters),
+            parameters: Arc::new(parameters),
         };
         let temperature = 300.0;
         let volume = METER
@@ -0,0 +1,187 @@
+use crate::record::HeatCapacityRecord;
+use feos_core::joback::{Joback, JobackRecord};
+use feos_core::parameter::ChemicalRecord;
+use feos_core::IdealGasContributionDual;
+use ndarray::Array1;
+use num_dual::DualNum;
+use std::collections::HashMap;
+use std::fmt;
+
+const RGAS: f64 = 6.022140857 * 1.38064852;
+const T0: f64 = 298.15;
+const P0: f64 = 1.0e5;
+const A3: f64 = 1e-30;
+const KB: f64 = 1.38064852e-23;
+
+/// Ideal gas model of a single component.
+#[derive(Debug, Clone)]
+pub enum IdealGasModel {
+    /// Heat capacity according to Joback and Reid (1987)
+    Joback(Joback),
+    /// Heat capacity polynomial with the coefficients [a, b, c, d, e]
+    /// of a [HeatCapacityRecord]
+    HeatCapacity([f64; 5]),
+    /// De Broglie wavelength of zero, only suitable for properties that
+    /// do not depend on the ideal gas heat capacity
+    Default,
+}
+
+/// Ideal gas contribution of gc-PC-SAFT with an individual model for every component.
+#[derive(Debug, Clone)]
+pub struct GcIdealGas {
+    pub models: Vec<IdealGasModel>,
+}
+
+impl GcIdealGas {
+    pub fn new(models: Vec<IdealGasModel>) -> Self {
+        Self { models }
+    }
+
+    /// Joback model for every component with a Joback record and the
+    /// default model for all other components.
+    pub fn joback(records: &[Option<JobackRecord>]) -> Self {
+        Self::new(
+            records
+                .iter()
+                .map(|r| match r {
+                    Some(r) => IdealGasModel::Joback(Joback::new(vec![r.clone()])),
+                    None => IdealGasModel::Default,
+                })
+                .collect(),
+        )
+    }
+
+    /// Replace the models of all components for which heat capacity records
+    /// of all groups are available.
+    ///
+    /// The chemical records are given in the order of the components and can
+    /// use different groups than the gc-PC-SAFT segments. Components with
+    /// missing groups keep their current model.
+    pub fn with_heat_capacity_records(
+        mut self,
+        chemical_records: &[ChemicalRecord],
+        records: &[HeatCapacityRecord],
+    ) -> Self {
+        let records: HashMap<_, _> = records.iter().map(|r| (&r.identifier, r)).collect();
+        for (model, chemical_record) in self.models.iter_mut().zip(chemical_records) {
+            let coefficients = chemical_record
+                .segment_id_count()
+                .iter()
+                .map(|(id, &n)| {
+                    records
+                        .get(id)
+                        .map(|r| [r.a * n, r.b * n, r.c * n, r.d * n, r.e * n])
+                })
+                .collect::<Option<Vec<_>>>();
+            if let Some(coefficients) = coefficients {
+                let mut sum = [0.0; 5];
+                for c in coefficients {
+                    sum.iter_mut().zip(c).for_each(|(s, c)| *s += c);
+                }
+                *model = IdealGasModel::HeatCapacity(sum);
+            }
+        }
+        self
+    }
+
+    pub fn subset(&self, component_list: &[usize]) -> Self {
+        Self::new(
+            component_list
+                .iter()
+                .map(|&i| self.models[i].clone())
+                .collect(),
+        )
+    }
+}
+
+impl<D: DualNum<f64>> IdealGasContributionDual<D> for GcIdealGas {
+    fn de_broglie_wavelength(&self, temperature: D, components: usize) -> Array1<D> {
+        Array1::from_shape_fn(components, |i| match &self.models[i] {
+            IdealGasModel::Joback(joback) => joback.de_broglie_wavelength(temperature, 1)[0],
+            IdealGasModel::HeatCapacity([a, b, c, d, e]) => {
+                let t = temperature;
+                let t2 = t * t;
+                let h = (t2 - T0 * T0) * 0.5 * *b
+                    + (t * t2 - T0.powi(3)) * *c / 3.0
+                    + (t2 * t2 - T0.powi(4)) * *d / 4.0
+                    + (t2 * t2 * t - T0.powi(5)) * *e / 5.0
+                    + (t - T0) * *a;
+                let s = (t - T0) * *b
+                    + (t2 - T0.powi(2)) * 0.5 * *c
+                    + (t2 * t - T0.powi(3)) * *d / 3.0
+                    + (t2 * t2 - T0.powi(4)) * *e / 4.0
+                    + (t / T0).ln() * *a;
+                (h - t * s) / (t * RGAS) + (t * KB / (P0 * A3)).ln()
+            }
+            IdealGasModel::Default => D::zero(),
+        })
+    }
+}
+
+impl fmt::Display for GcIdealGas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ideal gas (GC)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Identifier;
+
+    #[test]
+    fn test_heat_capacity_polynomial() {
+        let coefficients = [-2.0, 0.3, -1.5e-4, 3.0e-8, 0.0];
+        let [a, b, c, d, e] = coefficients;
+        let joback = GcIdealGas::joback(&[Some(JobackRecord::new(a, b, c, d, e))]);
+        let polynomial = GcIdealGas::new(vec![IdealGasModel::HeatCapacity(coefficients)]);
+        for &t in &[250.0, 300.0, 400.0] {
+            assert_relative_eq!(
+                joback.de_broglie_wavelength(t, 1)[0],
+                polynomial.de_broglie_wavelength(t, 1)[0],
+                max_relative = 1e-14
+            );
+        }
+    }
+
+    #[test]
+    fn test_fallback_per_component() {
+        let record = |name: &str, segments: &[&str]| {
+            ChemicalRecord::new(
+                Identifier::new("", Some(name), None, None, None, None),
+                segments.iter().map(|s| s.to_string()).collect(),
+                None,
+            )
+        };
+        let ideal_gas = GcIdealGas::joback(&[None, Some(JobackRecord::default()), None]);
+        assert!(matches!(ideal_gas.models[0], IdealGasModel::Default));
+        assert!(matches!(ideal_gas.models[1], IdealGasModel::Joback(_)));
+
+        let chemical_records = [
+            record("ethanol", &["C-(C)(H)3", "C-(C)(H)2(O)", "O-(C)(H)"]),
+            record("propane", &["C-(C)(H)3", "C-(C)2(H)2", "C-(C)(H)3"]),
+            record("methanol", &["C-(O)(H)3", "O-(C)(H)"]),
+        ];
+        let records = [
+            HeatCapacityRecord::new("C-(C)(H)3".into(), 25.9, 0.0, 0.0, 0.0, 0.0),
+            HeatCapacityRecord::new("C-(C)2(H)2".into(), 22.8, 0.0, 0.0, 0.0, 0.0),
+            HeatCapacityRecord::new("C-(C)(H)2(O)".into(), 20.9, 0.0, 0.0, 0.0, 0.0),
+            HeatCapacityRecord::new("O-(C)(H)".into(), 18.1, 0.0, 0.0, 0.0, 0.0),
+        ];
+        let ideal_gas = ideal_gas.with_heat_capacity_records(&chemical_records, &records);
+        match ideal_gas.models[0] {
+            IdealGasModel::HeatCapacity(c) => assert_relative_eq!(c[0], 25.9 + 20.9 + 18.1),
+            _ => panic!("ethanol should use the heat capacity records"),
+        }
+        match ideal_gas.models[1] {
+            IdealGasModel::HeatCapacity(c) => assert_relative_eq!(c[0], 2.0 * 25.9 + 22.8),
+            _ => panic!("propane should use the heat capacity records"),
+        }
+        assert!(matches!(ideal_gas.models[2], IdealGasModel::Default));
+
+        let subset = ideal_gas.subset(&[2, 1]);
+        assert!(matches!(subset.models[0], IdealGasModel::Default));
+        assert!(matches!(subset.models[1], IdealGasModel::HeatCapacity(_)));
+    }
+}
@@ -0,0 +1,522 @@
+use super::GcPcSaftEosParameters;
+use feos_core::{EosError, EosResult, HelmholtzEnergyDual, StateHD};
+use ndarray::Array1;
+use num_dual::DualNum;
+use std::f64::consts::PI;
+use std::fmt;
+use std::sync::Arc;
+
+/// $\frac{e^2}{4\pi\varepsilon_0k_\mathrm{B}}$ in units of Angstrom * Kelvin, i.e.,
+/// the Bjerrum length in vacuum times the temperature.
+pub(crate) const BJERRUM_LENGTH_VACUUM: f64 = 167100.94689828737;
+
+/// Model for the relative permittivity of the solvent used in the
+/// electrostatic contributions.
+#[derive(Clone, Debug)]
+pub enum Permittivity {
+    /// Constant relative permittivity
+    Constant(f64),
+    /// Relative permittivities of all components averaged with the salt-free
+    /// mole fractions, i.e., the mole fractions among all components that
+    /// do not contain charged segments. The values of charged components
+    /// are not used, so that the permittivity does not depend on the
+    /// concentration of the ions.
+    SaltFreeMixing(Vec<f64>),
+    /// Relative permittivity of the salt-free mixture (see `SaltFreeMixing`)
+    /// that decreases linearly with the molality $b$ of all components with
+    /// charged segments, i.e., $\varepsilon_r=\varepsilon_{r,\mathrm{sf}}-\delta b$
+    /// with the decrement $\delta$ in kg/mol.
+    SaltFreeMixingDecrement { epsilon_r: Vec<f64>, decrement: f64 },
+}
+
+/// Relative permittivity of water at 298.15 K.
+impl Default for Permittivity {
+    fn default() -> Self {
+        Self::Constant(78.4)
+    }
+}
+
+impl Permittivity {
+    pub(crate) fn subset(&self, component_list: &[usize]) -> Self {
+        let subset = |epsilon_r: &[f64]| component_list.iter().map(|&i| epsilon_r[i]).collect();
+        match self {
+            Self::Constant(epsilon_r) => Self::Constant(*epsilon_r),
+            Self::SaltFreeMixing(epsilon_r) => Self::SaltFreeMixing(subset(epsilon_r)),
+            Self::SaltFreeMixingDecrement {
+                epsilon_r,
+                decrement,
+            } => Self::SaltFreeMixingDecrement {
+                epsilon_r: subset(epsilon_r),
+                decrement: *decrement,
+            },
+        }
+    }
+
+    /// Check that the model provides a permittivity for every component.
+    pub(crate) fn check_components(&self, components: usize) -> EosResult<()> {
+        match self {
+            Self::Constant(_) => Ok(()),
+            Self::SaltFreeMixing(epsilon_r) | Self::SaltFreeMixingDecrement { epsilon_r, .. } => {
+                if epsilon_r.len() != components {
+                    return Err(EosError::IncompatibleComponents(
+                        components,
+                        epsilon_r.len(),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Relative permittivity for the given densities of the components,
+    /// which enter the salt-free mole fractions and the salt molality with
+    /// the given weights.
+    pub(crate) fn relative_permittivity<D: DualNum<f64>>(
+        &self,
+        weights: &PermittivityWeights,
+        molarweight: &Array1<f64>,
+        partial_density: &Array1<D>,
+    ) -> D {
+        match self {
+            Self::Constant(epsilon_r) => D::from(*epsilon_r),
+            Self::SaltFreeMixing(epsilon_r) => weights.salt_free_mixing(epsilon_r, partial_density),
+            Self::SaltFreeMixingDecrement {
+                epsilon_r,
+                decrement,
+            } => {
+                // molality in mol/kg with molar weights in g/mol
+                let mut rho_salt = D::zero();
+                let mut rho_mw_solvent = D::zero();
+                for ((&r, &w_salt), (&w_solvent, &mw)) in partial_density
+                    .iter()
+                    .zip(weights.salt.iter())
+                    .zip(weights.solvent.iter().zip(molarweight.iter()))
+                {
+                    rho_salt += r * w_salt;
+                    rho_mw_solvent += r * w_solvent * mw;
+                }
+                let molality = rho_salt / rho_mw_solvent * 1000.0;
+                weights.salt_free_mixing(epsilon_r, partial_density) - molality * *decrement
+            }
+        }
+    }
+}
+
+/// Weights of the components in the salt-free mole fractions and in the
+/// salt molality used in the permittivity models.
+#[derive(Clone, Debug)]
+pub(crate) struct PermittivityWeights {
+    /// Zero for components with charged segments and one otherwise
+    pub solvent: Array1<f64>,
+    /// One for components with charged segments and zero otherwise
+    pub salt: Array1<f64>,
+}
+
+impl PermittivityWeights {
+    pub(crate) fn new(
+        component_index: &Array1<usize>,
+        ion_segment: &Array1<usize>,
+        components: usize,
+    ) -> Self {
+        let mut salt = Array1::zeros(components);
+        for &s in ion_segment.iter() {
+            salt[component_index[s]] = 1.0;
+        }
+        Self {
+            solvent: 1.0 - &salt,
+            salt,
+        }
+    }
+
+    /// Weights for a parameter set in which every segment is a component. The
+    /// weights of a component are shared equally between its segments.
+    pub(crate) fn segment_resolved(&self, component_index: &Array1<usize>) -> Self {
+        let share = |w: &Array1<f64>| {
+            component_index
+                .mapv(|c| w[c] / component_index.iter().filter(|&&i| i == c).count() as f64)
+        };
+        Self {
+            solvent: share(&self.solvent),
+            salt: share(&self.salt),
+        }
+    }
+
+    fn salt_free_mixing<D: DualNum<f64>>(
+        &self,
+        epsilon_r: &[f64],
+        partial_density: &Array1<D>,
+    ) -> D {
+        let mut rho = D::zero();
+        let mut rho_epsilon_r = D::zero();
+        for ((&e, &w), &r) in epsilon_r
+            .iter()
+            .zip(self.solvent.iter())
+            .zip(partial_density.iter())
+        {
+            if w > 0.0 {
+                rho += r * w;
+                rho_epsilon_r += r * w * e;
+            }
+        }
+        rho_epsilon_r / rho
+    }
+}
+
+/// Densities of the charged segments.
+fn ion_density<D: DualNum<f64>>(
+    parameters: &GcPcSaftEosParameters,
+    partial_density: &Array1<D>,
+) -> Array1<D> {
+    Array1::from_shape_fn(parameters.ion_segment.len(), |k| {
+        partial_density[parameters.component_index[parameters.ion_segment[k]]] * parameters.n_ion[k]
+    })
+}
+
+/// Debye-Hückel contribution of the charged segments as in ePC-SAFT
+/// (Cameretti et al. 2005).
+#[derive(Clone)]
+pub struct DebyeHueckel {
+    pub parameters: Arc<GcPcSaftEosParameters>,
+    pub permittivity: Permittivity,
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for DebyeHueckel {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let p = &self.parameters;
+        let epsilon_r = self.permittivity.relative_permittivity(
+            &p.permittivity_weights,
+            &p.molarweight,
+            &state.partial_density,
+        );
+        let diameter = p.ion_segment.mapv(|s| p.sigma[s]);
+        let rho = ion_density(p, &state.partial_density);
+        state.volume
+            * debye_hueckel_helmholtz_energy_density(
+                state.temperature,
+                epsilon_r,
+                &rho,
+                &p.z,
+                &diameter,
+            )
+    }
+}
+
+impl fmt::Display for DebyeHueckel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Debye-Hückel")
+    }
+}
+
+/// Reduced Helmholtz energy density of the Debye-Hückel theory for ions
+/// with the given densities, charges and diameters.
+pub(crate) fn debye_hueckel_helmholtz_energy_density<D: DualNum<f64>>(
+    temperature: D,
+    epsilon_r: D,
+    rho: &Array1<D>,
+    z: &Array1<f64>,
+    diameter: &Array1<f64>,
+) -> D {
+    let rho_z2 = rho
+        .iter()
+        .zip(z.iter())
+        .fold(D::zero(), |acc, (&r, &z)| acc + r * z * z);
+    if rho_z2.re() <= 0.0 {
+        return D::zero();
+    }
+    let l_b = (temperature * epsilon_r).recip() * BJERRUM_LENGTH_VACUUM;
+    let kappa = (l_b * rho_z2 * 4.0 * PI).sqrt();
+    let sum = rho
+        .iter()
+        .zip(z.iter())
+        .zip(diameter.iter())
+        .fold(D::zero(), |acc, ((&r, &z), &d)| {
+            acc + r * z * z * chi(kappa * d)
+        });
+    -kappa * l_b * sum / 3.0
+}
+
+/// $\chi(x)=\frac{3}{x^3}\left(\frac{3}{2}+\ln(1+x)-2(1+x)+\frac{1}{2}(1+x)^2\right)$
+fn chi<D: DualNum<f64>>(x: D) -> D {
+    if x.re() < 0.1 {
+        // series expansion to avoid cancellation for small arguments
+        (3..16).rev().fold(D::zero(), |acc, k| {
+            let sign = if k % 2 == 0 { -3.0 } else { 3.0 };
+            acc * x + sign / k as f64
+        })
+    } else {
+        let x1 = x + 1.0;
+        (x1.ln() - x1 * 2.0 + x1 * x1 * 0.5 + 1.5) * 3.0 / x.powi(3)
+    }
+}
+
+/// Born contribution of the charged segments, using the segment diameter
+/// as Born diameter.
+#[derive(Clone)]
+pub struct Born {
+    pub parameters: Arc<GcPcSaftEosParameters>,
+    pub permittivity: Permittivity,
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Born {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let p = &self.parameters;
+        let epsilon_r = self.permittivity.relative_permittivity(
+            &p.permittivity_weights,
+            &p.molarweight,
+            &state.partial_density,
+        );
+        let diameter = p.ion_segment.mapv(|s| p.sigma[s]);
+        let rho = ion_density(p, &state.partial_density);
+        state.volume
+            * born_helmholtz_energy_density(state.temperature, epsilon_r, &rho, &p.z, &diameter)
+    }
+}
+
+impl fmt::Display for Born {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Born")
+    }
+}
+
+/// Reduced Helmholtz energy density of the Born model for ions with the
+/// given densities, charges and diameters.
+pub(crate) fn born_helmholtz_energy_density<D: DualNum<f64>>(
+    temperature: D,
+    epsilon_r: D,
+    rho: &Array1<D>,
+    z: &Array1<f64>,
+    diameter: &Array1<f64>,
+) -> D {
+    let sum = rho
+        .iter()
+        .zip(z.iter())
+        .zip(diameter.iter())
+        .fold(D::zero(), |acc, ((&r, &z), &d)| acc + r * z * z / d);
+    -sum * (-epsilon_r.recip() + 1.0) / temperature * BJERRUM_LENGTH_VACUUM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{GcPcSaft, GcPcSaftOptions};
+    use super::*;
+    use crate::record::GcPcSaftRecord;
+    use approx::assert_relative_eq;
+    use feos_core::joback::JobackRecord;
+    use feos_core::parameter::{ChemicalRecord, Identifier, ParameterError, SegmentRecord};
+    use feos_core::{Contributions, EntropyScaling, EosResult, EquationOfState, State};
+    use ndarray::arr1;
+    use quantity::si::{SIArray1, KELVIN, METER, MOL};
+    use std::rc::Rc;
+
+    fn segment(
+        identifier: &str,
+        m: f64,
+        sigma: f64,
+        epsilon_k: f64,
+        z: Option<f64>,
+    ) -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+        let record = GcPcSaftRecord {
+            m,
+            sigma,
+            epsilon_k,
+            z,
+            ..Default::default()
+        };
+        SegmentRecord::new(identifier.into(), 20.0, record, None)
+    }
+
+    /// Water and sodium chloride with ePC-SAFT parameters of Held et al. (2014).
+    fn water_nacl() -> Result<GcPcSaftEosParameters, ParameterError> {
+        let mut water = segment("H2O", 1.2047, 2.7927, 353.95, None);
+        water.model_record.kappa_ab = Some(0.0451);
+        water.model_record.epsilon_k_ab = Some(2425.67);
+        let segments = vec![
+            water,
+            segment("Na+", 1.0, 2.8232, 230.0, Some(1.0)),
+            segment("Cl-", 1.0, 2.7560, 170.0, Some(-1.0)),
+        ];
+        let chemical_records = vec![
+            ChemicalRecord::new(
+                Identifier::new("", Some("water"), None, None, None, None),
+                vec!["H2O".into()],
+                None,
+            ),
+            ChemicalRecord::new(
+                Identifier::new("", Some("sodium chloride"), None, None, None, None),
+                vec!["Na+".into(), "Cl-".into()],
+                Some(vec![]),
+            ),
+        ];
+        GcPcSaftEosParameters::from_segments(chemical_records, segments, None)
+    }
+
+    #[test]
+    fn test_electroneutrality() {
+        let segments = vec![segment("Na+", 1.0, 2.8232, 230.0, Some(1.0))];
+        let sodium = ChemicalRecord::new(
+            Identifier::new("", Some("sodium"), None, None, None, None),
+            vec!["Na+".into()],
+            None,
+        );
+        assert!(GcPcSaftEosParameters::from_segments(vec![sodium], segments, None).is_err());
+
+        let parameters = water_nacl().unwrap();
+        assert_eq!(parameters.z.len(), 2);
+        assert_eq!(parameters.z.sum(), 0.0);
+        assert_eq!(parameters.n_ion, arr1(&[1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_permittivity() -> EosResult<()> {
+        let parameters = Arc::new(water_nacl()?);
+        let options = GcPcSaftOptions {
+            born: true,
+            ..Default::default()
+        };
+        let eos = GcPcSaft::with_options(parameters.clone(), options);
+        let contributions: Vec<_> = eos.residual().iter().map(|c| c.to_string()).collect();
+        assert!(contributions.contains(&"Debye-Hückel".to_string()));
+        assert!(contributions.contains(&"Born".to_string()));
+        assert!(GcPcSaft::new(parameters.clone())
+            .with_permittivity(Permittivity::SaltFreeMixing(vec![78.4]))
+            .is_err());
+
+        // the permittivity of the salt does not contribute to the mixture
+        let t = 298.15 * KELVIN;
+        let moles = arr1(&[55.0, 1.0]) * MOL;
+        let a = |permittivity| -> EosResult<_> {
+            let eos = GcPcSaft::with_options(parameters.clone(), options)
+                .with_permittivity(permittivity)?;
+            let state = State::new_nvt(&Rc::new(eos), t, 1e-3 * METER.powi(3), &moles)?;
+            Ok(state.helmholtz_energy(Contributions::ResidualNvt))
+        };
+        assert_relative_eq!(
+            a(Permittivity::Constant(78.4))?,
+            a(Permittivity::SaltFreeMixing(vec![78.4, 10.0]))?,
+            max_relative = 1e-14
+        );
+        assert!(
+            a(Permittivity::Constant(78.4))? != a(Permittivity::Constant(50.0))?,
+            "the electrostatic contributions depend on the permittivity"
+        );
+
+        // the permittivity is kept for subsets
+        let eos = GcPcSaft::new(parameters)
+            .with_permittivity(Permittivity::SaltFreeMixing(vec![78.4, 10.0]))?
+            .subset(&[1]);
+        assert!(matches!(
+            &eos.permittivity,
+            Permittivity::SaltFreeMixing(e) if e == &vec![10.0]
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_permittivity_entropy_scaling() -> EosResult<()> {
+        let mut segments = vec![
+            segment("Na+", 1.0, 2.8232, 230.0, Some(1.0)),
+            segment("Cl-", 1.0, 2.7560, 170.0, Some(-1.0)),
+        ];
+        for s in segments.iter_mut() {
+            s.model_record.thermal_conductivity = Some([-0.1; 4]);
+        }
+        let salt = ChemicalRecord::new(
+            Identifier::new("", Some("sodium chloride"), None, None, None, None),
+            vec!["Na+".into(), "Cl-".into()],
+            Some(vec![]),
+        );
+        let parameters = Arc::new(GcPcSaftEosParameters::from_segments(
+            vec![salt],
+            segments,
+            None,
+        )?);
+
+        // the reference of the thermal conductivity uses the permittivity
+        // of the equation of state for the residual entropy
+        let t = 1500.0 * KELVIN;
+        let v = 1e-3 * METER.powi(3);
+        let moles = arr1(&[1.0]) * MOL;
+        let reference = |permittivity| -> EosResult<_> {
+            GcPcSaft::new(parameters.clone())
+                .with_permittivity(permittivity)?
+                .thermal_conductivity_reference(t, v, &moles)
+        };
+        assert!(
+            reference(Permittivity::Constant(78.4))? != reference(Permittivity::Constant(20.0))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_permittivity_decrement() -> EosResult<()> {
+        let parameters = Arc::new(water_nacl()?);
+        let t = 298.15 * KELVIN;
+        let a = |permittivity, moles: &SIArray1| -> EosResult<_> {
+            let eos = GcPcSaft::new(parameters.clone()).with_permittivity(permittivity)?;
+            let state = State::new_nvt(&Rc::new(eos), t, 1e-3 * METER.powi(3), moles)?;
+            Ok(state.helmholtz_energy(Contributions::ResidualNvt))
+        };
+        let decrement = |decrement| Permittivity::SaltFreeMixingDecrement {
+            epsilon_r: vec![78.4, 10.0],
+            decrement,
+        };
+
+        // 1 mol NaCl in 1 kg water (molar weight of the test segment: 20 g/mol)
+        let moles = arr1(&[50.0, 1.0]) * MOL;
+        assert_relative_eq!(
+            a(decrement(0.0), &moles)?,
+            a(Permittivity::SaltFreeMixing(vec![78.4, 10.0]), &moles)?,
+            max_relative = 1e-14
+        );
+        assert_relative_eq!(
+            a(decrement(15.0), &moles)?,
+            a(Permittivity::Constant(78.4 - 15.0), &moles)?,
+            max_relative = 1e-14
+        );
+
+        // the permittivity decreases with the salt concentration
+        let moles = arr1(&[50.0, 0.5]) * MOL;
+        assert_relative_eq!(
+            a(decrement(15.0), &moles)?,
+            a(Permittivity::Constant(78.4 - 7.5), &moles)?,
+            max_relative = 1e-14
+        );
+        assert!(GcPcSaft::new(parameters)
+            .with_permittivity(Permittivity::SaltFreeMixingDecrement {
+                epsilon_r: vec![78.4],
+                decrement: 15.0,
+            })
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_chi() {
+        let chi_exact = |x: f64| {
+            3.0 / x.powi(3) * (1.5 + (1.0 + x).ln() - 2.0 * (1.0 + x) + 0.5 * (1.0 + x).powi(2))
+        };
+        assert_relative_eq!(chi(0.1 - 1e-12), chi_exact(0.1), max_relative = 1e-10);
+        assert_relative_eq!(chi(0.0), 1.0);
+        assert_relative_eq!(chi(1.0), chi_exact(1.0));
+    }
+
+    #[test]
+    fn test_limiting_law() {
+        // NaCl (1 mmol/l) in water at 298.15 K
+        let t = 298.15;
+        let rho = 1e-3 * 6.02214076e23 * 1e-27;
+        let rho = arr1(&[rho, rho]);
+        let a = debye_hueckel_helmholtz_energy_density(
+            t,
+            78.4,
+            &rho,
+            &arr1(&[1.0, -1.0]),
+            &arr1(&[0.0, 0.0]),
+        );
+        let l_b = BJERRUM_LENGTH_VACUUM / (78.4 * t);
+        let kappa = (4.0 * PI * l_b * 2.0 * rho[0]).sqrt();
+        assert_relative_eq!(a, -kappa.powi(3) / (12.0 * PI), max_relative = 1e-14);
+        // Bjerrum length of water
+        assert_relative_eq!(l_b, 7.15, max_relative = 1e-3);
+    }
+}
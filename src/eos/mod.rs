@@ -1,22 +1,35 @@
-use feos_core::joback::Joback;
-use feos_core::{EquationOfState, HelmholtzEnergy, IdealGasContribution, MolarWeight};
+use feos_core::parameter::ParameterError;
+use feos_core::{
+    Contributions, EntropyScaling, EosError, EosResult, EquationOfState, HelmholtzEnergy,
+    IdealGasContribution, MolarWeight, State,
+};
 use ndarray::Array1;
 use quantity::si::*;
-use std::f64::consts::FRAC_PI_6;
+use std::f64::consts::{FRAC_PI_6, PI};
 use std::rc::Rc;
+use std::sync::Arc;
 
 pub(crate) mod association;
 pub(crate) mod dispersion;
+mod equivalent;
 mod hard_chain;
 mod hard_sphere;
-mod parameter;
-mod polar;
-use association::{Association, CrossAssociation};
+mod ideal_gas;
+pub(crate) mod ionic;
+pub(crate) mod parameter;
+pub(crate) mod polar;
+mod segment_contributions;
+use association::{single_segment_association, Association, CrossAssociation};
 use dispersion::Dispersion;
+pub use equivalent::{EquivalentPcSaft, VaporPressureComparison};
 use hard_chain::HardChain;
 use hard_sphere::HardSphere;
+pub use ideal_gas::{GcIdealGas, IdealGasModel};
+pub use ionic::Permittivity;
+use ionic::{Born, DebyeHueckel};
 pub use parameter::GcPcSaftEosParameters;
-use polar::Dipole;
+use polar::{Dipole, DipoleQuadrupole, Quadrupole};
+pub use segment_contributions::SegmentContributions;
 
 /// Customization options for the gc-PC-SAFT equation of state and functional.
 #[derive(Copy, Clone)]
@@ -27,6 +40,12 @@ pub struct GcPcSaftOptions {
     pub max_iter_cross_assoc: usize,
     /// tolerance for cross association calculation
     pub tol_cross_assoc: f64,
+    /// include the Born contribution for charged segments
+    pub born: bool,
+    /// cutoff radius (in Angstrom) of the mean-field Coulomb interaction of
+    /// charged segments in inhomogeneous systems (functional only), which
+    /// should be equal to the width of planar profiles
+    pub coulomb_cutoff: Option<f64>,
 }
 
 impl Default for GcPcSaftOptions {
@@ -35,24 +54,42 @@ impl Default for GcPcSaftOptions {
             max_eta: 0.5,
             max_iter_cross_assoc: 50,
             tol_cross_assoc: 1e-10,
+            born: false,
+            coulomb_cutoff: None,
         }
     }
 }
 
 /// gc-PC-SAFT equation of state
 pub struct GcPcSaft {
-    pub parameters: Rc<GcPcSaftEosParameters>,
+    pub parameters: Arc<GcPcSaftEosParameters>,
     options: GcPcSaftOptions,
     contributions: Vec<Box<dyn HelmholtzEnergy>>,
-    joback: Joback,
+    ideal_gas: GcIdealGas,
+    permittivity: Permittivity,
 }
 
 impl GcPcSaft {
-    pub fn new(parameters: Rc<GcPcSaftEosParameters>) -> Self {
+    pub fn new(parameters: Arc<GcPcSaftEosParameters>) -> Self {
         Self::with_options(parameters, GcPcSaftOptions::default())
     }
 
-    pub fn with_options(parameters: Rc<GcPcSaftEosParameters>, options: GcPcSaftOptions) -> Self {
+    pub fn with_options(parameters: Arc<GcPcSaftEosParameters>, options: GcPcSaftOptions) -> Self {
+        let permittivity = Permittivity::default();
+        Self {
+            parameters: parameters.clone(),
+            options,
+            contributions: Self::contributions(&parameters, options, &permittivity),
+            ideal_gas: GcIdealGas::joback(&parameters.joback_records),
+            permittivity,
+        }
+    }
+
+    fn contributions(
+        parameters: &Arc<GcPcSaftEosParameters>,
+        options: GcPcSaftOptions,
+        permittivity: &Permittivity,
+    ) -> Vec<Box<dyn HelmholtzEnergy>> {
         let mut contributions: Vec<Box<dyn HelmholtzEnergy>> = Vec::with_capacity(7);
         contributions.push(Box::new(HardSphere {
             parameters: parameters.clone(),
@@ -63,29 +100,62 @@ impl GcPcSaft {
         contributions.push(Box::new(Dispersion {
             parameters: parameters.clone(),
         }));
-        match parameters.assoc_segment.len() {
-            0 => (),
-            1 => contributions.push(Box::new(Association {
+        if single_segment_association(&parameters.assoc_segment, &parameters.sigma3_kappa_aibj) {
+            contributions.push(Box::new(Association {
                 parameters: parameters.clone(),
-            })),
-            _ => contributions.push(Box::new(CrossAssociation {
+            }))
+        } else if !parameters.assoc_segment.is_empty() {
+            contributions.push(Box::new(CrossAssociation {
                 parameters: parameters.clone(),
                 max_iter: options.max_iter_cross_assoc,
                 tol: options.tol_cross_assoc,
-            })),
-        };
+            }))
+        }
         if !parameters.dipole_comp.is_empty() {
-            contributions.push(Box::new(Dipole::new(&parameters)))
+            contributions.push(Box::new(Dipole::new(parameters)))
         }
-        Self {
-            parameters: parameters.clone(),
-            options,
-            contributions,
-            joback: parameters.joback_records.clone().map_or_else(
-                || Joback::default(parameters.chemical_records.len()),
-                Joback::new,
-            ),
+        if !parameters.quadrupole_comp.is_empty() {
+            contributions.push(Box::new(Quadrupole::new(parameters)))
+        }
+        if !parameters.dipole_comp.is_empty() && !parameters.quadrupole_comp.is_empty() {
+            contributions.push(Box::new(DipoleQuadrupole::new(parameters)))
+        }
+        if !parameters.ion_segment.is_empty() {
+            contributions.push(Box::new(DebyeHueckel {
+                parameters: parameters.clone(),
+                permittivity: permittivity.clone(),
+            }));
+            if options.born {
+                contributions.push(Box::new(Born {
+                    parameters: parameters.clone(),
+                    permittivity: permittivity.clone(),
+                }));
+            }
+        }
+        contributions
+    }
+
+    /// Replace the ideal gas contribution, which defaults to the Joback
+    /// model for all components for which Joback records are available.
+    pub fn with_ideal_gas(mut self, ideal_gas: GcIdealGas) -> EosResult<Self> {
+        if ideal_gas.models.len() != self.components() {
+            return Err(EosError::IncompatibleComponents(
+                self.components(),
+                ideal_gas.models.len(),
+            ));
         }
+        self.ideal_gas = ideal_gas;
+        Ok(self)
+    }
+
+    /// Replace the model for the relative permittivity in the electrostatic
+    /// contributions, which defaults to the constant permittivity of water
+    /// at 298.15 K.
+    pub fn with_permittivity(mut self, permittivity: Permittivity) -> EosResult<Self> {
+        permittivity.check_components(self.components())?;
+        self.contributions = Self::contributions(&self.parameters, self.options, &permittivity);
+        self.permittivity = permittivity;
+        Ok(self)
     }
 }
 
@@ -95,10 +165,14 @@ impl EquationOfState for GcPcSaft {
     }
 
     fn subset(&self, component_list: &[usize]) -> Self {
-        Self::with_options(
-            Rc::new(self.parameters.subset(component_list)),
-            self.options,
-        )
+        let parameters = Arc::new(self.parameters.subset(component_list));
+        let permittivity = self.permittivity.subset(component_list);
+        Self {
+            contributions: Self::contributions(&parameters, self.options, &permittivity),
+            ideal_gas: self.ideal_gas.subset(component_list),
+            permittivity,
+            ..Self::with_options(parameters, self.options)
+        }
     }
 
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
@@ -113,12 +187,287 @@ impl EquationOfState for GcPcSaft {
     }
 
     fn ideal_gas(&self) -> &dyn IdealGasContribution {
-        &self.joback
+        &self.ideal_gas
     }
 }
 
+// `GcPcSaft` itself is neither `Send` nor `Sync`, because `feos-core` stores
+// the contributions as trait objects without these bounds. Threads share the
+// parameters and models instead and construct their own equation of state.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<GcPcSaftEosParameters>();
+    assert_send_sync::<GcPcSaftOptions>();
+    assert_send_sync::<GcIdealGas>();
+    assert_send_sync::<Permittivity>();
+};
+
 impl MolarWeight<SIUnit> for GcPcSaft {
     fn molar_weight(&self) -> SIArray1 {
         self.parameters.molarweight.clone() * GRAM / MOL
     }
 }
+
+fn omega11(t: f64) -> f64 {
+    1.06036 * t.powf(-0.15610)
+        + 0.19300 * (-0.47635 * t).exp()
+        + 1.03587 * (-1.52996 * t).exp()
+        + 1.76474 * (-3.89411 * t).exp()
+}
+
+fn omega22(t: f64) -> f64 {
+    1.16145 * t.powf(-0.14874) + 0.52487 * (-0.77320 * t).exp() + 2.16178 * (-2.43787 * t).exp()
+        - 6.435e-4 * t.powf(0.14874) * (18.0323 * t.powf(-0.76830) - 7.27371).sin()
+}
+
+fn missing_coefficients(property: &str) -> EosError {
+    EosError::ParameterError(ParameterError::IncompatibleParameters(format!(
+        "Missing {} coefficients.",
+        property
+    )))
+}
+
+/// Entropy scaling using the molecular parameters `m_mix`, `sigma_mix` and
+/// `epsilon_k_mix` and the component coefficients that are combined from the
+/// segment coefficients.
+impl EntropyScaling<SIUnit> for GcPcSaft {
+    fn viscosity_reference(
+        &self,
+        temperature: SINumber,
+        _: SINumber,
+        moles: &SIArray1,
+    ) -> EosResult<SINumber> {
+        let p = &self.parameters;
+        let mw = &p.molarweight;
+        let x = moles.to_reduced(moles.sum())?;
+        let ce = (0..self.components())
+            .map(|i| {
+                let tr = temperature.to_reduced(p.epsilon_k_mix[i] * KELVIN)?;
+                Ok(
+                    5.0 / 16.0 * (mw[i] * GRAM / MOL * KB / NAV * temperature / PI).sqrt()?
+                        / omega22(tr)
+                        / (p.sigma_mix[i] * ANGSTROM).powi(2),
+                )
+            })
+            .collect::<EosResult<Vec<_>>>()?;
+        let mut ce_mix = 0.0 * MILLI * PASCAL * SECOND;
+        for i in 0..self.components() {
+            let denom = (0..self.components())
+                .map(|j| {
+                    Ok(x[j]
+                        * (1.0
+                            + (ce[i] / ce[j]).into_value()?.sqrt()
+                                * (mw[j] / mw[i]).powf(1.0 / 4.0))
+                        .powi(2)
+                        / (8.0 * (1.0 + mw[i] / mw[j])).sqrt())
+                })
+                .sum::<EosResult<f64>>()?;
+            ce_mix += ce[i] * x[i] / denom;
+        }
+        Ok(ce_mix)
+    }
+
+    fn viscosity_correlation(&self, s_res: f64, x: &Array1<f64>) -> EosResult<f64> {
+        let p = &self.parameters;
+        let coefficients = p
+            .viscosity
+            .as_ref()
+            .ok_or_else(|| missing_coefficients("viscosity"))?;
+        let m = (x * &p.m_mix).sum();
+        let s = s_res / m;
+        let pref = (x * &p.m_mix) / m;
+        let a = (&coefficients.row(0) * x).sum();
+        let b = (&coefficients.row(1) * &pref).sum();
+        let c = (&coefficients.row(2) * &pref).sum();
+        let d = (&coefficients.row(3) * &pref).sum();
+        Ok(a + b * s + c * s.powi(2) + d * s.powi(3))
+    }
+
+    fn diffusion_reference(
+        &self,
+        temperature: SINumber,
+        volume: SINumber,
+        moles: &SIArray1,
+    ) -> EosResult<SINumber> {
+        if self.components() != 1 {
+            return Err(EosError::IncompatibleComponents(self.components(), 1));
+        }
+        let p = &self.parameters;
+        let density = moles.sum() / volume;
+        let tr = temperature.to_reduced(p.epsilon_k_mix[0] * KELVIN)?;
+        Ok(
+            3.0 / 8.0 / (p.sigma_mix[0] * ANGSTROM).powi(2) / omega11(tr) / (density * NAV)
+                * (temperature * RGAS / PI / (p.molarweight[0] * GRAM / MOL) / p.m_mix[0])
+                    .sqrt()?,
+        )
+    }
+
+    fn diffusion_correlation(&self, s_res: f64, x: &Array1<f64>) -> EosResult<f64> {
+        if self.components() != 1 {
+            return Err(EosError::IncompatibleComponents(self.components(), 1));
+        }
+        let p = &self.parameters;
+        let coefficients = p
+            .diffusion
+            .as_ref()
+            .ok_or_else(|| missing_coefficients("diffusion"))?;
+        let m = (x * &p.m_mix).sum();
+        let s = s_res / m;
+        let pref = (x * &p.m_mix) / m;
+        let a = (&coefficients.row(0) * x).sum();
+        let b = (&coefficients.row(1) * &pref).sum();
+        let c = (&coefficients.row(2) * &pref).sum();
+        let d = (&coefficients.row(3) * &pref).sum();
+        let e = (&coefficients.row(4) * &pref).sum();
+        Ok(a + b * s - c * (1.0 - s.exp()) * s.powi(2) - d * s.powi(4) - e * s.powi(8))
+    }
+
+    // Equation 4 of DOI: 10.1021/acs.iecr.9b04289
+    fn thermal_conductivity_reference(
+        &self,
+        temperature: SINumber,
+        volume: SINumber,
+        moles: &SIArray1,
+    ) -> EosResult<SINumber> {
+        if self.components() != 1 {
+            return Err(EosError::IncompatibleComponents(self.components(), 1));
+        }
+        let p = &self.parameters;
+        let (m, sigma, epsilon_k) = (p.m_mix[0], p.sigma_mix[0], p.epsilon_k_mix[0]);
+        let eos = Rc::new(self.subset(&[0]));
+        let state = State::new_nvt(&eos, temperature, volume, moles)?;
+        let s_res_reduced = state
+            .molar_entropy(Contributions::ResidualNvt)
+            .to_reduced(RGAS)?
+            / m;
+        let t = temperature.to_reduced(KELVIN)?;
+        let tr = t / epsilon_k;
+        let ref_ce = 0.083235 * (t * m / p.molarweight[0]).sqrt() / sigma.powi(2) / omega22(tr)
+            * WATT
+            / METER
+            / KELVIN;
+        let alpha_visc = (2.0 * s_res_reduced).exp();
+        let ref_ts = (-0.0167141 * tr / m + 0.0470581 * (tr / m).powi(2))
+            * (m * m * sigma.powi(3) * epsilon_k)
+            * 1e-5
+            * WATT
+            / METER
+            / KELVIN;
+        Ok(ref_ce + ref_ts * alpha_visc)
+    }
+
+    fn thermal_conductivity_correlation(&self, s_res: f64, x: &Array1<f64>) -> EosResult<f64> {
+        if self.components() != 1 {
+            return Err(EosError::IncompatibleComponents(self.components(), 1));
+        }
+        let p = &self.parameters;
+        let coefficients = p
+            .thermal_conductivity
+            .as_ref()
+            .ok_or_else(|| missing_coefficients("thermal conductivity"))?;
+        let m = (x * &p.m_mix).sum();
+        let s = s_res / m;
+        let a = (&coefficients.row(0) * x).sum();
+        let b = (&coefficients.row(1) * x).sum();
+        let c = (&coefficients.row(2) * x).sum();
+        let d = (&coefficients.row(3) * x).sum();
+        Ok(a + b * s + c * (1.0 - s.exp()) + d * s.powi(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parameter::test::*;
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+
+    #[test]
+    fn test_entropy_scaling() -> EosResult<()> {
+        let eos = Rc::new(GcPcSaft::new(Arc::new(propanol_entropy_scaling())));
+        let t = 300.0 * KELVIN;
+        let state = State::new_nvt(&eos, t, 1.0 * METER.powi(3), &(arr1(&[15000.0]) * MOL))?;
+        let s_res = state
+            .molar_entropy(Contributions::ResidualNvt)
+            .to_reduced(RGAS)?;
+        assert_relative_eq!(
+            state.viscosity()?,
+            state.viscosity_reference()? * eos.viscosity_correlation(s_res, &arr1(&[1.0]))?.exp()
+        );
+        assert!(state.viscosity()? > 0.0 * PASCAL * SECOND);
+        assert!(state.diffusion()? > 0.0 * METER.powi(2) / SECOND);
+        assert!(state.thermal_conductivity()? > 0.0 * WATT / METER / KELVIN);
+
+        // the viscosity of a mixture of a component with itself does not depend on the composition
+        let parameters = propanol_entropy_scaling().subset(&[0, 0]);
+        let mixture = Rc::new(GcPcSaft::new(Arc::new(parameters)));
+        let state_mix = State::new_nvt(
+            &mixture,
+            t,
+            1.0 * METER.powi(3),
+            &(arr1(&[5000.0, 10000.0]) * MOL),
+        )?;
+        assert_relative_eq!(
+            state_mix.viscosity()?,
+            state.viscosity()?,
+            max_relative = 1e-10
+        );
+        assert!(state_mix.diffusion().is_err());
+
+        // missing coefficients
+        let eos = Rc::new(GcPcSaft::new(Arc::new(propanol())));
+        let state = State::new_nvt(&eos, t, 1.0 * METER.powi(3), &(arr1(&[15000.0]) * MOL))?;
+        assert!(state.viscosity().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ideal_gas() -> EosResult<()> {
+        let ideal_gas = GcIdealGas::new(vec![
+            IdealGasModel::HeatCapacity([40.0, 0.1, 0.0, 0.0, 0.0]),
+            IdealGasModel::Default,
+        ]);
+        let eos = GcPcSaft::new(Arc::new(ethanol_propanol(false)));
+        assert!(eos.subset(&[0]).with_ideal_gas(ideal_gas.clone()).is_err());
+
+        let eos = Rc::new(eos.with_ideal_gas(ideal_gas)?.subset(&[0]));
+        let t = 300.0 * KELVIN;
+        let state = State::new_nvt(&eos, t, 1.0 * METER.powi(3), &(arr1(&[10.0]) * MOL))?;
+        assert_relative_eq!(
+            state.c_p(Contributions::IdealGas),
+            (40.0 + 0.1 * 300.0) * JOULE / MOL / KELVIN,
+            max_relative = 1e-6
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_homosegmented() -> EosResult<()> {
+        // a single segment has no bonds in the heterosegmented model, whereas
+        // the homosegmented model adds the chain term of PC-SAFT
+        let hetero = Arc::new(propane_co2().subset(&[1]));
+        let homo = Arc::new(hetero.homosegmented());
+        let t = 300.0 * KELVIN;
+        let v = 1e-3 * METER.powi(3);
+        let n = arr1(&[5.0]) * MOL;
+        let a = |p: &Arc<GcPcSaftEosParameters>| -> EosResult<_> {
+            let eos = Rc::new(GcPcSaft::new(p.clone()));
+            Ok(State::new_nvt(&eos, t, v, &n)?.helmholtz_energy(Contributions::ResidualNvt))
+        };
+
+        let (m, sigma, epsilon_k) = (1.5131, 3.1869, 163.33);
+        let d = sigma * (1.0 - 0.12 * (-3.0 * epsilon_k / 300.0f64).exp());
+        let rho = 5.0 / 1e-3 * 6.02214076e23 * 1e-30;
+        let zeta2 = FRAC_PI_6 * rho * m * d.powi(2);
+        let zeta3 = FRAC_PI_6 * rho * m * d.powi(3);
+        let g = 1.0 / (1.0 - zeta3)
+            + 1.5 * d * zeta2 / (1.0 - zeta3).powi(2)
+            + 0.5 * (d * zeta2).powi(2) / (1.0 - zeta3).powi(3);
+        assert_relative_eq!(
+            a(&homo)? - a(&hetero)?,
+            -(m - 1.0) * g.ln() * 5.0 * MOL * RGAS * t,
+            max_relative = 1e-6
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
-use crate::record::GcPcSaftRecord;
+use super::ionic::PermittivityWeights;
+use crate::record::{AssociationRecord, GcPcSaftBinaryRecord, GcPcSaftRecord, PcSaftRecord};
+use crate::second_order::{self, SecondOrderRecord};
 use feos_core::joback::JobackRecord;
 use feos_core::parameter::{
-    BinaryRecord, ChemicalRecord, FromSegments, IdentifierOption, ParameterError, SegmentRecord,
+    BinaryRecord, ChemicalRecord, FromSegments, IdentifierOption, ParameterError, PureRecord,
+    SegmentRecord,
 };
 use indexmap::{IndexMap, IndexSet};
 use ndarray::{Array1, Array2};
@@ -12,10 +15,11 @@ use std::io::BufReader;
 use std::path::Path;
 
 /// Parameter set required for the gc-PC-SAFT equation of state.
+#[derive(Clone)]
 pub struct GcPcSaftEosParameters {
     pub molarweight: Array1<f64>,
     pub component_index: Array1<usize>,
-    identifiers: Vec<String>,
+    pub identifiers: Vec<String>,
 
     pub m: Array1<f64>,
     pub sigma: Array1<f64>,
@@ -24,35 +28,70 @@ pub struct GcPcSaftEosParameters {
 
     pub assoc_segment: Array1<usize>,
     pub n: Array1<f64>,
-    kappa_ab: Array1<f64>,
-    epsilon_k_ab: Array1<f64>,
-    pub na: Array1<f64>,
-    pub nb: Array1<f64>,
+    pub site_types: Vec<String>,
+    pub n_sites: Array1<f64>,
+
+    pub ion_segment: Array1<usize>,
+    pub z: Array1<f64>,
+    pub n_ion: Array1<f64>,
+    pub(crate) permittivity_weights: PermittivityWeights,
 
     pub dipole_comp: Array1<usize>,
     mu: Array1<f64>,
     pub mu2: Array1<f64>,
+    pub quadrupole_comp: Array1<usize>,
+    q: Array1<f64>,
+    pub q2: Array1<f64>,
     pub m_mix: Array1<f64>,
+    pub sigma_mix: Array1<f64>,
+    pub epsilon_k_mix: Array1<f64>,
     pub s_ij: Array2<f64>,
     pub e_k_ij: Array2<f64>,
 
     pub k_ij: Array2<f64>,
+    pub k_ij_t: Array2<f64>,
+    pub k_ij_ln_t: Array2<f64>,
+    pub l_ij: Array2<f64>,
     pub sigma_ij: Array2<f64>,
-    pub epsilon_k_ij: Array2<f64>,
     pub sigma3_kappa_aibj: Array2<f64>,
     pub epsilon_k_aibj: Array2<f64>,
 
+    pub viscosity: Option<Array2<f64>>,
+    pub diffusion: Option<Array2<f64>>,
+    pub thermal_conductivity: Option<Array2<f64>>,
+
     pub chemical_records: Vec<ChemicalRecord>,
     segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
-    binary_segment_records: Option<Vec<BinaryRecord<String, f64>>>,
-    pub joback_records: Option<Vec<JobackRecord>>,
+    binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+    second_order_records: Vec<SecondOrderRecord>,
+    pub joback_records: Vec<Option<JobackRecord>>,
+    homosegmented: bool,
 }
 
 impl GcPcSaftEosParameters {
     pub fn from_segments(
         chemical_records: Vec<ChemicalRecord>,
         segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
-        binary_segment_records: Option<Vec<BinaryRecord<String, f64>>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+    ) -> Result<Self, ParameterError> {
+        Self::from_segments_with_second_order(
+            chemical_records,
+            segment_records,
+            binary_segment_records,
+            vec![],
+        )
+    }
+
+    /// Creates parameters from segments with second-order group corrections.
+    ///
+    /// Second-order groups are detected in the bond graphs of the chemical
+    /// records and correct the parameters of the first segment of every
+    /// occurrence. Chemical records without bond information are not corrected.
+    pub fn from_segments_with_second_order(
+        chemical_records: Vec<ChemicalRecord>,
+        segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+        second_order_records: Vec<SecondOrderRecord>,
     ) -> Result<Self, ParameterError> {
         let segment_map: IndexMap<_, _> = segment_records
             .iter()
@@ -68,30 +107,46 @@ impl GcPcSaftEosParameters {
         let mut bonds = IndexMap::with_capacity(segment_records.len());
         let mut assoc_segment = Vec::new();
         let mut n = Vec::new();
-        let mut kappa_ab = Vec::new();
-        let mut epsilon_k_ab = Vec::new();
-        let mut na = Vec::new();
-        let mut nb = Vec::new();
+        let mut site_types = Vec::new();
+        let mut n_sites = Vec::new();
+        let mut site_records = Vec::new();
+
+        let mut ion_segment = Vec::new();
+        let mut z = Vec::new();
+        let mut n_ion = Vec::new();
 
         let mut dipole_comp = Vec::new();
         let mut mu = Vec::new();
         let mut mu2 = Vec::new();
+        let mut quadrupole_comp = Vec::new();
+        let mut q = Vec::new();
+        let mut q2 = Vec::new();
         let mut m_mix = Vec::new();
         let mut sigma_mix = Vec::new();
         let mut epsilon_k_mix = Vec::new();
 
+        let mut viscosity = Vec::new();
+        let mut diffusion = Vec::new();
+        let mut thermal_conductivity = Vec::new();
+
         let mut joback_records = Vec::new();
 
         for (i, chemical_record) in chemical_records.iter().enumerate() {
-            let mut segment_indices = IndexMap::with_capacity(segment_records.len());
-            let (segment_counts, bond_counts) = chemical_record.segment_and_bond_count();
-            let count: IndexMap<_, _> = segment_counts
+            let mut segment_indices = Vec::new();
+            let (segment_counts, bond_counts) =
+                second_order::segment_and_bond_count(chemical_record, &second_order_records);
+            let count: Vec<_> = segment_counts
                 .iter()
-                .map(|(id, &count)| {
+                .map(|(id, corrections, count)| {
                     let segment = segment_map
                         .get(id)
                         .ok_or_else(|| ParameterError::ComponentsNotFound(id.clone()))?;
-                    Ok((segment, count))
+                    let segment = second_order::corrected_segment(
+                        segment,
+                        corrections,
+                        &second_order_records,
+                    );
+                    Ok((segment, *count))
                 })
                 .collect::<Result<_, ParameterError>>()?;
 
@@ -99,9 +154,11 @@ impl GcPcSaftEosParameters {
             let mut sigma_i = 0.0;
             let mut epsilon_k_i = 0.0;
             let mut mu2_i = 0.0;
+            let mut q2_i = 0.0;
+            let mut z_i = 0.0;
 
             for (segment, count) in count.iter() {
-                segment_indices.insert(segment.identifier.clone(), m.len());
+                segment_indices.push(m.len());
 
                 molarweight[i] += segment.molarweight * count;
 
@@ -111,16 +168,21 @@ impl GcPcSaftEosParameters {
                 sigma.push(segment.model_record.sigma);
                 epsilon_k.push(segment.model_record.epsilon_k);
 
-                if let (Some(k), Some(e)) = (
-                    segment.model_record.kappa_ab,
-                    segment.model_record.epsilon_k_ab,
-                ) {
-                    assoc_segment.push(m.len() - 1);
-                    n.push(*count);
-                    kappa_ab.push(k);
-                    epsilon_k_ab.push(e);
-                    na.push(segment.model_record.na.unwrap_or(1.0));
-                    nb.push(segment.model_record.nb.unwrap_or(1.0));
+                if let Some(record) = segment.model_record.association_record() {
+                    for site in &record.sites {
+                        assoc_segment.push(m.len() - 1);
+                        n.push(*count);
+                        site_types.push(site.id.clone());
+                        n_sites.push(site.n);
+                        site_records.push(record.clone());
+                    }
+                }
+
+                if let Some(z_s) = segment.model_record.z.filter(|&z| z != 0.0) {
+                    ion_segment.push(m.len() - 1);
+                    z.push(z_s);
+                    n_ion.push(*count);
+                    z_i += z_s * count;
                 }
 
                 m_i += segment.model_record.m * count;
@@ -129,30 +191,77 @@ impl GcPcSaftEosParameters {
                 if let Some(mu) = segment.model_record.mu {
                     mu2_i += mu.powi(2) * count;
                 }
+                if let Some(q) = segment.model_record.q {
+                    q2_i += q.powi(2) * count;
+                }
             }
 
+            // Entropy scaling: group contribution method for the viscosity
+            // according to Lötgering-Lin and Gross (2015), in which the
+            // coefficients A and B are weighted with the segment volumes
+            // m_α σ_α³ and B is normalized with the molecular volume.
+            let volume_i: f64 = count
+                .iter()
+                .map(|(s, n)| n * s.model_record.m * s.model_record.sigma.powi(3))
+                .sum();
+            viscosity.push(
+                entropy_scaling_coefficients(count.iter().map(|(s, n)| {
+                    let volume = s.model_record.m * s.model_record.sigma.powi(3);
+                    let coefficients = s
+                        .model_record
+                        .viscosity
+                        .map(|[a, b, c, d]| [a * volume, b * volume, c, d]);
+                    (coefficients, *n)
+                }))
+                .map(|mut v| {
+                    v[1] /= volume_i.powf(0.45);
+                    v
+                }),
+            );
+            diffusion.push(entropy_scaling_coefficients(
+                count.iter().map(|(s, n)| (s.model_record.diffusion, *n)),
+            ));
+            thermal_conductivity.push(entropy_scaling_coefficients(
+                count
+                    .iter()
+                    .map(|(s, n)| (s.model_record.thermal_conductivity, *n)),
+            ));
+
+            if z_i.abs() > 1e-10 {
+                let identifier = chemical_record.identifier();
+                return Err(ParameterError::IncompatibleParameters(format!(
+                    "component {} is not electroneutral (net charge {})",
+                    identifier.name.as_ref().unwrap_or(&identifier.cas),
+                    z_i
+                )));
+            }
+
+            m_mix.push(m_i);
+            sigma_mix.push((sigma_i / m_i).cbrt());
+            epsilon_k_mix.push(epsilon_k_i / m_i);
+
             if mu2_i > 0.0 {
                 dipole_comp.push(i);
                 mu.push(mu2_i.sqrt());
                 mu2.push(mu2_i / m_i * (1e-19 * (JOULE / KELVIN / KB).into_value().unwrap()));
-                m_mix.push(m_i);
-                sigma_mix.push((sigma_i / m_i).cbrt());
-                epsilon_k_mix.push(epsilon_k_i / m_i);
             }
 
-            for (b, &count) in bond_counts.iter() {
-                let i1 = segment_indices.get(&b[0]);
-                let i2 = segment_indices.get(&b[1]);
-                if let (Some(&i1), Some(&i2)) = (i1, i2) {
-                    let indices = if i1 > i2 { [i2, i1] } else { [i1, i2] };
-                    let bond = bonds.entry(indices).or_insert(0.0);
-                    *bond += count;
-                }
+            if q2_i > 0.0 {
+                quadrupole_comp.push(i);
+                q.push(q2_i.sqrt());
+                q2.push(q2_i / m_i * (1e-19 * (JOULE / KELVIN / KB).into_value().unwrap()));
+            }
+
+            for (&[b1, b2], &count) in bond_counts.iter() {
+                let bond = bonds
+                    .entry([segment_indices[b1], segment_indices[b2]])
+                    .or_insert(0.0);
+                *bond += count;
             }
 
             let ideal_gas_segments: Option<Vec<_>> = count
                 .iter()
-                .map(|(s, &n)| s.ideal_gas_record.clone().map(|ig| (ig, n)))
+                .map(|(s, n)| s.ideal_gas_record.clone().map(|ig| (ig, *n)))
                 .collect();
 
             joback_records.push(
@@ -164,6 +273,9 @@ impl GcPcSaftEosParameters {
 
         // Binary interaction parameter
         let mut k_ij = Array2::zeros([epsilon_k.len(); 2]);
+        let mut k_ij_t = Array2::zeros([epsilon_k.len(); 2]);
+        let mut k_ij_ln_t = Array2::zeros([epsilon_k.len(); 2]);
+        let mut l_ij = Array2::zeros([epsilon_k.len(); 2]);
         if let Some(binary_segment_records) = binary_segment_records.as_ref() {
             let mut binary_segment_records_map = IndexMap::new();
             for binary_record in binary_segment_records {
@@ -179,8 +291,11 @@ impl GcPcSaftEosParameters {
             for (i, id1) in identifiers.iter().enumerate() {
                 for (j, id2) in identifiers.iter().cloned().enumerate() {
                     if component_index[i] != component_index[j] {
-                        if let Some(k) = binary_segment_records_map.get(&(id1.clone(), id2)) {
-                            k_ij[(i, j)] = *k;
+                        if let Some(b) = binary_segment_records_map.get(&(id1.clone(), id2)) {
+                            k_ij[(i, j)] = b.k_ij;
+                            k_ij_t[(i, j)] = b.k_ij_t;
+                            k_ij_ln_t[(i, j)] = b.k_ij_ln_t;
+                            l_ij[(i, j)] = b.l_ij;
                         }
                     }
                 }
@@ -188,32 +303,36 @@ impl GcPcSaftEosParameters {
         }
 
         // Combining rules dispersion
-        let sigma_ij =
-            Array2::from_shape_fn([sigma.len(); 2], |(i, j)| 0.5 * (sigma[i] + sigma[j]));
-        let epsilon_k_ij = Array2::from_shape_fn([epsilon_k.len(); 2], |(i, j)| {
-            (epsilon_k[i] * epsilon_k[j]).sqrt()
-        }) * (1.0 - &k_ij);
+        let sigma_ij = Array2::from_shape_fn([sigma.len(); 2], |(i, j)| {
+            0.5 * (sigma[i] + sigma[j]) * (1.0 - l_ij[(i, j)])
+        });
 
         // Combining rules polar
-        let s_ij = Array2::from_shape_fn([dipole_comp.len(); 2], |(i, j)| {
+        let s_ij = Array2::from_shape_fn([m_mix.len(); 2], |(i, j)| {
             0.5 * (sigma_mix[i] + sigma_mix[j])
         });
-        let e_k_ij = Array2::from_shape_fn([dipole_comp.len(); 2], |(i, j)| {
+        let e_k_ij = Array2::from_shape_fn([m_mix.len(); 2], |(i, j)| {
             (epsilon_k_mix[i] * epsilon_k_mix[j]).sqrt()
         });
 
         // Association
-        let sigma3_kappa_aibj = Array2::from_shape_fn([kappa_ab.len(); 2], |(i, j)| {
-            (sigma[assoc_segment[i]] * sigma[assoc_segment[j]]).powf(1.5)
-                * (kappa_ab[i] * kappa_ab[j]).sqrt()
-        });
-        let epsilon_k_aibj = Array2::from_shape_fn([epsilon_k_ab.len(); 2], |(i, j)| {
-            0.5 * (epsilon_k_ab[i] + epsilon_k_ab[j])
-        });
+        let (sigma3_kappa_aibj, epsilon_k_aibj) = association_parameters(
+            &assoc_segment,
+            &site_types,
+            &site_records,
+            &sigma,
+            &identifiers,
+            binary_segment_records.as_deref(),
+        );
+
+        let component_index = Array1::from_vec(component_index);
+        let ion_segment = Array1::from_vec(ion_segment);
+        let permittivity_weights =
+            PermittivityWeights::new(&component_index, &ion_segment, chemical_records.len());
 
         Ok(Self {
             molarweight,
-            component_index: Array1::from_vec(component_index),
+            component_index,
             identifiers,
             n: Array1::from_vec(n),
             m: Array1::from_vec(m),
@@ -221,25 +340,39 @@ impl GcPcSaftEosParameters {
             epsilon_k: Array1::from_vec(epsilon_k),
             bonds,
             assoc_segment: Array1::from_vec(assoc_segment),
-            kappa_ab: Array1::from_vec(kappa_ab),
-            epsilon_k_ab: Array1::from_vec(epsilon_k_ab),
-            na: Array1::from_vec(na),
-            nb: Array1::from_vec(nb),
+            site_types,
+            n_sites: Array1::from_vec(n_sites),
+            ion_segment,
+            z: Array1::from_vec(z),
+            n_ion: Array1::from_vec(n_ion),
+            permittivity_weights,
             dipole_comp: Array1::from_vec(dipole_comp),
             mu: Array1::from_vec(mu),
             mu2: Array1::from_vec(mu2),
+            quadrupole_comp: Array1::from_vec(quadrupole_comp),
+            q: Array1::from_vec(q),
+            q2: Array1::from_vec(q2),
             m_mix: Array1::from_vec(m_mix),
+            sigma_mix: Array1::from_vec(sigma_mix),
+            epsilon_k_mix: Array1::from_vec(epsilon_k_mix),
             s_ij,
             e_k_ij,
             k_ij,
+            k_ij_t,
+            k_ij_ln_t,
+            l_ij,
             sigma_ij,
-            epsilon_k_ij,
             sigma3_kappa_aibj,
             epsilon_k_aibj,
+            viscosity: coefficient_matrix(viscosity),
+            diffusion: coefficient_matrix(diffusion),
+            thermal_conductivity: coefficient_matrix(thermal_conductivity),
             chemical_records,
             segment_records,
             binary_segment_records,
-            joback_records: joback_records.into_iter().collect(),
+            second_order_records,
+            joback_records,
+            homosegmented: false,
         })
     }
 
@@ -294,8 +427,10 @@ impl GcPcSaftEosParameters {
         let binary_records = file_binary
             .map(|file_binary| {
                 let reader = BufReader::new(File::open(file_binary)?);
-                let binary_records: Result<Vec<BinaryRecord<String, f64>>, ParameterError> =
-                    Ok(serde_json::from_reader(reader)?);
+                let binary_records: Result<
+                    Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>,
+                    ParameterError,
+                > = Ok(serde_json::from_reader(reader)?);
                 binary_records
             })
             .transpose()?;
@@ -308,12 +443,189 @@ impl GcPcSaftEosParameters {
             .iter()
             .map(|&i| self.chemical_records[i].clone())
             .collect();
-        Self::from_segments(
+        let parameters = Self::from_segments_with_second_order(
             chemical_records,
             self.segment_records.clone(),
             self.binary_segment_records.clone(),
+            self.second_order_records.clone(),
         )
-        .unwrap()
+        .unwrap();
+        if self.homosegmented {
+            parameters.homosegmented()
+        } else {
+            parameters
+        }
+    }
+
+    /// Parameter set in which every segment is treated as a separate component.
+    ///
+    /// Evaluated at segment amounts that are equal to the amount of the
+    /// corresponding component, the residual Helmholtz energy agrees with that
+    /// of the original parameter set. The dipole and quadrupole moments of a
+    /// component are assigned to its first segment with a dipole or
+    /// quadrupole moment. The weights of a component in the permittivity
+    /// models are shared equally between its segments.
+    pub(crate) fn segment_resolved(&self) -> Self {
+        let comp = &self.component_index;
+        let first_segment = |comp_list: &Array1<usize>, polar: &dyn Fn(&GcPcSaftRecord) -> bool| {
+            comp_list.mapv(|c| {
+                (0..comp.len())
+                    .find(|&i| {
+                        comp[i] == c
+                            && self.segment_records.iter().any(|s| {
+                                s.identifier == self.identifiers[i] && polar(&s.model_record)
+                            })
+                    })
+                    // moments that stem from second-order corrections only
+                    .or_else(|| (0..comp.len()).find(|&i| comp[i] == c))
+                    .unwrap()
+            })
+        };
+        let mut p = self.clone();
+        p.dipole_comp = first_segment(&self.dipole_comp, &|r| r.mu.map_or(false, |mu| mu != 0.0));
+        p.quadrupole_comp =
+            first_segment(&self.quadrupole_comp, &|r| r.q.map_or(false, |q| q != 0.0));
+        p.molarweight = comp.mapv(|c| self.molarweight[c]);
+        p.m_mix = comp.mapv(|c| self.m_mix[c]);
+        p.sigma_mix = comp.mapv(|c| self.sigma_mix[c]);
+        p.epsilon_k_mix = comp.mapv(|c| self.epsilon_k_mix[c]);
+        p.s_ij = Array2::from_shape_fn([comp.len(); 2], |(i, j)| self.s_ij[(comp[i], comp[j])]);
+        p.e_k_ij = Array2::from_shape_fn([comp.len(); 2], |(i, j)| self.e_k_ij[(comp[i], comp[j])]);
+        p.permittivity_weights = self.permittivity_weights.segment_resolved(comp);
+        p.component_index = Array1::from_iter(0..comp.len());
+        p.joback_records = vec![None; comp.len()];
+        p
+    }
+
+    /// Parameter set of the homosegmented gc-PC-SAFT model.
+    ///
+    /// The segments of every component are collapsed into a single segment
+    /// with the parameters `m_mix`, `sigma_mix` and `epsilon_k_mix` obtained
+    /// from the group sums, so that the model reduces to PC-SAFT with
+    /// $m_i-1$ bonds per molecule. The association sites and charges of all
+    /// segments are assigned to the collapsed segment. Binary interaction
+    /// parameters of the components are averaged over all pairs of segments
+    /// weighted by their segment numbers.
+    pub fn homosegmented(&self) -> Self {
+        let comp = &self.component_index;
+        let components = self.m_mix.len();
+        let mut p = self.clone();
+        p.homosegmented = true;
+        p.identifiers = self
+            .chemical_records
+            .iter()
+            .map(|r| {
+                let identifier = r.identifier();
+                identifier.name.as_ref().unwrap_or(&identifier.cas).clone()
+            })
+            .collect();
+        p.component_index = Array1::from_iter(0..components);
+        p.m = self.m_mix.clone();
+        p.sigma = self.sigma_mix.clone();
+        p.epsilon_k = self.epsilon_k_mix.clone();
+        p.bonds = (0..components)
+            .map(|i| ([i, i], self.m_mix[i] - 1.0))
+            .collect();
+        p.assoc_segment = self.assoc_segment.mapv(|a| comp[a]);
+        p.ion_segment = self.ion_segment.mapv(|a| comp[a]);
+
+        // segment number weighted averages of the binary interaction parameters
+        let average = |x: &Array2<f64>| {
+            let mut x_mix = Array2::zeros([components; 2]);
+            for ((a, b), &x_ab) in x.indexed_iter() {
+                x_mix[(comp[a], comp[b])] += self.m[a] * self.m[b] * x_ab;
+            }
+            x_mix / Array2::from_shape_fn([components; 2], |(i, j)| self.m_mix[i] * self.m_mix[j])
+        };
+        p.k_ij = average(&self.k_ij);
+        p.k_ij_t = average(&self.k_ij_t);
+        p.k_ij_ln_t = average(&self.k_ij_ln_t);
+        p.l_ij = average(&self.l_ij);
+        p.sigma_ij = Array2::from_shape_fn([components; 2], |(i, j)| {
+            0.5 * (p.sigma[i] + p.sigma[j]) * (1.0 - p.l_ij[(i, j)])
+        });
+
+        // the association volumes refer to the diameters of the collapsed segments
+        let a = &self.assoc_segment;
+        p.sigma3_kappa_aibj = Array2::from_shape_fn(self.sigma3_kappa_aibj.raw_dim(), |(i, j)| {
+            self.sigma3_kappa_aibj[(i, j)]
+                * (p.sigma[comp[a[i]]] * p.sigma[comp[a[j]]]
+                    / (self.sigma[a[i]] * self.sigma[a[j]]))
+                    .powf(1.5)
+        });
+        p
+    }
+
+    /// Equivalent PC-SAFT parameters of every component.
+    ///
+    /// `m`, `sigma` and `epsilon_k` are the group sums `m_mix`, `sigma_mix`
+    /// and `epsilon_k_mix`, dipole and quadrupole moments are combined from
+    /// all segments and the association volumes are rescaled to the diameter
+    /// of the component. The association sites of a component are approximated
+    /// by a single pair of site types A and B with the total number of sites
+    /// and the association parameters averaged over all sites that associate
+    /// with sites of the same segment. Sites of other types than A and B are
+    /// split equally between types A and B.
+    pub fn equivalent_pcsaft_records(&self) -> Vec<PureRecord<PcSaftRecord, JobackRecord>> {
+        (0..self.m_mix.len())
+            .map(|i| {
+                let mu = self.dipole_comp.iter().position(|&c| c == i);
+                let q = self.quadrupole_comp.iter().position(|&c| c == i);
+
+                let (mut kappa_ab, mut epsilon_k_ab, mut weight) = (0.0, 0.0, 0.0);
+                let (mut na, mut nb) = (0.0, 0.0);
+                for (a, &s) in self.assoc_segment.iter().enumerate() {
+                    if self.component_index[s] != i {
+                        continue;
+                    }
+                    let n_a = self.n[a] * self.n_sites[a];
+                    match self.site_types[a].as_str() {
+                        "A" => na += n_a,
+                        "B" => nb += n_a,
+                        _ => {
+                            na += 0.5 * n_a;
+                            nb += 0.5 * n_a;
+                        }
+                    }
+                    let partners: Vec<_> = (0..self.assoc_segment.len())
+                        .filter(|&b| {
+                            self.assoc_segment[b] == s && self.sigma3_kappa_aibj[(a, b)] > 0.0
+                        })
+                        .collect();
+                    for &b in &partners {
+                        let w = n_a / partners.len() as f64;
+                        kappa_ab += w * self.sigma3_kappa_aibj[(a, b)] / self.sigma_mix[i].powi(3);
+                        epsilon_k_ab += w * self.epsilon_k_aibj[(a, b)];
+                        weight += w;
+                    }
+                }
+                let association = (na + nb > 0.0).then(|| {
+                    if weight > 0.0 {
+                        (kappa_ab / weight, epsilon_k_ab / weight)
+                    } else {
+                        (0.0, 0.0)
+                    }
+                });
+
+                let model_record = PcSaftRecord::new(
+                    self.m_mix[i],
+                    self.sigma_mix[i],
+                    self.epsilon_k_mix[i],
+                    mu.map(|d| self.mu[d]),
+                    q.map(|d| self.q[d]),
+                    association.map(|(kappa_ab, _)| kappa_ab),
+                    association.map(|(_, epsilon_k_ab)| epsilon_k_ab),
+                    association.map(|_| na),
+                    association.map(|_| nb),
+                );
+                PureRecord::new(
+                    self.chemical_records[i].identifier().clone(),
+                    self.molarweight[i],
+                    model_record,
+                    self.joback_records[i].clone(),
+                )
+            })
+            .collect()
     }
 
     pub fn to_markdown(&self) -> String {
@@ -321,43 +633,58 @@ impl GcPcSaftEosParameters {
         let o = &mut output;
         write!(
             o,
-            "|component|molarweight|dipole moment|segment|count|$m$|$\\sigma$|$\\varepsilon$|$\\kappa_{{AB}}$|$\\varepsilon_{{AB}}$|$N_A$|$N_B$|$\\mu$|$Q$|\n|-|-|-|-|-|-|-|-|-|-|-|-|-|-|"
+            "|component|molarweight|dipole moment|quadrupole moment|segment|count|$m$|$\\sigma$|$\\varepsilon$|sites|$\\kappa_{{AB}}$|$\\varepsilon_{{AB}}$|$\\mu$|$Q$|\n|-|-|-|-|-|-|-|-|-|-|-|-|-|-|"
         )
         .unwrap();
         for i in 0..self.m.len() {
             let component = if i > 0 && self.component_index[i] == self.component_index[i - 1] {
-                "||".to_string()
+                "|||".to_string()
             } else {
-                let pure = self.chemical_records[self.component_index[i]].identifier();
+                let c = self.component_index[i];
+                let pure = self.chemical_records[c].identifier();
                 format!(
-                    "{}|{}|{}",
+                    "{}|{}|{}|{}",
                     pure.name.as_ref().unwrap_or(&pure.cas),
-                    self.molarweight[self.component_index[i]],
-                    if let Some(d) = self.dipole_comp.iter().position(|&d| d == i) {
+                    self.molarweight[c],
+                    if let Some(d) = self.dipole_comp.iter().position(|&d| d == c) {
                         format!("{}", self.mu[d])
                     } else {
                         "".into()
+                    },
+                    if let Some(q) = self.quadrupole_comp.iter().position(|&q| q == c) {
+                        format!("{}", self.q[q])
+                    } else {
+                        "".into()
                     }
                 )
             };
-            let association = if let Some(a) = self.assoc_segment.iter().position(|&a| a == i) {
-                format!(
-                    "{}|{}|{}|{}",
-                    self.kappa_ab[a], self.epsilon_k_ab[a], self.na[a], self.nb[a]
-                )
-            } else {
-                "|||".to_string()
-            };
+            let association = association_markdown(
+                i,
+                self.assoc_segment.as_slice().unwrap(),
+                &self.site_types,
+                self.n_sites.as_slice().unwrap(),
+                self.sigma[i],
+                &self.sigma3_kappa_aibj,
+                &self.epsilon_k_aibj,
+            );
+            let segment = self
+                .segment_records
+                .iter()
+                .find(|s| s.identifier == self.identifiers[i])
+                .map(|s| &s.model_record);
+            let fmt_option = |x: Option<f64>| x.map_or_else(String::new, |x| x.to_string());
             write!(
                 o,
-                "\n|{}|{}|{}|{}|{}|{}|{}|||",
+                "\n|{}|{}|{}|{}|{}|{}|{}|{}|{}|",
                 component,
                 self.identifiers[i],
                 self.n[i],
                 self.m[i],
                 self.sigma[i],
                 self.epsilon_k[i],
-                association
+                association,
+                fmt_option(segment.and_then(|s| s.mu)),
+                fmt_option(segment.and_then(|s| s.q)),
             )
             .unwrap();
         }
@@ -389,6 +716,145 @@ impl GcPcSaftEosParameters {
     }
 }
 
+/// Association volumes (multiplied by $\sigma^3$) and energies of all pairs
+/// of association sites.
+///
+/// Two site types associate, if the record of at least one of the segments
+/// specifies parameters for the pair. If both records do, the combining
+/// rules are used, otherwise the association is induced according to
+/// Kleiner2007. Binary segment records replace the parameters of all
+/// associating site types of the corresponding segments.
+pub(crate) fn association_parameters(
+    assoc_segment: &[usize],
+    site_types: &[String],
+    site_records: &[AssociationRecord],
+    sigma: &[f64],
+    identifiers: &[String],
+    binary_segment_records: Option<&[BinaryRecord<String, GcPcSaftBinaryRecord>]>,
+) -> (Array2<f64>, Array2<f64>) {
+    let mut binary_segment_records_map = IndexMap::new();
+    for binary_record in binary_segment_records.unwrap_or(&[]) {
+        binary_segment_records_map.insert(
+            (binary_record.id1.clone(), binary_record.id2.clone()),
+            binary_record.model_record,
+        );
+        binary_segment_records_map.insert(
+            (binary_record.id2.clone(), binary_record.id1.clone()),
+            binary_record.model_record,
+        );
+    }
+
+    let nsites = assoc_segment.len();
+    let mut sigma3_kappa_aibj = Array2::zeros([nsites; 2]);
+    let mut epsilon_k_aibj = Array2::zeros([nsites; 2]);
+    for i in 0..nsites {
+        for j in 0..nsites {
+            let (ai, aj) = (assoc_segment[i], assoc_segment[j]);
+            let (si, sj) = (&site_types[i], &site_types[j]);
+            let (mut kappa_aibj, mut epsilon_k) = match (
+                site_records[i].interaction(si, sj),
+                site_records[j].interaction(si, sj),
+            ) {
+                (Some(a), Some(b)) => (
+                    (a.kappa_ab * b.kappa_ab).sqrt(),
+                    0.5 * (a.epsilon_k_ab + b.epsilon_k_ab),
+                ),
+                (Some(a), None) | (None, Some(a)) => (a.kappa_ab, 0.5 * a.epsilon_k_ab),
+                (None, None) => continue,
+            };
+            if let Some(b) =
+                binary_segment_records_map.get(&(identifiers[ai].clone(), identifiers[aj].clone()))
+            {
+                kappa_aibj = b.kappa_ab.unwrap_or(kappa_aibj);
+                epsilon_k = b.epsilon_k_ab.unwrap_or(epsilon_k);
+            }
+            sigma3_kappa_aibj[(i, j)] = (sigma[ai] * sigma[aj]).powf(1.5) * kappa_aibj;
+            epsilon_k_aibj[(i, j)] = epsilon_k;
+        }
+    }
+    (sigma3_kappa_aibj, epsilon_k_aibj)
+}
+
+/// Association sites of a segment and the association parameters of all
+/// pairs of its sites as columns of a markdown table.
+pub(crate) fn association_markdown(
+    segment: usize,
+    assoc_segment: &[usize],
+    site_types: &[String],
+    n_sites: &[f64],
+    sigma: f64,
+    sigma3_kappa_aibj: &Array2<f64>,
+    epsilon_k_aibj: &Array2<f64>,
+) -> String {
+    let sites: Vec<_> = (0..assoc_segment.len())
+        .filter(|&a| assoc_segment[a] == segment)
+        .collect();
+    let mut pairs = Vec::new();
+    for (k, &a) in sites.iter().enumerate() {
+        for &b in &sites[k..] {
+            if sigma3_kappa_aibj[(a, b)] > 0.0 {
+                pairs.push((a, b));
+            }
+        }
+    }
+    format!(
+        "{}|{}|{}",
+        sites
+            .iter()
+            .map(|&a| format!("{} {}", n_sites[a], site_types[a]))
+            .collect::<Vec<_>>()
+            .join(", "),
+        pairs
+            .iter()
+            .map(|&(a, b)| format!(
+                "{}-{}: {}",
+                site_types[a],
+                site_types[b],
+                sigma3_kappa_aibj[(a, b)] / sigma.powi(3)
+            ))
+            .collect::<Vec<_>>()
+            .join(", "),
+        pairs
+            .iter()
+            .map(|&(a, b)| format!(
+                "{}-{}: {}",
+                site_types[a],
+                site_types[b],
+                epsilon_k_aibj[(a, b)]
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Sum of the entropy scaling coefficients of all segments of a component,
+/// if all segments provide them.
+fn entropy_scaling_coefficients<const N: usize>(
+    segments: impl Iterator<Item = (Option<[f64; N]>, f64)>,
+) -> Option<[f64; N]> {
+    segments
+        .map(|(c, n)| c.map(|c| (c, n)))
+        .collect::<Option<Vec<_>>>()
+        .map(|segments| {
+            let mut coefficients = [0.0; N];
+            for (c, n) in segments {
+                for (coefficient, c) in coefficients.iter_mut().zip(c.iter()) {
+                    *coefficient += c * n;
+                }
+            }
+            coefficients
+        })
+}
+
+/// Matrix of entropy scaling coefficients (one column per component),
+/// if the coefficients are available for all components.
+fn coefficient_matrix<const N: usize>(coefficients: Vec<Option<[f64; N]>>) -> Option<Array2<f64>> {
+    coefficients
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .map(|c| Array2::from_shape_fn((N, c.len()), |(k, i)| c[i][k]))
+}
+
 impl std::fmt::Display for GcPcSaftEosParameters {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "GcPcSaftParameters(")?;
@@ -400,15 +866,21 @@ impl std::fmt::Display for GcPcSaftEosParameters {
         write!(f, "\n\tbonds={:?}", self.bonds)?;
         if !self.assoc_segment.is_empty() {
             write!(f, "\n\tassoc_segment={}", self.assoc_segment)?;
-            write!(f, "\n\tkappa_ab={}", self.kappa_ab)?;
-            write!(f, "\n\tepsilon_k_ab={}", self.epsilon_k_ab)?;
-            write!(f, "\n\tna={}", self.na)?;
-            write!(f, "\n\tnb={}", self.nb)?;
+            write!(f, "\n\tsite_types={:?}", self.site_types)?;
+            write!(f, "\n\tn_sites={}", self.n_sites)?;
         }
         if !self.dipole_comp.is_empty() {
             write!(f, "\n\tdipole_comp={}", self.dipole_comp)?;
             write!(f, "\n\tmu={}", self.mu)?;
         }
+        if !self.quadrupole_comp.is_empty() {
+            write!(f, "\n\tquadrupole_comp={}", self.quadrupole_comp)?;
+            write!(f, "\n\tq={}", self.q)?;
+        }
+        if !self.ion_segment.is_empty() {
+            write!(f, "\n\tion_segment={}", self.ion_segment)?;
+            write!(f, "\n\tz={}", self.z)?;
+        }
         write!(f, "\n)")
     }
 }
@@ -416,13 +888,22 @@ impl std::fmt::Display for GcPcSaftEosParameters {
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use crate::record::{AssociationSite, SiteInteraction};
+    use approx::assert_relative_eq;
     use feos_core::parameter::{ChemicalRecord, Identifier};
+    use ndarray::arr1;
+    use num_dual::{Dual64, DualNum};
 
     fn ch3() -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
         SegmentRecord::new(
             "CH3".into(),
             15.0,
-            GcPcSaftRecord::new(0.77247, 3.6937, 181.49, None, None, None, None, None, None),
+            GcPcSaftRecord {
+                m: 0.77247,
+                sigma: 3.6937,
+                epsilon_k: 181.49,
+                ..Default::default()
+            },
             None,
         )
     }
@@ -431,7 +912,12 @@ pub mod test {
         SegmentRecord::new(
             "CH2".into(),
             14.0,
-            GcPcSaftRecord::new(0.7912, 3.0207, 157.23, None, None, None, None, None, None),
+            GcPcSaftRecord {
+                m: 0.7912,
+                sigma: 3.0207,
+                epsilon_k: 157.23,
+                ..Default::default()
+            },
             None,
         )
     }
@@ -440,23 +926,84 @@ pub mod test {
         SegmentRecord::new(
             "OH".into(),
             0.0,
-            GcPcSaftRecord::new(
-                1.0231,
-                2.7702,
-                334.29,
-                None,
-                Some(0.009583),
-                Some(2575.9),
-                None,
-                None,
-                None,
-            ),
+            GcPcSaftRecord {
+                m: 1.0231,
+                sigma: 2.7702,
+                epsilon_k: 334.29,
+                kappa_ab: Some(0.009583),
+                epsilon_k_ab: Some(2575.9),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    fn nh2() -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+        SegmentRecord::new(
+            "NH2".into(),
+            16.0,
+            GcPcSaftRecord {
+                m: 0.82284,
+                sigma: 3.1129,
+                epsilon_k: 309.93,
+                kappa_ab: Some(0.005769),
+                epsilon_k_ab: Some(1471.5),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    fn carbonyl(induced: bool) -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+        SegmentRecord::new(
+            ">C=O".into(),
+            28.0,
+            GcPcSaftRecord {
+                m: 1.1889,
+                sigma: 3.1026,
+                epsilon_k: 280.43,
+                nb: if induced { Some(2.0) } else { None },
+                ..Default::default()
+            },
             None,
         )
     }
 
-    pub fn ch3_oh() -> BinaryRecord<String, f64> {
-        BinaryRecord::new("CH3".to_string(), "OH".to_string(), -0.0087)
+    fn cooh() -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+        SegmentRecord::new(
+            "COOH".into(),
+            45.0,
+            GcPcSaftRecord {
+                m: 1.3,
+                sigma: 3.1,
+                epsilon_k: 320.0,
+                association_record: Some(AssociationRecord::new(
+                    vec![AssociationSite::new("C".into(), 1.0)],
+                    vec![SiteInteraction::new("C".into(), "C".into(), 0.01, 2500.0)],
+                )),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    fn co2() -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+        SegmentRecord::new(
+            "CO2".into(),
+            44.0,
+            GcPcSaftRecord {
+                m: 1.5131,
+                sigma: 3.1869,
+                epsilon_k: 163.33,
+                q: Some(4.4),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    pub fn ch3_oh() -> BinaryRecord<String, GcPcSaftBinaryRecord> {
+        BinaryRecord::new("CH3".to_string(), "OH".to_string(), (-0.0087).into())
     }
 
     pub fn propane() -> GcPcSaftEosParameters {
@@ -477,6 +1024,46 @@ pub mod test {
         GcPcSaftEosParameters::from_segments(vec![pure], vec![ch3(), ch2(), oh()], None).unwrap()
     }
 
+    /// 1-propanol with (arbitrary) entropy scaling coefficients for all segments.
+    pub fn propanol_entropy_scaling() -> GcPcSaftEosParameters {
+        let pure = ChemicalRecord::new(
+            Identifier::new("71-23-8", Some("1-propanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let segment_records: Vec<_> = [
+            (ch3(), [-0.2, -0.6, -0.1, -0.01], -0.1, 0.1),
+            (ch2(), [-0.1, -0.4, -0.05, -0.005], -0.05, 0.05),
+            (oh(), [-0.5, -0.8, -0.2, -0.02], -0.2, 0.2),
+        ]
+        .iter()
+        .cloned()
+        .map(
+            |(mut segment, viscosity, diffusion, thermal_conductivity)| {
+                segment.model_record.viscosity = Some(viscosity);
+                segment.model_record.diffusion = Some([diffusion; 5]);
+                segment.model_record.thermal_conductivity = Some([thermal_conductivity; 4]);
+                segment
+            },
+        )
+        .collect();
+        GcPcSaftEosParameters::from_segments(vec![pure], segment_records, None).unwrap()
+    }
+
+    /// 1-propanol with a single type of self-associating sites on the OH group,
+    /// specified by `na` and `nb` of which one is zero.
+    pub fn propanol_single_site_type(na: f64, nb: f64) -> GcPcSaftEosParameters {
+        let pure = ChemicalRecord::new(
+            Identifier::new("71-23-8", Some("1-propanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let mut oh = oh();
+        oh.model_record.na = Some(na);
+        oh.model_record.nb = Some(nb);
+        GcPcSaftEosParameters::from_segments(vec![pure], vec![ch3(), ch2(), oh], None).unwrap()
+    }
+
     pub fn ethanol_propanol(binary: bool) -> GcPcSaftEosParameters {
         let ethanol = ChemicalRecord::new(
             Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
@@ -497,6 +1084,119 @@ pub mod test {
         .unwrap()
     }
 
+    pub fn ethanol_acetone(induced: bool) -> GcPcSaftEosParameters {
+        let ethanol = ChemicalRecord::new(
+            Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let acetone = ChemicalRecord::new(
+            Identifier::new("67-64-1", Some("acetone"), None, None, None, None),
+            vec!["CH3".into(), ">C=O".into(), "CH3".into()],
+            None,
+        );
+        GcPcSaftEosParameters::from_segments(
+            vec![ethanol, acetone],
+            vec![ch3(), ch2(), oh(), carbonyl(induced)],
+            None,
+        )
+        .unwrap()
+    }
+
+    pub fn propanoic_acid() -> GcPcSaftEosParameters {
+        let pure = ChemicalRecord::new(
+            Identifier::new("79-09-4", Some("propanoic acid"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "COOH".into()],
+            None,
+        );
+        GcPcSaftEosParameters::from_segments(vec![pure], vec![ch3(), ch2(), cooh()], None).unwrap()
+    }
+
+    pub fn propanol_propanoic_acid() -> GcPcSaftEosParameters {
+        let propanol = ChemicalRecord::new(
+            Identifier::new("71-23-8", Some("1-propanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let acid = ChemicalRecord::new(
+            Identifier::new("79-09-4", Some("propanoic acid"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "COOH".into()],
+            None,
+        );
+        let mut oh = oh();
+        oh.model_record.association_record = Some(AssociationRecord::new(
+            vec![AssociationSite::new("C".into(), 1.0)],
+            vec![SiteInteraction::new("C".into(), "C".into(), 0.02, 1500.0)],
+        ));
+        GcPcSaftEosParameters::from_segments(
+            vec![propanol, acid],
+            vec![ch3(), ch2(), oh, cooh()],
+            None,
+        )
+        .unwrap()
+    }
+
+    pub fn propane_co2() -> GcPcSaftEosParameters {
+        let propane = ChemicalRecord::new(
+            Identifier::new("74-98-6", Some("propane"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "CH3".into()],
+            None,
+        );
+        let carbon_dioxide = ChemicalRecord::new(
+            Identifier::new("124-38-9", Some("carbon dioxide"), None, None, None, None),
+            vec!["CO2".into()],
+            None,
+        );
+        GcPcSaftEosParameters::from_segments(
+            vec![propane, carbon_dioxide],
+            vec![ch3(), ch2(), co2()],
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quadrupole() {
+        let params = propane_co2();
+        assert_eq!(params.quadrupole_comp, arr1(&[1]));
+        assert!(params.dipole_comp.is_empty());
+        assert_relative_eq!(params.q[0], 4.4, max_relative = 1e-14);
+        assert_eq!(params.m_mix.len(), 2);
+        assert_relative_eq!(params.sigma_mix[1], 3.1869, max_relative = 1e-14);
+    }
+
+    #[test]
+    fn test_homosegmented() {
+        let hetero = ethanol_propanol(true);
+        let params = hetero.homosegmented();
+        assert_eq!(params.identifiers, vec!["ethanol", "1-propanol"]);
+        assert_eq!(params.m, hetero.m_mix);
+        assert_eq!(params.sigma, hetero.sigma_mix);
+        assert_eq!(params.epsilon_k, hetero.epsilon_k_mix);
+        assert_relative_eq!(params.bonds[&[1, 1]], hetero.m_mix[1] - 1.0);
+        assert_eq!(params.assoc_segment, arr1(&[0, 0, 1, 1]));
+        assert_eq!(params.n, arr1(&[1.0, 1.0, 1.0, 1.0]));
+
+        // CH3 of one component interacts with OH of the other component
+        let (m_ch3, m_oh) = (0.77247, 1.0231);
+        assert_relative_eq!(
+            params.k_ij[(0, 1)],
+            -0.0087 * 2.0 * m_ch3 * m_oh / (hetero.m_mix[0] * hetero.m_mix[1]),
+            max_relative = 1e-14
+        );
+        assert_eq!(params.k_ij[(0, 0)], 0.0);
+        assert_relative_eq!(
+            params.sigma3_kappa_aibj[(0, 1)],
+            (params.sigma[0] * params.sigma[1]).powf(1.5) * 0.009583,
+            max_relative = 1e-14
+        );
+
+        // the mode is kept for subsets
+        let subset = params.subset(&[1]);
+        assert_eq!(subset.m.len(), 1);
+        assert_eq!(subset.identifiers, vec!["1-propanol"]);
+    }
+
     #[test]
     fn test_kij() {
         let params = ethanol_propanol(true);
@@ -514,15 +1214,207 @@ pub mod test {
             .unwrap();
         println!("{:?}", params.identifiers);
         println!("{}", params.k_ij);
+        let epsilon_k_ij = params.epsilon_k_ij(300.0);
         // CH3 - CH2
-        assert_eq!(
-            params.epsilon_k_ij[(ch3.0, ch2.0)],
-            (181.49f64 * 157.23).sqrt()
-        );
+        assert_eq!(epsilon_k_ij[(ch3.0, ch2.0)], (181.49f64 * 157.23).sqrt());
         // CH3 - OH
         assert_eq!(
-            params.epsilon_k_ij[(ch3.0, oh.0)],
+            epsilon_k_ij[(ch3.0, oh.0)],
             (181.49f64 * 334.29).sqrt() * 1.0087
         );
     }
+
+    #[test]
+    fn test_kij_temperature_dependent() {
+        let binary: Vec<BinaryRecord<String, GcPcSaftBinaryRecord>> = serde_json::from_str(
+            r#"[
+                {"id1": "CH3", "id2": "OH", "model_record": {"k_ij": 0.01, "k_ij_t": -3.0, "k_ij_ln_t": 0.002, "l_ij": 0.05}},
+                {"id1": "CH2", "id2": "OH", "model_record": -0.0087}
+            ]"#,
+        )
+        .unwrap();
+        let ethanol = ChemicalRecord::new(
+            Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let propanol = ChemicalRecord::new(
+            Identifier::new("71-23-8", Some("1-propanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let params = GcPcSaftEosParameters::from_segments(
+            vec![ethanol, propanol],
+            vec![ch3(), ch2(), oh()],
+            Some(binary),
+        )
+        .unwrap();
+        let index = |id: &str, c: usize| {
+            (0..params.m.len())
+                .find(|&i| params.identifiers[i] == id && params.component_index[i] == c)
+                .unwrap()
+        };
+        let (ch3, ch2, oh) = (index("CH3", 0), index("CH2", 0), index("OH", 1));
+        let t = 350.0;
+        let k_ij = 0.01 - 3.0 / t + 0.002 * t.ln();
+        assert_relative_eq!(
+            params.epsilon_k_ij(t)[(ch3, oh)],
+            (181.49f64 * 334.29).sqrt() * (1.0 - k_ij),
+            max_relative = 1e-14
+        );
+        assert_relative_eq!(
+            params.epsilon_k_ij(t)[(ch2, oh)],
+            (157.23f64 * 334.29).sqrt() * 1.0087,
+            max_relative = 1e-14
+        );
+        assert_relative_eq!(
+            params.sigma_ij[(oh, ch3)],
+            0.5 * (3.6937 + 2.7702) * 0.95,
+            max_relative = 1e-14
+        );
+        assert_eq!(
+            params.sigma_ij[(ch3, index("OH", 0))],
+            0.5 * (3.6937 + 2.7702)
+        );
+
+        // temperature derivative of the dispersion energy
+        let de_dt = params.epsilon_k_ij(Dual64::from_re(t).derive())[(ch3, oh)].eps[0];
+        assert_relative_eq!(
+            de_dt,
+            -(181.49f64 * 334.29).sqrt() * (3.0 / t.powi(2) + 0.002 / t),
+            max_relative = 1e-14
+        );
+    }
+
+    #[test]
+    fn test_binary_cross_association() {
+        let ethanol = ChemicalRecord::new(
+            Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "OH".into()],
+            None,
+        );
+        let ethylamine = ChemicalRecord::new(
+            Identifier::new("75-04-7", Some("ethylamine"), None, None, None, None),
+            vec!["CH3".into(), "CH2".into(), "NH2".into()],
+            None,
+        );
+        let binary = BinaryRecord::new(
+            "NH2".to_string(),
+            "OH".to_string(),
+            GcPcSaftBinaryRecord::new(0.0, 0.0, 0.0, 0.0, Some(0.01), Some(2000.0)),
+        );
+        let params = GcPcSaftEosParameters::from_segments(
+            vec![ethanol, ethylamine],
+            vec![ch3(), ch2(), oh(), nh2()],
+            Some(vec![binary]),
+        )
+        .unwrap();
+        let (s_oh, s_nh2) = (2.7702f64, 3.1129f64);
+        // sites: A and B of OH, A and B of NH2
+        assert_eq!(params.assoc_segment.len(), 4);
+        for (i, j) in [(0, 3), (3, 0), (1, 2), (2, 1)] {
+            assert_relative_eq!(
+                params.sigma3_kappa_aibj[(i, j)],
+                (s_oh * s_nh2).powf(1.5) * 0.01,
+                max_relative = 1e-14
+            );
+            assert_eq!(params.epsilon_k_aibj[(i, j)], 2000.0);
+        }
+        // sites of the same type do not associate
+        assert_eq!(params.sigma3_kappa_aibj[(0, 2)], 0.0);
+        // self-association is not affected
+        assert_relative_eq!(
+            params.sigma3_kappa_aibj[(0, 1)],
+            s_oh.powi(3) * 0.009583,
+            max_relative = 1e-14
+        );
+        assert_eq!(params.epsilon_k_aibj[(2, 3)], 1471.5);
+    }
+
+    #[test]
+    fn test_induced_association() {
+        let params = ethanol_acetone(true);
+        assert_eq!(params.site_types, vec!["A", "B", "B"]);
+        assert_eq!(params.n_sites, arr1(&[1.0, 1.0, 2.0]));
+        let (s_oh, s_co) = (2.7702f64, 3.1026f64);
+        assert_eq!(params.sigma3_kappa_aibj[(2, 2)], 0.0);
+        assert_eq!(params.sigma3_kappa_aibj[(1, 2)], 0.0);
+        assert_relative_eq!(
+            params.sigma3_kappa_aibj[(0, 2)],
+            (s_oh * s_co).powf(1.5) * 0.009583,
+            max_relative = 1e-14
+        );
+        assert_eq!(params.epsilon_k_aibj[(2, 0)], 0.5 * 2575.9);
+    }
+
+    #[test]
+    fn test_association_sites() {
+        let params = propanol_propanoic_acid();
+        // OH: legacy A/B sites and additional C sites, COOH: C sites
+        assert_eq!(params.site_types, vec!["A", "B", "C", "C"]);
+        assert_eq!(params.assoc_segment[0], params.assoc_segment[2]);
+        assert_ne!(params.assoc_segment[2], params.assoc_segment[3]);
+        assert_eq!(params.n_sites, arr1(&[1.0, 1.0, 1.0, 1.0]));
+        assert_eq!(params.sigma3_kappa_aibj[(0, 3)], 0.0);
+        assert_eq!(params.sigma3_kappa_aibj[(1, 2)], 0.0);
+        assert_eq!(params.epsilon_k_aibj[(0, 1)], 2575.9);
+        assert_eq!(params.epsilon_k_aibj[(2, 3)], 0.5 * (1500.0 + 2500.0));
+
+        let record: GcPcSaftRecord = serde_json::from_str(
+            r#"{"m": 1.3, "sigma": 3.1, "epsilon_k": 320.0, "association_record": {"sites": [{"id": "C", "n": 1.0}], "interactions": [{"id1": "C", "id2": "C", "kappa_ab": 0.01, "epsilon_k_ab": 2500.0}]}}"#,
+        )
+        .unwrap();
+        let association_record = record.association_record().unwrap();
+        assert_eq!(association_record.sites.len(), 1);
+        assert_eq!(association_record.sites[0].id, "C");
+        assert_eq!(
+            association_record
+                .interaction("C", "C")
+                .unwrap()
+                .epsilon_k_ab,
+            2500.0
+        );
+
+        // legacy records with a single site type associate with themselves
+        let record = GcPcSaftRecord::new(
+            1.0,
+            3.0,
+            300.0,
+            None,
+            Some(0.01),
+            Some(2500.0),
+            Some(1.0),
+            Some(0.0),
+            None,
+        );
+        let association_record = record.association_record().unwrap();
+        assert_eq!(association_record.sites.len(), 1);
+        assert!(association_record.interaction("A", "A").is_some());
+        assert!(association_record.interaction("A", "B").is_none());
+    }
+
+    #[test]
+    fn test_entropy_scaling_coefficients() {
+        let params = propanol_entropy_scaling();
+        let v = |s: SegmentRecord<GcPcSaftRecord, JobackRecord>| {
+            s.model_record.m * s.model_record.sigma.powi(3)
+        };
+        let (v_ch3, v_ch2, v_oh) = (v(ch3()), v(ch2()), v(oh()));
+        let v_total = v_ch3 + 2.0 * v_ch2 + v_oh;
+        let viscosity = params.viscosity.unwrap();
+        assert_eq!(viscosity.shape(), &[4, 1]);
+        assert_relative_eq!(
+            viscosity[(0, 0)],
+            -0.2 * v_ch3 - 2.0 * 0.1 * v_ch2 - 0.5 * v_oh
+        );
+        assert_relative_eq!(
+            viscosity[(1, 0)],
+            (-0.6 * v_ch3 - 2.0 * 0.4 * v_ch2 - 0.8 * v_oh) / v_total.powf(0.45)
+        );
+        assert_relative_eq!(viscosity[(2, 0)], -0.1 - 2.0 * 0.05 - 0.2);
+        assert_relative_eq!(viscosity[(3, 0)], -0.01 - 2.0 * 0.005 - 0.02);
+        assert_eq!(params.diffusion.unwrap().shape(), &[5, 1]);
+        assert_relative_eq!(params.thermal_conductivity.unwrap()[(2, 0)], 0.4);
+        assert!(propanol().viscosity.is_none());
+    }
 }
@@ -1,10 +1,11 @@
+use super::hard_sphere::zeta;
 use super::GcPcSaftEosParameters;
 use feos_core::{HelmholtzEnergyDual, StateHD};
 use ndarray::prelude::*;
 use num_dual::DualNum;
 use std::f64::consts::{FRAC_PI_3, PI};
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 
 // Dipole parameters
 pub const AD: [[f64; 3]; 5] = [
@@ -30,165 +31,556 @@ pub const CD: [[f64; 3]; 4] = [
     [0.69028490492, -0.27012609786, -3.43967436378],
 ];
 
+// Quadrupole parameters
+pub const AQ: [[f64; 3]; 5] = [
+    [1.2378308, 1.2854109, 1.7942954],
+    [2.4355031, -11.465615, 0.7695103],
+    [1.6330905, 22.086893, 7.2647923],
+    [-1.6118152, 7.4691383, 94.486699],
+    [6.9771185, -17.197772, -77.148458],
+];
+
+pub const BQ: [[f64; 3]; 5] = [
+    [0.4542718, -0.8137340, 6.8682675],
+    [-4.5016264, 10.064030, -5.1732238],
+    [3.5858868, -10.876631, -17.240207],
+    [0.0; 3],
+    [0.0; 3],
+];
+
+pub const CQ: [[f64; 3]; 4] = [
+    [-0.5000437, 2.0002094, 3.1358271],
+    [6.5318692, -6.7838658, 7.2475888],
+    [-16.014780, 20.383246, 3.0759478],
+    [14.425970, -10.895984, 0.0],
+];
+
+// Dipole-Quadrupole parameters
+pub const ADQ: [[f64; 3]; 5] = [
+    [0.697094963, -0.673459279, 0.670340770],
+    [-0.633554144, -1.425899106, -4.338471826],
+    [2.945509028, 4.19441392, 7.234168360],
+    [-1.467027314, 1.0266216, 0.0],
+    [0.0; 3],
+];
+
+pub const BDQ: [[f64; 3]; 5] = [
+    [-0.484038322, 0.67651011, -1.167560146],
+    [1.970405465, -3.013867512, 2.13488432],
+    [-2.118572671, 0.46742656, 0.0],
+    [0.0; 3],
+    [0.0; 3],
+];
+
+pub const CDQ: [[f64; 2]; 3] = [
+    [0.795009692, -2.099579397],
+    [3.386863396, -5.941376392],
+    [0.475106328, -0.178820384],
+];
+
 pub const PI_SQ_43: f64 = 4.0 * PI * FRAC_PI_3;
 
-fn pair_integral_ij<D: DualNum<f64>>(mij1: f64, mij2: f64, eta: D, eps_ij_t: D) -> D {
+fn pair_integral_ij<D: DualNum<f64>>(
+    mij1: f64,
+    mij2: f64,
+    eta: D,
+    a: &[[f64; 3]; 5],
+    b: &[[f64; 3]; 5],
+    eps_ij_t: D,
+) -> D {
     let eta2 = eta * eta;
     let etas = [D::one(), eta, eta2, eta2 * eta, eta2 * eta2];
-    (0..AD.len())
+    (0..a.len())
         .map(|i| {
             etas[i]
-                * (eps_ij_t * (BD[i][0] + mij1 * BD[i][1] + mij2 * BD[i][2])
-                    + (AD[i][0] + mij1 * AD[i][1] + mij2 * AD[i][2]))
+                * (eps_ij_t * (b[i][0] + mij1 * b[i][1] + mij2 * b[i][2])
+                    + (a[i][0] + mij1 * a[i][1] + mij2 * a[i][2]))
         })
         .sum()
 }
 
-fn triplet_integral_ijk<D: DualNum<f64>>(mijk1: f64, mijk2: f64, eta: D) -> D {
+fn triplet_integral_ijk<D: DualNum<f64>>(mijk1: f64, mijk2: f64, eta: D, c: &[[f64; 3]; 4]) -> D {
     let eta2 = eta * eta;
     let etas = [D::one(), eta, eta2, eta2 * eta];
-    (0..CD.len())
-        .map(|i| etas[i] * (CD[i][0] + mijk1 * CD[i][1] + mijk2 * CD[i][2]))
+    (0..c.len())
+        .map(|i| etas[i] * (c[i][0] + mijk1 * c[i][1] + mijk2 * c[i][2]))
         .sum()
 }
 
-pub struct Dipole {
-    parameters: Rc<GcPcSaftEosParameters>,
+fn triplet_integral_ijk_dq<D: DualNum<f64>>(mijk: f64, eta: D) -> D {
+    let etas = [D::one(), eta, eta * eta];
+    (0..CDQ.len())
+        .map(|i| etas[i] * (CDQ[i][0] + mijk * CDQ[i][1]))
+        .sum()
+}
+
+/// Mean segment numbers of all pairs and triplets of the given components.
+fn mean_segment_numbers(
+    comp: &Array1<usize>,
+    m_mix: &Array1<f64>,
+) -> (Array2<f64>, Array2<f64>, Array3<f64>, Array3<f64>) {
+    let n = comp.len();
+    let mut mij1 = Array2::zeros((n, n));
+    let mut mij2 = Array2::zeros((n, n));
+    let mut mijk1 = Array3::zeros((n, n, n));
+    let mut mijk2 = Array3::zeros((n, n, n));
+    for i in 0..n {
+        let mi = m_mix[comp[i]].min(2.0);
+        mij1[[i, i]] = (mi - 1.0) / mi;
+        mij2[[i, i]] = mij1[[i, i]] * (mi - 2.0) / mi;
+
+        mijk1[[i, i, i]] = mij1[[i, i]];
+        mijk2[[i, i, i]] = mij2[[i, i]];
+        for j in i + 1..n {
+            let mj = m_mix[comp[j]].min(2.0);
+            let mij = (mi * mj).sqrt();
+            mij1[[i, j]] = (mij - 1.0) / mij;
+            mij2[[i, j]] = mij1[[i, j]] * (mij - 2.0) / mij;
+            let mijk = (mi * mi * mj).cbrt();
+            mijk1[[i, i, j]] = (mijk - 1.0) / mijk;
+            mijk2[[i, i, j]] = mijk1[[i, i, j]] * (mijk - 2.0) / mijk;
+            let mijk = (mi * mj * mj).cbrt();
+            mijk1[[i, j, j]] = (mijk - 1.0) / mijk;
+            mijk2[[i, j, j]] = mijk1[[i, j, j]] * (mijk - 2.0) / mijk;
+            for k in j + 1..n {
+                let mk = m_mix[comp[k]].min(2.0);
+                let mijk = (mi * mj * mk).cbrt();
+                mijk1[[i, j, k]] = (mijk - 1.0) / mijk;
+                mijk2[[i, j, k]] = mijk1[[i, j, k]] * (mijk - 2.0) / mijk;
+            }
+        }
+    }
+    (mij1, mij2, mijk1, mijk2)
+}
+
+/// Pair and triplet contributions of a single multipole type (dipoles or quadrupoles)
+/// of the form of Gross and Vrabec.
+#[derive(Clone)]
+struct MultipoleTerms {
+    comp: Array1<usize>,
     mij1: Array2<f64>,
     mij2: Array2<f64>,
     mijk1: Array3<f64>,
     mijk2: Array3<f64>,
     f2_term: Array2<f64>,
     f3_term: Array3<f64>,
+    e_k_ij: Array2<f64>,
 }
 
-impl Dipole {
-    pub fn new(parameters: &Rc<GcPcSaftEosParameters>) -> Self {
-        let ndipole = parameters.dipole_comp.len();
+impl MultipoleTerms {
+    fn new(
+        comp: &Array1<usize>,
+        moment2: &Array1<f64>,
+        m_mix: &Array1<f64>,
+        s_ij: &Array2<f64>,
+        e_k_ij: &Array2<f64>,
+        exponent: [i32; 2],
+    ) -> Self {
+        let n = comp.len();
 
-        let f2_term = Array2::from_shape_fn([ndipole; 2], |(i, j)| {
-            let di = parameters.dipole_comp[i];
-            let dj = parameters.dipole_comp[j];
-            parameters.mu2[i] * parameters.mu2[j] / parameters.s_ij[[di, dj]].powi(3)
+        let f2_term = Array2::from_shape_fn([n; 2], |(i, j)| {
+            moment2[i] * moment2[j] / s_ij[[comp[i], comp[j]]].powi(exponent[0])
         });
 
-        let f3_term = Array3::from_shape_fn([ndipole; 3], |(i, j, k)| {
-            let di = parameters.dipole_comp[i];
-            let dj = parameters.dipole_comp[j];
-            let dk = parameters.dipole_comp[k];
-            parameters.mu2[i] * parameters.mu2[j] * parameters.mu2[k]
-                / (parameters.s_ij[[di, dj]]
-                    * parameters.s_ij[[di, dk]]
-                    * parameters.s_ij[[dj, dk]])
+        let f3_term = Array3::from_shape_fn([n; 3], |(i, j, k)| {
+            moment2[i] * moment2[j] * moment2[k]
+                / (s_ij[[comp[i], comp[j]]] * s_ij[[comp[i], comp[k]]] * s_ij[[comp[j], comp[k]]])
+                    .powi(exponent[1])
         });
 
-        let mut mij1 = Array2::zeros((ndipole, ndipole));
-        let mut mij2 = Array2::zeros((ndipole, ndipole));
-        let mut mijk1 = Array3::zeros((ndipole, ndipole, ndipole));
-        let mut mijk2 = Array3::zeros((ndipole, ndipole, ndipole));
-        for i in 0..ndipole {
-            let mi = parameters.m_mix[i].min(2.0);
-            mij1[[i, i]] = (mi - 1.0) / mi;
-            mij2[[i, i]] = mij1[[i, i]] * (mi - 2.0) / mi;
-
-            mijk1[[i, i, i]] = mij1[[i, i]];
-            mijk2[[i, i, i]] = mij2[[i, i]];
-            for j in i + 1..ndipole {
-                let mj = parameters.m_mix[j].min(2.0);
-                let mij = (mi * mj).sqrt();
-                mij1[[i, j]] = (mij - 1.0) / mij;
-                mij2[[i, j]] = mij1[[i, j]] * (mij - 2.0) / mij;
-                let mijk = (mi * mi * mj).cbrt();
-                mijk1[[i, i, j]] = (mijk - 1.0) / mijk;
-                mijk2[[i, i, j]] = mijk1[[i, i, j]] * (mijk - 2.0) / mijk;
-                let mijk = (mi * mj * mj).cbrt();
-                mijk1[[i, j, j]] = (mijk - 1.0) / mijk;
-                mijk2[[i, j, j]] = mijk1[[i, j, j]] * (mijk - 2.0) / mijk;
-                for k in j + 1..ndipole {
-                    let mk = parameters.m_mix[k].min(2.0);
-                    let mijk = (mi * mj * mk).cbrt();
-                    mijk1[[i, j, k]] = (mijk - 1.0) / mijk;
-                    mijk2[[i, j, k]] = mijk1[[i, j, k]] * (mijk - 2.0) / mijk;
-                }
-            }
-        }
+        let (mij1, mij2, mijk1, mijk2) = mean_segment_numbers(comp, m_mix);
         Self {
-            parameters: parameters.clone(),
+            comp: comp.clone(),
             mij1,
             mij2,
             mijk1,
             mijk2,
             f2_term,
             f3_term,
+            e_k_ij: Array2::from_shape_fn([n; 2], |(i, j)| e_k_ij[[comp[i], comp[j]]]),
         }
     }
-}
 
-impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Dipole {
-    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
-        let p = &self.parameters;
-        let ndipole = p.dipole_comp.len();
-
-        let t_inv = state.temperature.inv();
-        let eps_ij_t = Array2::from_shape_fn([ndipole; 2], |(i, j)| {
-            let di = p.dipole_comp[i];
-            let dj = p.dipole_comp[j];
-            t_inv * p.e_k_ij[[di, dj]]
-        });
+    /// Second and third order perturbation terms (without prefactors) for the
+    /// given component densities and packing fraction.
+    fn phi<D: DualNum<f64>>(
+        &self,
+        temperature: D,
+        rho: &Array1<D>,
+        eta: D,
+        a: &[[f64; 3]; 5],
+        b: &[[f64; 3]; 5],
+        c: &[[f64; 3]; 4],
+    ) -> (D, D) {
+        let n = self.comp.len();
+        let rho = self.comp.mapv(|i| rho[i]);
 
-        let rho = &state.partial_density;
-        let r = p.hs_diameter(state.temperature) * 0.5;
-        let eta = (rho * &p.m * &r * &r * &r).sum() * 4.0 * FRAC_PI_3;
+        let t_inv = temperature.inv();
+        let eps_ij_t = self.e_k_ij.mapv(|e| t_inv * e);
 
         let mut phi2 = D::zero();
         let mut phi3 = D::zero();
-        for i in 0..ndipole {
-            let di = p.dipole_comp[i];
-            phi2 -= (rho[di] * rho[di] * self.f2_term[[i, i]])
+        for i in 0..n {
+            phi2 += (rho[i] * rho[i] * self.f2_term[[i, i]])
                 * pair_integral_ij(
                     self.mij1[[i, i]],
                     self.mij2[[i, i]],
                     eta,
-                    eps_ij_t[[di, di]],
+                    a,
+                    b,
+                    eps_ij_t[[i, i]],
                 );
-            phi3 -= (rho[di] * rho[di] * rho[di] * self.f3_term[[i, i, i]])
-                * triplet_integral_ijk(self.mijk1[[i, i, i]], self.mijk2[[i, i, i]], eta);
-            for j in (i + 1)..ndipole {
-                let dj = p.dipole_comp[j];
-                phi2 -= (rho[di] * rho[dj] * self.f2_term[[i, j]])
+            phi3 += (rho[i] * rho[i] * rho[i] * self.f3_term[[i, i, i]])
+                * triplet_integral_ijk(self.mijk1[[i, i, i]], self.mijk2[[i, i, i]], eta, c);
+            for j in (i + 1)..n {
+                phi2 += (rho[i] * rho[j] * self.f2_term[[i, j]])
                     * pair_integral_ij(
                         self.mij1[[i, j]],
                         self.mij2[[i, j]],
                         eta,
-                        eps_ij_t[[di, dj]],
+                        a,
+                        b,
+                        eps_ij_t[[i, j]],
                     )
                     * 2.0;
-                phi3 -= (rho[di] * rho[di] * rho[dj] * self.f3_term[[i, i, j]])
-                    * triplet_integral_ijk(self.mijk1[[i, i, j]], self.mijk2[[i, i, j]], eta)
+                phi3 += (rho[i] * rho[i] * rho[j] * self.f3_term[[i, i, j]])
+                    * triplet_integral_ijk(self.mijk1[[i, i, j]], self.mijk2[[i, i, j]], eta, c)
                     * 3.0;
-                phi3 -= (rho[di] * rho[dj] * rho[dj] * self.f3_term[[i, j, j]])
-                    * triplet_integral_ijk(self.mijk1[[i, j, j]], self.mijk2[[i, j, j]], eta)
+                phi3 += (rho[i] * rho[j] * rho[j] * self.f3_term[[i, j, j]])
+                    * triplet_integral_ijk(self.mijk1[[i, j, j]], self.mijk2[[i, j, j]], eta, c)
                     * 3.0;
-                for k in (j + 1)..ndipole {
-                    let dk = p.dipole_comp[k];
-                    phi3 -= (rho[di] * rho[dj] * rho[dk] * self.f3_term[[i, j, k]])
-                        * triplet_integral_ijk(self.mijk1[[i, j, k]], self.mijk2[[i, j, k]], eta)
+                for k in (j + 1)..n {
+                    phi3 += (rho[i] * rho[j] * rho[k] * self.f3_term[[i, j, k]])
+                        * triplet_integral_ijk(
+                            self.mijk1[[i, j, k]],
+                            self.mijk2[[i, j, k]],
+                            eta,
+                            c,
+                        )
                         * 6.0;
                 }
             }
         }
-        phi2 *= t_inv * t_inv * PI;
-        phi3 *= t_inv.powi(3) * PI_SQ_43;
-        let mut result = phi2 * phi2 / (phi2 - phi3) * state.volume;
-        if result.re().is_nan() {
-            result = phi2 * state.volume
-        }
+        (phi2 * t_inv * t_inv, phi3 * t_inv.powi(3))
+    }
+}
+
+/// Combine second and third order terms using a Pade approximation.
+fn pade<D: DualNum<f64>>(phi2: D, phi3: D) -> D {
+    let result = phi2 * phi2 / (phi2 - phi3);
+    if result.re().is_nan() {
+        phi2
+    } else {
         result
     }
 }
 
+/// Dipole-dipole interactions of all dipolar components (Gross and Vrabec 2006).
+#[derive(Clone)]
+pub(crate) struct DipoleTerms(MultipoleTerms);
+
+impl DipoleTerms {
+    pub fn new(
+        dipole_comp: &Array1<usize>,
+        mu2: &Array1<f64>,
+        m_mix: &Array1<f64>,
+        s_ij: &Array2<f64>,
+        e_k_ij: &Array2<f64>,
+    ) -> Self {
+        Self(MultipoleTerms::new(
+            dipole_comp,
+            mu2,
+            m_mix,
+            s_ij,
+            e_k_ij,
+            [3, 1],
+        ))
+    }
+
+    /// Reduced Helmholtz energy density for the given component densities
+    /// and the total packing fraction.
+    pub fn helmholtz_energy_density<D: DualNum<f64>>(
+        &self,
+        temperature: D,
+        rho: &Array1<D>,
+        eta: D,
+    ) -> D {
+        let (phi2, phi3) = self.0.phi(temperature, rho, eta, &AD, &BD, &CD);
+        pade(-phi2 * PI, -phi3 * PI_SQ_43)
+    }
+}
+
+/// Quadrupole-quadrupole interactions of all quadrupolar components (Gross 2005).
+#[derive(Clone)]
+pub(crate) struct QuadrupoleTerms(MultipoleTerms);
+
+impl QuadrupoleTerms {
+    pub fn new(
+        quadrupole_comp: &Array1<usize>,
+        q2: &Array1<f64>,
+        m_mix: &Array1<f64>,
+        s_ij: &Array2<f64>,
+        e_k_ij: &Array2<f64>,
+    ) -> Self {
+        Self(MultipoleTerms::new(
+            quadrupole_comp,
+            q2,
+            m_mix,
+            s_ij,
+            e_k_ij,
+            [7, 3],
+        ))
+    }
+
+    /// Reduced Helmholtz energy density for the given component densities
+    /// and the total packing fraction.
+    pub fn helmholtz_energy_density<D: DualNum<f64>>(
+        &self,
+        temperature: D,
+        rho: &Array1<D>,
+        eta: D,
+    ) -> D {
+        let (phi2, phi3) = self.0.phi(temperature, rho, eta, &AQ, &BQ, &CQ);
+        pade(-phi2 * (PI * 0.5625), phi3 * (PI * PI * 0.421875))
+    }
+}
+
+/// Cross interactions between dipolar and quadrupolar components (Vrabec and Gross 2008).
+#[derive(Clone)]
+pub(crate) struct DipoleQuadrupoleTerms {
+    dipole_comp: Array1<usize>,
+    quadrupole_comp: Array1<usize>,
+    mij1: Array2<f64>,
+    mij2: Array2<f64>,
+    mijk_ddq: Array3<f64>,
+    mijk_dqq: Array3<f64>,
+    f2_term: Array2<f64>,
+    f3_term_ddq: Array3<f64>,
+    f3_term_dqq: Array3<f64>,
+    e_k_ij: Array2<f64>,
+}
+
+impl DipoleQuadrupoleTerms {
+    pub fn new(
+        dipole_comp: &Array1<usize>,
+        mu2: &Array1<f64>,
+        quadrupole_comp: &Array1<usize>,
+        q2: &Array1<f64>,
+        sigma_mix: &Array1<f64>,
+        m_mix: &Array1<f64>,
+        s_ij: &Array2<f64>,
+        e_k_ij: &Array2<f64>,
+    ) -> Self {
+        let nd = dipole_comp.len();
+        let nq = quadrupole_comp.len();
+        let s = |i: usize, j: usize| s_ij[[i, j]];
+        let m = |i: usize| m_mix[i].min(2.0);
+
+        // dipole and quadrupole terms of the form mu^2 * sigma and Q^2 / sigma
+        let mu2_term = Array1::from_shape_fn(nd, |i| mu2[i] * sigma_mix[dipole_comp[i]]);
+        let q2_term = Array1::from_shape_fn(nq, |j| q2[j] / sigma_mix[quadrupole_comp[j]]);
+
+        let f2_term = Array2::from_shape_fn((nd, nq), |(i, j)| {
+            mu2[i] * q2[j] / s(dipole_comp[i], quadrupole_comp[j]).powi(5)
+        });
+        let f3_term_ddq = Array3::from_shape_fn((nd, nq, nd), |(i, j, k)| {
+            let (di, qj, dk) = (dipole_comp[i], quadrupole_comp[j], dipole_comp[k]);
+            mu2_term[i] * q2_term[j] * mu2_term[k] / (s(di, qj) * s(di, dk) * s(qj, dk)).powi(2)
+        });
+        let f3_term_dqq = Array3::from_shape_fn((nd, nq, nq), |(i, j, k)| {
+            let (di, qj, qk) = (dipole_comp[i], quadrupole_comp[j], quadrupole_comp[k]);
+            mu2_term[i] * q2_term[j] * q2_term[k] / (s(di, qj) * s(di, qk) * s(qj, qk)).powi(2)
+                * 1.19374
+        });
+
+        let mut mij1 = Array2::zeros((nd, nq));
+        let mut mij2 = Array2::zeros((nd, nq));
+        for i in 0..nd {
+            for j in 0..nq {
+                let mij = (m(dipole_comp[i]) * m(quadrupole_comp[j])).sqrt();
+                mij1[[i, j]] = (mij - 1.0) / mij;
+                mij2[[i, j]] = mij1[[i, j]] * (mij - 2.0) / mij;
+            }
+        }
+        let mijk_ddq = Array3::from_shape_fn((nd, nq, nd), |(i, j, k)| {
+            let mijk = (m(dipole_comp[i]) * m(quadrupole_comp[j]) * m(dipole_comp[k])).cbrt();
+            (mijk - 1.0) / mijk
+        });
+        let mijk_dqq = Array3::from_shape_fn((nd, nq, nq), |(i, j, k)| {
+            let mijk = (m(dipole_comp[i]) * m(quadrupole_comp[j]) * m(quadrupole_comp[k])).cbrt();
+            (mijk - 1.0) / mijk
+        });
+
+        Self {
+            dipole_comp: dipole_comp.clone(),
+            quadrupole_comp: quadrupole_comp.clone(),
+            mij1,
+            mij2,
+            mijk_ddq,
+            mijk_dqq,
+            f2_term,
+            f3_term_ddq,
+            f3_term_dqq,
+            e_k_ij: Array2::from_shape_fn((nd, nq), |(i, j)| {
+                e_k_ij[[dipole_comp[i], quadrupole_comp[j]]]
+            }),
+        }
+    }
+
+    /// Reduced Helmholtz energy density for the given component densities
+    /// and the total packing fraction.
+    pub fn helmholtz_energy_density<D: DualNum<f64>>(
+        &self,
+        temperature: D,
+        rho: &Array1<D>,
+        eta: D,
+    ) -> D {
+        let rho_d = self.dipole_comp.mapv(|i| rho[i]);
+        let rho_q = self.quadrupole_comp.mapv(|i| rho[i]);
+
+        let t_inv = temperature.inv();
+
+        let mut phi2 = D::zero();
+        let mut phi3 = D::zero();
+        for i in 0..rho_d.len() {
+            for j in 0..rho_q.len() {
+                phi2 -= (rho_d[i] * rho_q[j] * self.f2_term[[i, j]])
+                    * pair_integral_ij(
+                        self.mij1[[i, j]],
+                        self.mij2[[i, j]],
+                        eta,
+                        &ADQ,
+                        &BDQ,
+                        t_inv * self.e_k_ij[[i, j]],
+                    );
+                for k in 0..rho_d.len() {
+                    phi3 -= (rho_d[i] * rho_q[j] * rho_d[k] * self.f3_term_ddq[[i, j, k]])
+                        * triplet_integral_ijk_dq(self.mijk_ddq[[i, j, k]], eta);
+                }
+                for k in 0..rho_q.len() {
+                    phi3 -= (rho_d[i] * rho_q[j] * rho_q[k] * self.f3_term_dqq[[i, j, k]])
+                        * triplet_integral_ijk_dq(self.mijk_dqq[[i, j, k]], eta);
+                }
+            }
+        }
+        phi2 *= t_inv * t_inv * PI * 2.25;
+        phi3 *= t_inv.powi(3) * PI * PI;
+        pade(phi2, phi3)
+    }
+}
+
+pub struct Dipole {
+    parameters: Arc<GcPcSaftEosParameters>,
+    dipole: DipoleTerms,
+}
+
+impl Dipole {
+    pub fn new(parameters: &Arc<GcPcSaftEosParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            dipole: DipoleTerms::new(
+                &parameters.dipole_comp,
+                &parameters.mu2,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Dipole {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let p = &self.parameters;
+        let diameter = p.hs_diameter(state.temperature);
+        let eta = zeta(p, &diameter, &state.partial_density, [3])[0];
+        self.dipole
+            .helmholtz_energy_density(state.temperature, &state.partial_density, eta)
+            * state.volume
+    }
+}
+
 impl fmt::Display for Dipole {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Dipole")
     }
 }
+
+pub struct Quadrupole {
+    parameters: Arc<GcPcSaftEosParameters>,
+    quadrupole: QuadrupoleTerms,
+}
+
+impl Quadrupole {
+    pub fn new(parameters: &Arc<GcPcSaftEosParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            quadrupole: QuadrupoleTerms::new(
+                &parameters.quadrupole_comp,
+                &parameters.q2,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for Quadrupole {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let p = &self.parameters;
+        let diameter = p.hs_diameter(state.temperature);
+        let eta = zeta(p, &diameter, &state.partial_density, [3])[0];
+        self.quadrupole
+            .helmholtz_energy_density(state.temperature, &state.partial_density, eta)
+            * state.volume
+    }
+}
+
+impl fmt::Display for Quadrupole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Quadrupole")
+    }
+}
+
+pub struct DipoleQuadrupole {
+    parameters: Arc<GcPcSaftEosParameters>,
+    dipole_quadrupole: DipoleQuadrupoleTerms,
+}
+
+impl DipoleQuadrupole {
+    pub fn new(parameters: &Arc<GcPcSaftEosParameters>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            dipole_quadrupole: DipoleQuadrupoleTerms::new(
+                &parameters.dipole_comp,
+                &parameters.mu2,
+                &parameters.quadrupole_comp,
+                &parameters.q2,
+                &parameters.sigma_mix,
+                &parameters.m_mix,
+                &parameters.s_ij,
+                &parameters.e_k_ij,
+            ),
+        }
+    }
+}
+
+impl<D: DualNum<f64>> HelmholtzEnergyDual<D> for DipoleQuadrupole {
+    fn helmholtz_energy(&self, state: &StateHD<D>) -> D {
+        let p = &self.parameters;
+        let diameter = p.hs_diameter(state.temperature);
+        let eta = zeta(p, &diameter, &state.partial_density, [3])[0];
+        self.dipole_quadrupole.helmholtz_energy_density(
+            state.temperature,
+            &state.partial_density,
+            eta,
+        ) * state.volume
+    }
+}
+
+impl fmt::Display for DipoleQuadrupole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dipole-Quadrupole")
+    }
+}
@@ -0,0 +1,245 @@
+use super::GcPcSaft;
+use feos_core::{EosResult, EosUnit, EquationOfState, HelmholtzEnergy, State, StateHD};
+use ndarray::{Array1, Array2};
+use num_dual::Dual64;
+use quantity::si::{SIArray2, SIUnit};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Number of Gauss-Legendre points for the integration along the density.
+const QUADRATURE_POINTS: usize = 40;
+
+/// Segment-resolved residual Helmholtz energy and chemical potentials of a state.
+///
+/// Segments are indexed like `identifiers` and `component_index` of the
+/// [GcPcSaftEosParameters](super::GcPcSaftEosParameters), contributions in
+/// the order of [EquationOfState::residual].
+///
+/// The chemical potential of a segment is the derivative of the residual
+/// Helmholtz energy with respect to the amount of the segment at constant
+/// temperature, volume and amounts of all other segments. Bonds are shared
+/// equally between the bonded segments, the number of molecules in the mean
+/// segment number of the dispersion term is shared between the segments
+/// according to their segment number, and dipole and quadrupole moments are
+/// assigned to the first segment of a component with a moment. The shares of
+/// all segments of a component add up to its residual chemical potential.
+///
+/// The Helmholtz energy of a segment is its chemical potential integrated
+/// along the density from the ideal gas to the state, so that the shares of
+/// all segments add up to the residual Helmholtz energy.
+pub struct SegmentContributions {
+    /// Identifiers of the segments
+    pub identifiers: Vec<String>,
+    /// Index of the component of every segment
+    pub component_index: Array1<usize>,
+    /// Names of the Helmholtz energy contributions
+    pub contributions: Vec<String>,
+    /// Shares of the residual Helmholtz energy (contribution, segment)
+    pub helmholtz_energy: SIArray2,
+    /// Shares of the residual chemical potentials at constant volume (contribution, segment)
+    pub chemical_potential: SIArray2,
+}
+
+impl GcPcSaft {
+    /// Residual Helmholtz energy and chemical potentials of the state
+    /// resolved by segments and contributions.
+    pub fn segment_contributions(
+        &self,
+        state: &State<SIUnit, Self>,
+    ) -> EosResult<SegmentContributions> {
+        let parameters = &self.parameters;
+        // every segment is a component with the permittivity of its component
+        let component_index = parameters.component_index.to_vec();
+        let eos = Self::with_options(Arc::new(parameters.segment_resolved()), self.options)
+            .with_permittivity(self.permittivity.subset(&component_index))?;
+        let t = state
+            .temperature
+            .to_reduced(SIUnit::reference_temperature())?;
+        let v = state.volume.to_reduced(SIUnit::reference_volume())?;
+        let n = state.moles.to_reduced(SIUnit::reference_moles())?;
+        let moles = parameters.component_index.mapv(|i| n[i]);
+
+        let quadrature = gauss_legendre(QUADRATURE_POINTS);
+        let residual = eos.residual();
+        let mut a = Array2::zeros((residual.len(), moles.len()));
+        let mut mu = Array2::zeros((residual.len(), moles.len()));
+        for (k, contribution) in residual.iter().map(|c| c.as_ref()).enumerate() {
+            for i in 0..moles.len() {
+                mu[(k, i)] = segment_chemical_potential(contribution, t, v, &moles, i);
+                a[(k, i)] = quadrature
+                    .iter()
+                    .map(|&(x, w)| {
+                        w * segment_chemical_potential(contribution, t, v, &(&moles * x), i)
+                    })
+                    .sum::<f64>()
+                    * moles[i];
+            }
+        }
+
+        Ok(SegmentContributions {
+            identifiers: parameters.identifiers.clone(),
+            component_index: parameters.component_index.clone(),
+            contributions: residual.iter().map(|c| c.to_string()).collect(),
+            helmholtz_energy: a * SIUnit::reference_energy(),
+            chemical_potential: mu * (SIUnit::reference_energy() / SIUnit::reference_moles()),
+        })
+    }
+}
+
+/// Derivative of a contribution with respect to the amount of segment `i` (in reduced units).
+fn segment_chemical_potential(
+    contribution: &dyn HelmholtzEnergy,
+    temperature: f64,
+    volume: f64,
+    moles: &Array1<f64>,
+    i: usize,
+) -> f64 {
+    let mut moles = moles.mapv(Dual64::from_re);
+    moles[i] = moles[i].derive();
+    let state = StateHD::new(Dual64::from_re(temperature), Dual64::from_re(volume), moles);
+    contribution.helmholtz_energy(&state).eps[0] * temperature
+}
+
+/// Nodes and weights of the Gauss-Legendre quadrature on the interval [0, 1].
+fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|i| {
+            let mut x = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            let mut dp = 0.0;
+            for _ in 0..100 {
+                // Legendre polynomial and its derivative from the recurrence relation
+                let (mut p0, mut p1) = (1.0, x);
+                for k in 2..=n {
+                    let p2 = ((2 * k - 1) as f64 * x * p1 - (k - 1) as f64 * p0) / k as f64;
+                    p0 = p1;
+                    p1 = p2;
+                }
+                dp = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+                let dx = p1 / dp;
+                x -= dx;
+                if dx.abs() < 1e-15 {
+                    break;
+                }
+            }
+            (0.5 * (1.0 - x), 1.0 / ((1.0 - x * x) * dp * dp))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parameter::test::*;
+    use super::*;
+    use crate::eos::{GcPcSaftEosParameters, GcPcSaftOptions, Permittivity};
+    use crate::record::GcPcSaftRecord;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{ChemicalRecord, Identifier, SegmentRecord};
+    use feos_core::Contributions;
+    use ndarray::arr1;
+    use quantity::si::{SIArray1, KELVIN, METER, MOL};
+    use std::rc::Rc;
+
+    fn check(parameters: GcPcSaftEosParameters) -> EosResult<()> {
+        check_eos(
+            GcPcSaft::new(Arc::new(parameters)),
+            arr1(&[6000.0, 9000.0]) * MOL,
+        )
+    }
+
+    fn check_eos(eos: GcPcSaft, moles: SIArray1) -> EosResult<()> {
+        let eos = Rc::new(eos);
+        let state = State::new_nvt(&eos, 300.0 * KELVIN, METER.powi(3), &moles)?;
+        let segments = eos.segment_contributions(&state)?;
+        assert_eq!(segments.contributions.len(), eos.residual().len());
+        assert_relative_eq!(
+            segments.helmholtz_energy.sum(),
+            state.helmholtz_energy(Contributions::ResidualNvt),
+            max_relative = 1e-10
+        );
+        let mu = state.chemical_potential(Contributions::ResidualNvt);
+        for c in 0..eos.components() {
+            let mut mu_c = 0.0 * mu.get(c);
+            for (i, &ci) in segments.component_index.iter().enumerate() {
+                if ci == c {
+                    for k in 0..segments.contributions.len() {
+                        mu_c += segments.chemical_potential.get((k, i));
+                    }
+                }
+            }
+            assert_relative_eq!(mu_c, mu.get(c), max_relative = 1e-10);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gauss_legendre() {
+        let quadrature = gauss_legendre(QUADRATURE_POINTS);
+        assert_relative_eq!(
+            quadrature.iter().map(|(_, w)| w).sum::<f64>(),
+            1.0,
+            max_relative = 1e-14
+        );
+        assert_relative_eq!(
+            quadrature.iter().map(|(x, w)| w * x.powi(7)).sum::<f64>(),
+            0.125,
+            max_relative = 1e-14
+        );
+    }
+
+    #[test]
+    fn test_segment_contributions_association() -> EosResult<()> {
+        check(ethanol_propanol(true))
+    }
+
+    #[test]
+    fn test_segment_contributions_dipole() -> EosResult<()> {
+        check(ethanol_acetone(false))
+    }
+
+    #[test]
+    fn test_segment_contributions_quadrupole() -> EosResult<()> {
+        check(propane_co2())
+    }
+
+    #[test]
+    fn test_segment_contributions_permittivity() -> EosResult<()> {
+        let segment = |identifier: &str, m, sigma, epsilon_k, z| {
+            let record = GcPcSaftRecord {
+                m,
+                sigma,
+                epsilon_k,
+                z,
+                ..Default::default()
+            };
+            SegmentRecord::new(identifier.to_string(), 20.0, record, None)
+        };
+        let segment_records = vec![
+            segment("H2O", 1.2047, 2.7927, 353.95, None),
+            segment("CH3", 0.77247, 3.6937, 181.49, None),
+            segment("CH2", 0.7912, 3.0207, 157.23, None),
+            segment("Na+", 1.0, 2.8232, 230.0, Some(1.0)),
+            segment("Cl-", 1.0, 2.7560, 170.0, Some(-1.0)),
+        ];
+        let record = |name: &str, segments: &[&str]| {
+            ChemicalRecord::new(
+                Identifier::new("", Some(name), None, None, None, None),
+                segments.iter().map(|&s| s.to_string()).collect(),
+                None,
+            )
+        };
+        let chemical_records = vec![
+            record("water", &["H2O"]),
+            record("ethane-like solvent", &["CH3", "CH2"]),
+            record("sodium chloride", &["Na+", "Cl-"]),
+        ];
+        let parameters =
+            GcPcSaftEosParameters::from_segments(chemical_records, segment_records, None)?;
+        let options = GcPcSaftOptions {
+            born: true,
+            ..Default::default()
+        };
+        let eos = GcPcSaft::with_options(Arc::new(parameters), options)
+            .with_permittivity(Permittivity::SaltFreeMixing(vec![78.4, 20.0, 10.0]))?;
+        check_eos(eos, arr1(&[40000.0, 10000.0, 500.0]) * MOL)
+    }
+}
@@ -0,0 +1,1150 @@
+//! Regression of segment parameters to experimental data.
+//!
+//! Selected parameters of selected segments are adjusted to experimental
+//! vapor pressures, saturated liquid densities and enthalpies of vaporization
+//! of pure components using a Levenberg-Marquardt algorithm with a finite
+//! difference approximation of the Jacobian.
+//!
+//! Binary interaction parameters between segments are adjusted to bubble and
+//! dew point pressures of binary mixtures in the same way.
+use crate::eos::{GcPcSaft, GcPcSaftEosParameters};
+use crate::record::{GcPcSaftBinaryRecord, GcPcSaftRecord, SiteInteraction};
+use feos_core::joback::JobackRecord;
+use feos_core::parameter::{BinaryRecord, ChemicalRecord, ParameterError, SegmentRecord};
+use feos_core::{Contributions, EosResult, PhaseEquilibrium, SolverOptions};
+use ndarray::{arr1, Array1, Array2};
+use num_dual::linalg::LU;
+use quantity::si::{SIArray1, SINumber, SIUnit};
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Parameter of a [GcPcSaftRecord] that can be adjusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordParameter {
+    M,
+    Sigma,
+    EpsilonK,
+    Mu,
+    Q,
+    /// Association volume of the self-association of the segment, given either
+    /// by `kappa_ab` or by all interactions of the `association_record`
+    KappaAB,
+    /// Association energy of the self-association of the segment, given either
+    /// by `epsilon_k_ab` or by all interactions of the `association_record`
+    EpsilonKAB,
+}
+
+impl RecordParameter {
+    fn get(&self, record: &GcPcSaftRecord) -> f64 {
+        match self {
+            Self::M => record.m,
+            Self::Sigma => record.sigma,
+            Self::EpsilonK => record.epsilon_k,
+            Self::Mu => record.mu.unwrap_or(0.0),
+            Self::Q => record.q.unwrap_or(0.0),
+            Self::KappaAB => record
+                .kappa_ab
+                .or_else(|| interactions(record).first().map(|i| i.kappa_ab))
+                .unwrap_or(0.0),
+            Self::EpsilonKAB => record
+                .epsilon_k_ab
+                .or_else(|| interactions(record).first().map(|i| i.epsilon_k_ab))
+                .unwrap_or(0.0),
+        }
+    }
+
+    fn set(&self, record: &mut GcPcSaftRecord, value: f64) {
+        match self {
+            Self::M => record.m = value,
+            Self::Sigma => record.sigma = value,
+            Self::EpsilonK => record.epsilon_k = value,
+            Self::Mu => record.mu = Some(value),
+            Self::Q => record.q = Some(value),
+            Self::KappaAB => {
+                if record.kappa_ab.is_some() || interactions(record).is_empty() {
+                    record.kappa_ab = Some(value);
+                }
+                for interaction in interactions_mut(record) {
+                    interaction.kappa_ab = value;
+                }
+            }
+            Self::EpsilonKAB => {
+                if record.epsilon_k_ab.is_some() || interactions(record).is_empty() {
+                    record.epsilon_k_ab = Some(value);
+                }
+                for interaction in interactions_mut(record) {
+                    interaction.epsilon_k_ab = value;
+                }
+            }
+        }
+    }
+}
+
+/// Site-site interactions of the association record of a segment.
+fn interactions(record: &GcPcSaftRecord) -> &[SiteInteraction] {
+    record
+        .association_record
+        .as_ref()
+        .map_or(&[][..], |r| &r.interactions[..])
+}
+
+fn interactions_mut(record: &mut GcPcSaftRecord) -> &mut [SiteInteraction] {
+    record
+        .association_record
+        .as_mut()
+        .map_or(&mut [][..], |r| &mut r.interactions[..])
+}
+
+/// Free parameter of the regression: a parameter of a single segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentParameter {
+    /// Identifier of the segment
+    pub segment: String,
+    /// Adjusted parameter
+    pub parameter: RecordParameter,
+}
+
+impl SegmentParameter {
+    /// Create a new `SegmentParameter`.
+    pub fn new(segment: &str, parameter: RecordParameter) -> Self {
+        Self {
+            segment: segment.into(),
+            parameter,
+        }
+    }
+}
+
+/// Pure component property that can be used in the regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// Vapor pressure
+    VaporPressure,
+    /// Molar density of the saturated liquid
+    LiquidDensity,
+    /// Molar enthalpy of vaporization
+    EnthalpyOfVaporization,
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VaporPressure => write!(f, "vapor pressure"),
+            Self::LiquidDensity => write!(f, "liquid density"),
+            Self::EnthalpyOfVaporization => write!(f, "enthalpy of vaporization"),
+        }
+    }
+}
+
+/// Experimental data of a single property at given temperatures.
+#[derive(Clone)]
+pub struct DataSet {
+    pub property: Property,
+    pub temperature: SIArray1,
+    pub target: SIArray1,
+    /// Weight of the data set in the objective function
+    pub weight: f64,
+}
+
+impl DataSet {
+    /// Create a new `DataSet` with unit weight.
+    pub fn new(property: Property, temperature: SIArray1, target: SIArray1) -> Self {
+        Self {
+            property,
+            temperature,
+            target,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Experimental data of a molecule.
+#[derive(Clone)]
+pub struct MoleculeData {
+    pub chemical_record: ChemicalRecord,
+    pub datasets: Vec<DataSet>,
+}
+
+impl MoleculeData {
+    /// Create a new `MoleculeData`.
+    pub fn new(chemical_record: ChemicalRecord, datasets: Vec<DataSet>) -> Self {
+        Self {
+            chemical_record,
+            datasets,
+        }
+    }
+}
+
+/// Check that a data set contains at least one data point and the same
+/// number of values for every quantity.
+fn check_data_points(property: &dyn fmt::Display, lengths: &[usize]) -> Result<(), ParameterError> {
+    if lengths[0] == 0 {
+        return Err(ParameterError::IncompatibleParameters(format!(
+            "the data set of the {} contains no data points",
+            property
+        )));
+    }
+    if lengths.iter().any(|&n| n != lengths[0]) {
+        return Err(ParameterError::IncompatibleParameters(format!(
+            "the quantities in the data set of the {} have different lengths {:?}",
+            property, lengths
+        )));
+    }
+    Ok(())
+}
+
+/// Options for the Levenberg-Marquardt algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct EstimatorOptions {
+    /// Maximum number of iterations
+    pub max_iter: usize,
+    /// Tolerance for the relative change of the objective function
+    pub tol: f64,
+    /// Initial damping parameter
+    pub damping: f64,
+    /// Relative step size of the finite differences
+    pub step: f64,
+    /// Residual used for data points that could not be calculated
+    pub penalty: f64,
+}
+
+impl Default for EstimatorOptions {
+    fn default() -> Self {
+        Self {
+            max_iter: 50,
+            tol: 1e-10,
+            damping: 1e-3,
+            step: 1e-6,
+            penalty: 1.0,
+        }
+    }
+}
+
+/// Regression of segment parameters to pure component data.
+pub struct SegmentEstimator {
+    segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+    binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+    parameters: Vec<SegmentParameter>,
+    molecules: Vec<MoleculeData>,
+}
+
+/// Result of the regression.
+pub struct Estimation {
+    /// Segment records with the adjusted parameters
+    pub segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+    /// Values of the free parameters
+    pub parameters: Array1<f64>,
+    /// Number of iterations
+    pub iterations: usize,
+    /// Whether the change of the objective function or the parameters fell below
+    /// the tolerance before the maximum number of iterations was reached
+    pub converged: bool,
+    /// Final value of the objective function (sum of squared weighted relative deviations)
+    pub cost: f64,
+    /// Average absolute relative deviation per molecule and data set
+    pub aard: Vec<Vec<f64>>,
+}
+
+impl SegmentEstimator {
+    /// Create a new `SegmentEstimator`.
+    ///
+    /// Returns an error if a free parameter refers to a segment that is not
+    /// contained in `segment_records` or if a data set is empty or contains
+    /// a different number of temperatures and target values.
+    pub fn new(
+        segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+        parameters: Vec<SegmentParameter>,
+        molecules: Vec<MoleculeData>,
+    ) -> Result<Self, ParameterError> {
+        for p in parameters.iter() {
+            if !segment_records.iter().any(|s| s.identifier == p.segment) {
+                return Err(ParameterError::ComponentsNotFound(p.segment.clone()));
+            }
+        }
+        for dataset in molecules.iter().flat_map(|m| m.datasets.iter()) {
+            check_data_points(
+                &dataset.property,
+                &[dataset.temperature.len(), dataset.target.len()],
+            )?;
+        }
+        Ok(Self {
+            segment_records,
+            binary_segment_records,
+            parameters,
+            molecules,
+        })
+    }
+
+    /// Current values of the free parameters.
+    pub fn initial_parameters(&self) -> Array1<f64> {
+        self.parameters
+            .iter()
+            .map(|p| {
+                let segment = self
+                    .segment_records
+                    .iter()
+                    .find(|s| s.identifier == p.segment)
+                    .unwrap();
+                p.parameter.get(&segment.model_record)
+            })
+            .collect()
+    }
+
+    /// Segment records for given values of the free parameters.
+    pub fn segment_records(
+        &self,
+        parameters: &Array1<f64>,
+    ) -> Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> {
+        let mut segment_records = self.segment_records.clone();
+        for (p, &value) in self.parameters.iter().zip(parameters.iter()) {
+            for segment in segment_records
+                .iter_mut()
+                .filter(|s| s.identifier == p.segment)
+            {
+                p.parameter.set(&mut segment.model_record, value);
+            }
+        }
+        segment_records
+    }
+
+    /// Relative deviations between model and experiment for every data set of
+    /// every molecule. Data points that cannot be calculated are `NaN`.
+    pub fn relative_deviations(&self, parameters: &Array1<f64>) -> Vec<Vec<Array1<f64>>> {
+        let segment_records = self.segment_records(parameters);
+        self.molecules
+            .iter()
+            .map(|molecule| {
+                let eos = GcPcSaftEosParameters::from_segments(
+                    vec![molecule.chemical_record.clone()],
+                    segment_records.clone(),
+                    self.binary_segment_records.clone(),
+                )
+                .map(|p| Rc::new(GcPcSaft::new(Arc::new(p))));
+                molecule
+                    .datasets
+                    .iter()
+                    .map(|dataset| match &eos {
+                        Ok(eos) => (0..dataset.temperature.len())
+                            .map(|i| {
+                                let t = dataset.temperature.get(i);
+                                let target = dataset.target.get(i);
+                                predict(eos, dataset.property, t)
+                                    .and_then(|model| Ok((model / target).into_value()? - 1.0))
+                                    .unwrap_or(f64::NAN)
+                            })
+                            .collect(),
+                        Err(_) => Array1::from_elem(dataset.temperature.len(), f64::NAN),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Weighted residuals of all data points used in the objective function.
+    fn residuals(&self, parameters: &Array1<f64>, penalty: f64) -> Array1<f64> {
+        let deviations = self.relative_deviations(parameters);
+        let mut residuals = Vec::new();
+        for (molecule, deviations) in self.molecules.iter().zip(deviations) {
+            for (dataset, deviations) in molecule.datasets.iter().zip(deviations) {
+                extend_residuals(&mut residuals, &deviations, dataset.weight, penalty);
+            }
+        }
+        Array1::from_vec(residuals)
+    }
+
+    /// Average absolute relative deviations per molecule and data set.
+    pub fn aard(&self, parameters: &Array1<f64>) -> Vec<Vec<f64>> {
+        self.relative_deviations(parameters)
+            .iter()
+            .map(|m| m.iter().map(|d| d.mapv(f64::abs).mean().unwrap()).collect())
+            .collect()
+    }
+
+    /// Adjust the free parameters using the Levenberg-Marquardt algorithm.
+    pub fn fit(&self, options: EstimatorOptions) -> EosResult<Estimation> {
+        let (x, iterations, cost, converged) = levenberg_marquardt(
+            self.initial_parameters(),
+            |x| self.residuals(x, options.penalty),
+            options,
+        )?;
+        Ok(Estimation {
+            segment_records: self.segment_records(&x),
+            aard: self.aard(&x),
+            parameters: x,
+            iterations,
+            converged,
+            cost,
+        })
+    }
+}
+
+/// Append the weighted deviations of a data set to the residuals. Every data set
+/// contributes with its weight independent of the number of data points.
+fn extend_residuals(residuals: &mut Vec<f64>, deviations: &Array1<f64>, weight: f64, penalty: f64) {
+    let weight = (weight / deviations.len() as f64).sqrt();
+    residuals.extend(
+        deviations
+            .iter()
+            .map(|&d| weight * if d.is_finite() { d } else { penalty }),
+    );
+}
+
+/// Minimize the sum of squared residuals using the Levenberg-Marquardt algorithm.
+///
+/// Returns the optimal parameters, the number of iterations, the final
+/// value of the objective function and whether the algorithm converged. The
+/// algorithm stops without convergence if no step reduces the objective function.
+fn levenberg_marquardt<F>(
+    x0: Array1<f64>,
+    residuals: F,
+    options: EstimatorOptions,
+) -> EosResult<(Array1<f64>, usize, f64, bool)>
+where
+    F: Fn(&Array1<f64>) -> Array1<f64>,
+{
+    let n = x0.len();
+    let mut x = x0;
+    let scale = x.mapv(|x| if x != 0.0 { x.abs() } else { 1.0 });
+    let mut r = residuals(&x);
+    let mut cost = r.dot(&r);
+    let mut damping = options.damping;
+    let mut iterations = 0;
+    let mut converged = false;
+
+    for k in 1..=options.max_iter {
+        iterations = k;
+
+        // Jacobian from forward differences
+        let mut jacobian = Array2::zeros((r.len(), n));
+        for j in 0..n {
+            let h = options.step * scale[j];
+            let mut xh = x.clone();
+            xh[j] += h;
+            let rh = residuals(&xh);
+            jacobian.column_mut(j).assign(&((rh - &r) / h));
+        }
+        let jtj = jacobian.t().dot(&jacobian);
+        let jtr = jacobian.t().dot(&r);
+
+        // increase damping until the step reduces the objective function
+        let step = loop {
+            let mut a = jtj.clone();
+            for j in 0..n {
+                a[(j, j)] += damping * jtj[(j, j)].max(1e-12);
+            }
+            let dx = LU::new(a)?.solve(&(-&jtr));
+            let x_new = &x + &dx;
+            let r_new = residuals(&x_new);
+            let cost_new = r_new.dot(&r_new);
+            if cost_new <= cost {
+                let converged = (cost - cost_new) <= options.tol * cost.max(f64::EPSILON)
+                    || (&dx / &scale).mapv(f64::abs).sum() < options.tol;
+                x = x_new;
+                r = r_new;
+                cost = cost_new;
+                damping = (damping / 10.0).max(1e-12);
+                break Some(converged);
+            }
+            damping *= 10.0;
+            if damping > 1e12 {
+                break None;
+            }
+        };
+        match step {
+            Some(true) => {
+                converged = true;
+                break;
+            }
+            Some(false) => (),
+            None => break,
+        }
+    }
+    Ok((x, iterations, cost, converged))
+}
+
+/// Model prediction of a pure component property at given temperature.
+fn predict(eos: &Rc<GcPcSaft>, property: Property, temperature: SINumber) -> EosResult<SINumber> {
+    let vle =
+        PhaseEquilibrium::<SIUnit, _, 2>::pure(eos, temperature, None, SolverOptions::default())?;
+    Ok(match property {
+        Property::VaporPressure => vle.vapor().pressure(Contributions::Total),
+        Property::LiquidDensity => vle.liquid().density,
+        Property::EnthalpyOfVaporization => {
+            vle.vapor().molar_enthalpy(Contributions::Total)
+                - vle.liquid().molar_enthalpy(Contributions::Total)
+        }
+    })
+}
+
+impl fmt::Display for Estimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Estimation(iterations={}, converged={}, cost={:e}, parameters={})",
+            self.iterations, self.converged, self.cost, self.parameters
+        )
+    }
+}
+
+/// Parameter of a [GcPcSaftBinaryRecord] that can be adjusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryRecordParameter {
+    /// Constant part of $k_{ij}$
+    KIJ,
+    /// Coefficient of the $1/T$ term of $k_{ij}$
+    KIJT,
+    /// Coefficient of the $\ln T$ term of $k_{ij}$
+    KIJLnT,
+    /// Binary interaction parameter for the segment diameter
+    LIJ,
+}
+
+impl BinaryRecordParameter {
+    fn get(&self, record: &GcPcSaftBinaryRecord) -> f64 {
+        match self {
+            Self::KIJ => record.k_ij,
+            Self::KIJT => record.k_ij_t,
+            Self::KIJLnT => record.k_ij_ln_t,
+            Self::LIJ => record.l_ij,
+        }
+    }
+
+    fn set(&self, record: &mut GcPcSaftBinaryRecord, value: f64) {
+        match self {
+            Self::KIJ => record.k_ij = value,
+            Self::KIJT => record.k_ij_t = value,
+            Self::KIJLnT => record.k_ij_ln_t = value,
+            Self::LIJ => record.l_ij = value,
+        }
+    }
+}
+
+/// Free parameter of the regression: a binary parameter of a pair of segments.
+///
+/// The parameter is shared by all mixtures that contain the segment pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinarySegmentParameter {
+    /// Identifier of the first segment
+    pub id1: String,
+    /// Identifier of the second segment
+    pub id2: String,
+    /// Adjusted parameter
+    pub parameter: BinaryRecordParameter,
+}
+
+impl BinarySegmentParameter {
+    /// Create a new `BinarySegmentParameter`.
+    pub fn new(id1: &str, id2: &str, parameter: BinaryRecordParameter) -> Self {
+        Self {
+            id1: id1.into(),
+            id2: id2.into(),
+            parameter,
+        }
+    }
+
+    fn matches(&self, record: &BinaryRecord<String, GcPcSaftBinaryRecord>) -> bool {
+        record.id1 == self.id1 && record.id2 == self.id2
+            || record.id1 == self.id2 && record.id2 == self.id1
+    }
+}
+
+/// Binary mixture property that can be used in the regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryProperty {
+    /// Bubble point pressure at given temperature and liquid composition
+    BubblePointPressure,
+    /// Dew point pressure at given temperature and vapor composition
+    DewPointPressure,
+}
+
+impl fmt::Display for BinaryProperty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BubblePointPressure => write!(f, "bubble point pressure"),
+            Self::DewPointPressure => write!(f, "dew point pressure"),
+        }
+    }
+}
+
+/// Experimental phase equilibrium data of a binary mixture.
+#[derive(Clone)]
+pub struct BinaryDataSet {
+    pub property: BinaryProperty,
+    pub temperature: SIArray1,
+    pub pressure: SIArray1,
+    /// Mole fraction of the first component in the liquid (bubble points)
+    /// or in the vapor (dew points)
+    pub molefracs: Array1<f64>,
+    /// Weight of the data set in the objective function
+    pub weight: f64,
+}
+
+impl BinaryDataSet {
+    /// Create a new `BinaryDataSet` with unit weight.
+    pub fn new(
+        property: BinaryProperty,
+        temperature: SIArray1,
+        pressure: SIArray1,
+        molefracs: Array1<f64>,
+    ) -> Self {
+        Self {
+            property,
+            temperature,
+            pressure,
+            molefracs,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Experimental data of a binary mixture.
+#[derive(Clone)]
+pub struct MixtureData {
+    pub chemical_records: [ChemicalRecord; 2],
+    pub datasets: Vec<BinaryDataSet>,
+}
+
+impl MixtureData {
+    /// Create a new `MixtureData`.
+    pub fn new(chemical_records: [ChemicalRecord; 2], datasets: Vec<BinaryDataSet>) -> Self {
+        Self {
+            chemical_records,
+            datasets,
+        }
+    }
+}
+
+/// Regression of binary segment parameters to phase equilibrium data of binary mixtures.
+pub struct BinarySegmentEstimator {
+    segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+    binary_segment_records: Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>,
+    parameters: Vec<BinarySegmentParameter>,
+    mixtures: Vec<MixtureData>,
+}
+
+/// Result of the regression of binary segment parameters.
+pub struct BinaryEstimation {
+    /// Binary segment records with the adjusted parameters
+    pub binary_segment_records: Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>,
+    /// Values of the free parameters
+    pub parameters: Array1<f64>,
+    /// Number of iterations
+    pub iterations: usize,
+    /// Whether the change of the objective function or the parameters fell below
+    /// the tolerance before the maximum number of iterations was reached
+    pub converged: bool,
+    /// Final value of the objective function (sum of squared weighted relative deviations)
+    pub cost: f64,
+    /// Average absolute relative deviation per mixture and data set
+    pub aard: Vec<Vec<f64>>,
+}
+
+impl BinarySegmentEstimator {
+    /// Create a new `BinarySegmentEstimator`.
+    ///
+    /// The segment records and all binary segment records that are not
+    /// adjusted stay fixed. Free parameters of segment pairs without a binary
+    /// segment record start from zero.
+    ///
+    /// Returns an error if a free parameter refers to a segment that is not
+    /// contained in `segment_records` or if a data set is empty or contains
+    /// a different number of temperatures, pressures and mole fractions.
+    pub fn new(
+        segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+        binary_segment_records: Option<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>>,
+        parameters: Vec<BinarySegmentParameter>,
+        mixtures: Vec<MixtureData>,
+    ) -> Result<Self, ParameterError> {
+        let mut binary_segment_records = binary_segment_records.unwrap_or_default();
+        for p in parameters.iter() {
+            for id in [&p.id1, &p.id2] {
+                if !segment_records.iter().any(|s| &s.identifier == id) {
+                    return Err(ParameterError::ComponentsNotFound(id.clone()));
+                }
+            }
+            if !binary_segment_records.iter().any(|b| p.matches(b)) {
+                binary_segment_records.push(BinaryRecord::new(
+                    p.id1.clone(),
+                    p.id2.clone(),
+                    GcPcSaftBinaryRecord::default(),
+                ));
+            }
+        }
+        for dataset in mixtures.iter().flat_map(|m| m.datasets.iter()) {
+            check_data_points(
+                &dataset.property,
+                &[
+                    dataset.temperature.len(),
+                    dataset.pressure.len(),
+                    dataset.molefracs.len(),
+                ],
+            )?;
+        }
+        Ok(Self {
+            segment_records,
+            binary_segment_records,
+            parameters,
+            mixtures,
+        })
+    }
+
+    /// Current values of the free parameters.
+    pub fn initial_parameters(&self) -> Array1<f64> {
+        self.parameters
+            .iter()
+            .map(|p| {
+                let record = self
+                    .binary_segment_records
+                    .iter()
+                    .find(|b| p.matches(b))
+                    .unwrap();
+                p.parameter.get(&record.model_record)
+            })
+            .collect()
+    }
+
+    /// Binary segment records for given values of the free parameters.
+    pub fn binary_segment_records(
+        &self,
+        parameters: &Array1<f64>,
+    ) -> Vec<BinaryRecord<String, GcPcSaftBinaryRecord>> {
+        let mut binary_segment_records = self.binary_segment_records.clone();
+        for (p, &value) in self.parameters.iter().zip(parameters.iter()) {
+            for record in binary_segment_records.iter_mut().filter(|b| p.matches(b)) {
+                p.parameter.set(&mut record.model_record, value);
+            }
+        }
+        binary_segment_records
+    }
+
+    /// Relative deviations between model and experiment for every data set of
+    /// every mixture. Data points that cannot be calculated are `NaN`.
+    pub fn relative_deviations(&self, parameters: &Array1<f64>) -> Vec<Vec<Array1<f64>>> {
+        let binary_segment_records = self.binary_segment_records(parameters);
+        self.mixtures
+            .iter()
+            .map(|mixture| {
+                let eos = GcPcSaftEosParameters::from_segments(
+                    mixture.chemical_records.to_vec(),
+                    self.segment_records.clone(),
+                    Some(binary_segment_records.clone()),
+                )
+                .map(|p| Rc::new(GcPcSaft::new(Arc::new(p))));
+                mixture
+                    .datasets
+                    .iter()
+                    .map(|dataset| match &eos {
+                        Ok(eos) => (0..dataset.temperature.len())
+                            .map(|i| {
+                                let t = dataset.temperature.get(i);
+                                let target = dataset.pressure.get(i);
+                                let x = dataset.molefracs[i];
+                                predict_binary(eos, dataset.property, t, x, target)
+                                    .and_then(|model| Ok((model / target).into_value()? - 1.0))
+                                    .unwrap_or(f64::NAN)
+                            })
+                            .collect(),
+                        Err(_) => Array1::from_elem(dataset.temperature.len(), f64::NAN),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Weighted residuals of all data points used in the objective function.
+    fn residuals(&self, parameters: &Array1<f64>, penalty: f64) -> Array1<f64> {
+        let deviations = self.relative_deviations(parameters);
+        let mut residuals = Vec::new();
+        for (mixture, deviations) in self.mixtures.iter().zip(deviations) {
+            for (dataset, deviations) in mixture.datasets.iter().zip(deviations) {
+                extend_residuals(&mut residuals, &deviations, dataset.weight, penalty);
+            }
+        }
+        Array1::from_vec(residuals)
+    }
+
+    /// Average absolute relative deviations per mixture and data set.
+    pub fn aard(&self, parameters: &Array1<f64>) -> Vec<Vec<f64>> {
+        self.relative_deviations(parameters)
+            .iter()
+            .map(|m| m.iter().map(|d| d.mapv(f64::abs).mean().unwrap()).collect())
+            .collect()
+    }
+
+    /// Adjust the free parameters using the Levenberg-Marquardt algorithm.
+    pub fn fit(&self, options: EstimatorOptions) -> EosResult<BinaryEstimation> {
+        let (x, iterations, cost, converged) = levenberg_marquardt(
+            self.initial_parameters(),
+            |x| self.residuals(x, options.penalty),
+            options,
+        )?;
+        Ok(BinaryEstimation {
+            binary_segment_records: self.binary_segment_records(&x),
+            aard: self.aard(&x),
+            parameters: x,
+            iterations,
+            converged,
+            cost,
+        })
+    }
+}
+
+impl BinaryEstimation {
+    /// Write the binary segment records to a JSON file that can be used
+    /// in [GcPcSaftEosParameters::from_json_segments].
+    pub fn to_json<P: AsRef<Path>>(&self, file: P) -> Result<(), ParameterError> {
+        let writer = BufWriter::new(File::create(file)?);
+        serde_json::to_writer_pretty(writer, &self.binary_segment_records)?;
+        Ok(())
+    }
+}
+
+/// Model prediction of a bubble or dew point pressure of a binary mixture.
+fn predict_binary(
+    eos: &Rc<GcPcSaft>,
+    property: BinaryProperty,
+    temperature: SINumber,
+    molefrac: f64,
+    pressure: SINumber,
+) -> EosResult<SINumber> {
+    let molefracs = arr1(&[molefrac, 1.0 - molefrac]);
+    let options = (SolverOptions::default(), SolverOptions::default());
+    let vle = match property {
+        BinaryProperty::BubblePointPressure => PhaseEquilibrium::bubble_point(
+            eos,
+            temperature,
+            &molefracs,
+            Some(pressure),
+            None,
+            options,
+        )?,
+        BinaryProperty::DewPointPressure => PhaseEquilibrium::dew_point(
+            eos,
+            temperature,
+            &molefracs,
+            Some(pressure),
+            None,
+            options,
+        )?,
+    };
+    Ok(vle.vapor().pressure(Contributions::Total))
+}
+
+impl fmt::Display for BinaryEstimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BinaryEstimation(iterations={}, converged={}, cost={:e}, parameters={})",
+            self.iterations, self.converged, self.cost, self.parameters
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::record::{AssociationRecord, AssociationSite};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, IdentifierOption};
+    use quantity::si::{BAR, KELVIN};
+    use std::io::BufReader;
+
+    fn alkane(name: &str, n: usize) -> ChemicalRecord {
+        let mut segments = vec!["CH3".to_string()];
+        segments.extend(vec!["CH2".to_string(); n - 2]);
+        segments.push("CH3".into());
+        ChemicalRecord::new(
+            Identifier::new("", Some(name), None, None, None, None),
+            segments,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_fit_synthetic_data() -> EosResult<()> {
+        let reader = BufReader::new(File::open("parameters/sauer2014_hetero.json").unwrap());
+        let segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> =
+            serde_json::from_reader(reader).unwrap();
+        let free = vec![SegmentParameter::new("CH2", RecordParameter::EpsilonK)];
+        let temperature = arr1(&[250.0, 300.0, 350.0]) * KELVIN;
+
+        // generate data with the original parameters
+        let molecules: Vec<_> = [("butane", 4), ("hexane", 6)]
+            .iter()
+            .map(|&(name, n)| {
+                let record = alkane(name, n);
+                let params = GcPcSaftEosParameters::from_segments(
+                    vec![record.clone()],
+                    segment_records.clone(),
+                    None,
+                )?;
+                let eos = Rc::new(GcPcSaft::new(Arc::new(params)));
+                let datasets = [
+                    Property::VaporPressure,
+                    Property::LiquidDensity,
+                    Property::EnthalpyOfVaporization,
+                ]
+                .iter()
+                .map(|&property| {
+                    let target = (0..temperature.len())
+                        .map(|i| predict(&eos, property, temperature.get(i)))
+                        .collect::<EosResult<_>>()?;
+                    Ok(DataSet::new(
+                        property,
+                        temperature.clone(),
+                        SIArray1::from_vec(target),
+                    ))
+                })
+                .collect::<EosResult<_>>()?;
+                Ok(MoleculeData::new(record, datasets))
+            })
+            .collect::<EosResult<_>>()?;
+
+        // perturb the parameter and fit it again
+        let epsilon_k = segment_records
+            .iter()
+            .find(|s| s.identifier == "CH2")
+            .unwrap()
+            .model_record
+            .epsilon_k;
+        let mut perturbed = segment_records.clone();
+        for s in perturbed.iter_mut().filter(|s| s.identifier == "CH2") {
+            s.model_record.epsilon_k *= 1.05;
+        }
+        let estimator = SegmentEstimator::new(perturbed, None, free, molecules)?;
+        assert!(estimator.aard(&estimator.initial_parameters())[0][0] > 0.05);
+
+        let estimation = estimator.fit(EstimatorOptions::default())?;
+        assert!(estimation.converged);
+        assert_relative_eq!(estimation.parameters[0], epsilon_k, max_relative = 1e-6);
+        let ch2 = estimation
+            .segment_records
+            .iter()
+            .find(|s| s.identifier == "CH2")
+            .unwrap();
+        assert_relative_eq!(ch2.model_record.epsilon_k, epsilon_k, max_relative = 1e-6);
+        assert_eq!(estimation.aard.len(), 2);
+        for aard in estimation.aard.iter().flatten() {
+            assert!(*aard < 1e-6);
+        }
+        Ok(())
+    }
+
+    fn alcohol(name: &str, n: usize) -> ChemicalRecord {
+        let mut segments = vec!["CH3".to_string()];
+        segments.extend(vec!["CH2".to_string(); n - 1]);
+        segments.push("OH".into());
+        ChemicalRecord::new(
+            Identifier::new("", Some(name), None, None, None, None),
+            segments,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_fit_binary_synthetic_data() -> EosResult<()> {
+        let reader = BufReader::new(File::open("parameters/sauer2014_hetero.json").unwrap());
+        let segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> =
+            serde_json::from_reader(reader).unwrap();
+        let k_ij = -0.0087;
+        let binary = vec![BinaryRecord::new(
+            "CH3".to_string(),
+            "OH".to_string(),
+            k_ij.into(),
+        )];
+        let free = vec![BinarySegmentParameter::new(
+            "OH",
+            "CH3",
+            BinaryRecordParameter::KIJ,
+        )];
+        let temperature = arr1(&[320.0, 320.0, 320.0]) * KELVIN;
+        let molefracs = arr1(&[0.2, 0.5, 0.8]);
+
+        // generate data with the original parameters
+        let mixtures: Vec<_> = [
+            (alcohol("ethanol", 2), alkane("hexane", 6)),
+            (alcohol("1-propanol", 3), alkane("pentane", 5)),
+        ]
+        .iter()
+        .map(|(alcohol, alkane)| {
+            let records = [alcohol.clone(), alkane.clone()];
+            let params = GcPcSaftEosParameters::from_segments(
+                records.to_vec(),
+                segment_records.clone(),
+                Some(binary.clone()),
+            )?;
+            let eos = Rc::new(GcPcSaft::new(Arc::new(params)));
+            let datasets = [
+                BinaryProperty::BubblePointPressure,
+                BinaryProperty::DewPointPressure,
+            ]
+            .iter()
+            .map(|&property| {
+                let pressure = (0..temperature.len())
+                    .map(|i| {
+                        let x = arr1(&[molefracs[i], 1.0 - molefracs[i]]);
+                        let options = (SolverOptions::default(), SolverOptions::default());
+                        let t = temperature.get(i);
+                        let vle = match property {
+                            BinaryProperty::BubblePointPressure => {
+                                PhaseEquilibrium::bubble_point(&eos, t, &x, None, None, options)
+                            }
+                            BinaryProperty::DewPointPressure => {
+                                PhaseEquilibrium::dew_point(&eos, t, &x, None, None, options)
+                            }
+                        }?;
+                        Ok(vle.vapor().pressure(Contributions::Total))
+                    })
+                    .collect::<EosResult<_>>()?;
+                Ok(BinaryDataSet::new(
+                    property,
+                    temperature.clone(),
+                    SIArray1::from_vec(pressure),
+                    molefracs.clone(),
+                ))
+            })
+            .collect::<EosResult<_>>()?;
+            Ok(MixtureData::new(records, datasets))
+        })
+        .collect::<EosResult<_>>()?;
+
+        // fit the binary parameter starting from zero
+        let estimator = BinarySegmentEstimator::new(segment_records, None, free, mixtures)?;
+        assert_eq!(estimator.initial_parameters(), arr1(&[0.0]));
+        assert!(estimator.aard(&estimator.initial_parameters())[0][0] > 1e-3);
+
+        let estimation = estimator.fit(EstimatorOptions::default())?;
+        assert!(estimation.converged);
+        assert_relative_eq!(estimation.parameters[0], k_ij, max_relative = 1e-6);
+        assert_eq!(estimation.aard.len(), 2);
+        for aard in estimation.aard.iter().flatten() {
+            assert!(*aard < 1e-6);
+        }
+
+        // the written file can be used to construct parameters
+        let path = std::env::temp_dir().join("gc_pcsaft_test_binary_segments.json");
+        estimation.to_json(&path)?;
+        let params = GcPcSaftEosParameters::from_json_segments(
+            &["ethanol", "hexane"],
+            Path::new("parameters/gc_substances.json"),
+            Path::new("parameters/sauer2014_hetero.json"),
+            Some(&path),
+            IdentifierOption::Name,
+        );
+        std::fs::remove_file(&path).unwrap();
+        let k_ij_fit = params?.k_ij;
+        assert!(k_ij_fit.iter().any(|&k| k != 0.0));
+        for &k in k_ij_fit.iter().filter(|&&k| k != 0.0) {
+            assert_relative_eq!(k, k_ij, max_relative = 1e-6);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stalled_fit() -> EosResult<()> {
+        // the minimum is a kink that cannot be reached with the linearized residuals
+        let residuals = |x: &Array1<f64>| arr1(&[1.0 + 1e-3 * (x[0] - 1.0).abs()]);
+        let (x, _, cost, converged) =
+            levenberg_marquardt(arr1(&[1.0]), residuals, EstimatorOptions::default())?;
+        assert!(!converged);
+        assert_eq!(x[0], 1.0);
+        assert_eq!(cost, 1.0);
+
+        let residuals = |x: &Array1<f64>| arr1(&[x[0] - 2.0, 10.0 * (x[1] - x[0].powi(2))]);
+        let (x, _, _, converged) =
+            levenberg_marquardt(arr1(&[0.0, 1.0]), residuals, EstimatorOptions::default())?;
+        assert!(converged);
+        assert_relative_eq!(x[0], 2.0, max_relative = 1e-6);
+        assert_relative_eq!(x[1], 4.0, max_relative = 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_association_parameters() {
+        let legacy = GcPcSaftRecord::new(
+            1.0,
+            3.0,
+            200.0,
+            None,
+            Some(0.01),
+            Some(2000.0),
+            None,
+            None,
+            None,
+        );
+        let record = GcPcSaftRecord {
+            association_record: Some(AssociationRecord::new(
+                vec![AssociationSite::new("C".into(), 1.0)],
+                vec![SiteInteraction::new("C".into(), "C".into(), 0.02, 2500.0)],
+            )),
+            ..Default::default()
+        };
+        assert_eq!(RecordParameter::KappaAB.get(&legacy), 0.01);
+        assert_eq!(RecordParameter::EpsilonKAB.get(&record), 2500.0);
+
+        let mut legacy_fit = legacy.clone();
+        RecordParameter::KappaAB.set(&mut legacy_fit, 0.03);
+        assert_eq!(
+            legacy_fit.association_record().unwrap().interactions[0].kappa_ab,
+            0.03
+        );
+
+        let mut record_fit = record.clone();
+        RecordParameter::KappaAB.set(&mut record_fit, 0.03);
+        RecordParameter::EpsilonKAB.set(&mut record_fit, 3000.0);
+        assert!(record_fit.kappa_ab.is_none());
+        assert_eq!(RecordParameter::KappaAB.get(&record_fit), 0.03);
+        let interaction = record_fit.association_record().unwrap().interactions[0].clone();
+        assert_eq!(interaction.kappa_ab, 0.03);
+        assert_eq!(interaction.epsilon_k_ab, 3000.0);
+    }
+
+    #[test]
+    fn test_missing_segment() {
+        let free = vec![SegmentParameter::new("CH4", RecordParameter::M)];
+        assert!(SegmentEstimator::new(vec![], None, free, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_data_sets() {
+        let temperature = arr1(&[250.0, 300.0]) * KELVIN;
+        let estimator = |target: SIArray1| {
+            let dataset = DataSet::new(Property::VaporPressure, temperature.clone(), target);
+            let molecules = vec![MoleculeData::new(alkane("butane", 4), vec![dataset])];
+            SegmentEstimator::new(vec![], None, vec![], molecules)
+        };
+        assert!(estimator(arr1(&[1.0, 2.0]) * BAR).is_ok());
+        assert!(estimator(arr1(&[1.0]) * BAR).is_err());
+
+        let dataset = DataSet::new(
+            Property::VaporPressure,
+            Array1::<f64>::zeros(0) * KELVIN,
+            Array1::<f64>::zeros(0) * BAR,
+        );
+        let molecules = vec![MoleculeData::new(alkane("butane", 4), vec![dataset])];
+        assert!(SegmentEstimator::new(vec![], None, vec![], molecules).is_err());
+    }
+
+    #[test]
+    fn test_invalid_binary_data_sets() {
+        let temperature = arr1(&[320.0, 320.0]) * KELVIN;
+        let estimator = |pressure: SIArray1, molefracs: Array1<f64>| {
+            let dataset = BinaryDataSet::new(
+                BinaryProperty::BubblePointPressure,
+                temperature.clone(),
+                pressure,
+                molefracs,
+            );
+            let records = [alcohol("ethanol", 2), alkane("hexane", 6)];
+            let mixtures = vec![MixtureData::new(records, vec![dataset])];
+            BinarySegmentEstimator::new(vec![], None, vec![], mixtures)
+        };
+        assert!(estimator(arr1(&[1.0, 2.0]) * BAR, arr1(&[0.2, 0.8])).is_ok());
+        assert!(estimator(arr1(&[1.0]) * BAR, arr1(&[0.2, 0.8])).is_err());
+        assert!(estimator(arr1(&[1.0, 2.0]) * BAR, arr1(&[0.2])).is_err());
+    }
+}
@@ -0,0 +1,874 @@
+//! Automatic fragmentation of molecules into segments based on their SMILES.
+//!
+//! The SMILES string is parsed into a molecular graph of heavy atoms. The atoms are
+//! then assigned to groups using a list of [GroupDefinition]s, that are matched in
+//! the order in which they are provided. The resulting [ChemicalRecord] contains
+//! the segments and the bonds between them and can be used for both the equation
+//! of state and the heterosegmented functional.
+use feos_core::parameter::{ChemicalRecord, Identifier, ParameterError};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Order of a bond in a molecule or group definition.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BondOrder {
+    Single,
+    Double,
+    Triple,
+    Aromatic,
+}
+
+impl BondOrder {
+    fn valence(&self) -> usize {
+        match self {
+            Self::Single | Self::Aromatic => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+        }
+    }
+}
+
+/// Pattern for a single heavy atom within a group definition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AtomPattern {
+    /// Element symbol
+    pub element: String,
+    /// Number of (implicit or explicit) hydrogen atoms
+    #[serde(default)]
+    pub hydrogens: usize,
+    /// Number of non-aromatic double bonds
+    #[serde(default)]
+    pub double_bonds: usize,
+    /// Number of triple bonds
+    #[serde(default)]
+    pub triple_bonds: usize,
+    /// Whether the atom is part of an aromatic ring
+    #[serde(default)]
+    pub aromatic: bool,
+    /// Size of the smallest ring containing the atom or `None` for acyclic atoms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub ring_size: Option<usize>,
+}
+
+impl AtomPattern {
+    /// Create a new `AtomPattern`.
+    pub fn new(
+        element: &str,
+        hydrogens: usize,
+        double_bonds: usize,
+        triple_bonds: usize,
+        aromatic: bool,
+        ring_size: Option<usize>,
+    ) -> Self {
+        Self {
+            element: element.into(),
+            hydrogens,
+            double_bonds,
+            triple_bonds,
+            aromatic,
+            ring_size,
+        }
+    }
+
+    /// Pattern for an acyclic, saturated atom.
+    fn chain(element: &str, hydrogens: usize) -> Self {
+        Self::new(element, hydrogens, 0, 0, false, None)
+    }
+}
+
+/// Definition of a group (segment) in terms of the heavy atoms it contains.
+///
+/// Every atom in `atoms` except the first has to be bonded to at least one
+/// of the atoms preceding it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GroupDefinition {
+    /// Identifier of the segment
+    pub identifier: String,
+    /// Heavy atoms of the group
+    pub atoms: Vec<AtomPattern>,
+    /// Bonds between the atoms of the group
+    #[serde(default)]
+    pub bonds: Vec<(usize, usize, BondOrder)>,
+}
+
+impl GroupDefinition {
+    /// Create a new `GroupDefinition`.
+    pub fn new(
+        identifier: &str,
+        atoms: Vec<AtomPattern>,
+        bonds: Vec<(usize, usize, BondOrder)>,
+    ) -> Self {
+        Self {
+            identifier: identifier.into(),
+            atoms,
+            bonds,
+        }
+    }
+
+    fn single(identifier: &str, atom: AtomPattern) -> Self {
+        Self::new(identifier, vec![atom], vec![])
+    }
+
+    /// Group definitions for all segments in the parameter sets of Sauer et al. (2014).
+    ///
+    /// The definitions are ordered such that larger groups are matched first.
+    pub fn sauer2014() -> Vec<Self> {
+        use BondOrder::*;
+        let carbonyl = |h| AtomPattern::new("C", h, 1, 0, false, None);
+        let oxo = AtomPattern::new("O", 0, 1, 0, false, None);
+        let ring = |h, d, size| AtomPattern::new("C", h, d, 0, false, Some(size));
+        let alkene = |h| AtomPattern::new("C", h, 1, 0, false, None);
+        vec![
+            // formate group, bonded via the ester oxygen
+            Self::new(
+                "HCOO",
+                vec![carbonyl(1), oxo.clone(), AtomPattern::chain("O", 0)],
+                vec![(0, 1, Double), (0, 2, Single)],
+            ),
+            // carboxylic acid group, bonded via the carbon atom
+            Self::new(
+                "HCOO",
+                vec![carbonyl(0), oxo.clone(), AtomPattern::chain("O", 1)],
+                vec![(0, 1, Double), (0, 2, Single)],
+            ),
+            Self::new(
+                "COO",
+                vec![carbonyl(0), oxo.clone(), AtomPattern::chain("O", 0)],
+                vec![(0, 1, Double), (0, 2, Single)],
+            ),
+            Self::new("CH=O", vec![carbonyl(1), oxo.clone()], vec![(0, 1, Double)]),
+            Self::new(">C=O", vec![carbonyl(0), oxo], vec![(0, 1, Double)]),
+            Self::new(
+                "OCH3",
+                vec![AtomPattern::chain("O", 0), AtomPattern::chain("C", 3)],
+                vec![(0, 1, Single)],
+            ),
+            Self::new(
+                "OCH2",
+                vec![AtomPattern::chain("O", 0), AtomPattern::chain("C", 2)],
+                vec![(0, 1, Single)],
+            ),
+            Self::new(
+                "C≡CH",
+                vec![
+                    AtomPattern::new("C", 1, 0, 1, false, None),
+                    AtomPattern::new("C", 0, 0, 1, false, None),
+                ],
+                vec![(0, 1, Triple)],
+            ),
+            Self::single("OH", AtomPattern::chain("O", 1)),
+            Self::single("NH2", AtomPattern::chain("N", 2)),
+            Self::single("CH_arom", AtomPattern::new("C", 1, 0, 0, true, Some(6))),
+            Self::single("C_arom", AtomPattern::new("C", 0, 0, 0, true, Some(6))),
+            Self::single("CH2_hex", ring(2, 0, 6)),
+            Self::single("CH_hex", ring(1, 0, 6)),
+            Self::single("CH_hex", ring(1, 1, 6)),
+            Self::single("CH2_pent", ring(2, 0, 5)),
+            Self::single("CH_pent", ring(1, 0, 5)),
+            Self::single("CH_pent", ring(1, 1, 5)),
+            Self::single("CH3", AtomPattern::chain("C", 3)),
+            Self::single("CH2", AtomPattern::chain("C", 2)),
+            Self::single(">CH", AtomPattern::chain("C", 1)),
+            Self::single(">C<", AtomPattern::chain("C", 0)),
+            Self::single("=CH2", alkene(2)),
+            Self::single("=CH", alkene(1)),
+            Self::single("=C<", alkene(0)),
+        ]
+    }
+
+    /// Number of bonds the group forms with other segments.
+    ///
+    /// The valence is determined from the default valences of the atoms,
+    /// their hydrogens and the bonds within the group. Double and triple
+    /// bonds to other groups count as a single bond between segments.
+    /// An error is returned if the definition is invalid.
+    pub fn valence(&self) -> Result<usize, ParameterError> {
+        self.check()?;
+        let mut valence = 0;
+        for (i, atom) in self.atoms.iter().enumerate() {
+            let internal = |o| self.internal_bonds(i, o);
+            let bonds: usize = self
+                .bonds
+                .iter()
+                .filter(|&&(a, b, _)| a == i || b == i)
+                .map(|(_, _, order)| order.valence())
+                .sum();
+            let used = atom.hydrogens
+                + bonds
+                + (atom.double_bonds - internal(BondOrder::Double))
+                + 2 * (atom.triple_bonds - internal(BondOrder::Triple))
+                + atom.aromatic as usize;
+            valence += default_valences(&atom.element)
+                .first()
+                .map_or(0, |&v| v.saturating_sub(used));
+        }
+        Ok(valence)
+    }
+
+    /// Number of bonds of the given order between atom `i` and other atoms of the group.
+    fn internal_bonds(&self, i: usize, order: BondOrder) -> usize {
+        self.bonds
+            .iter()
+            .filter(|&&(a, b, o)| (a == i || b == i) && o == order)
+            .count()
+    }
+
+    fn check(&self) -> Result<(), ParameterError> {
+        let n = self.atoms.len();
+        let connected = (1..n).all(|i| {
+            self.bonds
+                .iter()
+                .any(|&(a, b, _)| (a == i && b < i) || (b == i && a < i))
+        });
+        if n == 0 || !connected || self.bonds.iter().any(|&(a, b, _)| a >= n || b >= n) {
+            return Err(ParameterError::IncompatibleParameters(format!(
+                "The definition of group '{}' is not a connected set of atoms.",
+                self.identifier
+            )));
+        }
+        for (i, atom) in self.atoms.iter().enumerate() {
+            if self.internal_bonds(i, BondOrder::Double) > atom.double_bonds
+                || self.internal_bonds(i, BondOrder::Triple) > atom.triple_bonds
+            {
+                return Err(ParameterError::IncompatibleParameters(format!(
+                    "Atom {} in the definition of group '{}' has more double or triple bonds within the group than specified in its pattern.",
+                    i, self.identifier
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Heavy atom of a molecule.
+#[derive(Debug, Clone)]
+struct Atom {
+    element: String,
+    aromatic: bool,
+    hydrogens: Option<usize>,
+}
+
+/// Molecular graph of the heavy atoms of a molecule.
+#[derive(Debug)]
+struct Molecule {
+    atoms: Vec<Atom>,
+    bonds: Vec<(usize, usize, BondOrder)>,
+}
+
+impl Molecule {
+    fn neighbors(&self) -> Vec<Vec<(usize, BondOrder)>> {
+        let mut neighbors = vec![Vec::new(); self.atoms.len()];
+        for &(a, b, order) in self.bonds.iter() {
+            neighbors[a].push((b, order));
+            neighbors[b].push((a, order));
+        }
+        neighbors
+    }
+
+    /// Smallest cycle that contains the bond between atoms `a` and `b`.
+    fn smallest_cycle(
+        neighbors: &[Vec<(usize, BondOrder)>],
+        a: usize,
+        b: usize,
+    ) -> Option<Vec<usize>> {
+        let mut parent = vec![None; neighbors.len()];
+        parent[a] = Some(a);
+        let mut queue = VecDeque::from(vec![a]);
+        while let Some(i) = queue.pop_front() {
+            for &(j, _) in neighbors[i].iter() {
+                if parent[j].is_some() || (i == a && j == b) {
+                    continue;
+                }
+                parent[j] = Some(i);
+                if j == b {
+                    let mut cycle = vec![b];
+                    let mut k = b;
+                    while k != a {
+                        k = parent[k].unwrap();
+                        cycle.push(k);
+                    }
+                    return Some(cycle);
+                }
+                queue.push_back(j);
+            }
+        }
+        None
+    }
+
+    /// Determine the sizes of the smallest rings of every atom and
+    /// convert alternating six-membered rings to aromatic rings.
+    fn perceive_rings(&mut self) -> Vec<Option<usize>> {
+        let neighbors = self.neighbors();
+        let cycles: Vec<_> = self
+            .bonds
+            .iter()
+            .filter_map(|&(a, b, _)| Self::smallest_cycle(&neighbors, a, b))
+            .collect();
+
+        let mut ring_size = vec![None; self.atoms.len()];
+        for cycle in cycles.iter() {
+            for &i in cycle.iter() {
+                ring_size[i] =
+                    Some(ring_size[i].map_or(cycle.len(), |s: usize| s.min(cycle.len())));
+            }
+        }
+
+        for cycle in cycles.iter().filter(|c| c.len() == 6) {
+            let ring_bonds: Vec<_> = (0..6)
+                .map(|k| {
+                    let (a, b) = (cycle[k], cycle[(k + 1) % 6]);
+                    self.bonds
+                        .iter()
+                        .position(|&(i, j, _)| (i, j) == (a, b) || (i, j) == (b, a))
+                        .unwrap()
+                })
+                .collect();
+            let alternating = (0..6).all(|k| {
+                let o1 = self.bonds[ring_bonds[k]].2;
+                let o2 = self.bonds[ring_bonds[(k + 1) % 6]].2;
+                matches!(
+                    (o1, o2),
+                    (BondOrder::Single, BondOrder::Double)
+                        | (BondOrder::Double, BondOrder::Single)
+                        | (BondOrder::Aromatic, _)
+                        | (_, BondOrder::Aromatic)
+                )
+            }) && ring_bonds
+                .iter()
+                .any(|&k| self.bonds[k].2 == BondOrder::Double);
+            if alternating {
+                for &k in ring_bonds.iter() {
+                    self.bonds[k].2 = BondOrder::Aromatic;
+                }
+                for &i in cycle.iter() {
+                    self.atoms[i].aromatic = true;
+                }
+            }
+        }
+        ring_size
+    }
+}
+
+/// Default valences of the elements in the organic subset.
+fn default_valences(element: &str) -> &'static [usize] {
+    match element {
+        "B" => &[3],
+        "C" => &[4],
+        "N" | "P" => &[3, 5],
+        "O" => &[2],
+        "S" => &[2, 4, 6],
+        "F" | "Cl" | "Br" | "I" => &[1],
+        _ => &[],
+    }
+}
+
+fn smiles_error(smiles: &str, message: &str) -> ParameterError {
+    ParameterError::IncompatibleParameters(format!(
+        "Unable to parse SMILES '{}': {}",
+        smiles, message
+    ))
+}
+
+/// Parse the heavy atoms and bonds of a SMILES string.
+fn parse_smiles(smiles: &str) -> Result<Molecule, ParameterError> {
+    let mut atoms: Vec<Atom> = Vec::new();
+    let mut bonds = Vec::new();
+    let mut previous: Option<usize> = None;
+    let mut branches = Vec::new();
+    let mut bond: Option<BondOrder> = None;
+    let mut ring_closures: HashMap<usize, (usize, Option<BondOrder>)> = HashMap::new();
+
+    let default_order = |atoms: &[Atom], a: usize, b: usize| {
+        if atoms[a].aromatic && atoms[b].aromatic {
+            BondOrder::Aromatic
+        } else {
+            BondOrder::Single
+        }
+    };
+
+    let chars: Vec<char> = smiles.chars().collect();
+    let mut k = 0;
+    while k < chars.len() {
+        let c = chars[k];
+        k += 1;
+        let atom = match c {
+            '-' | '/' | '\\' => {
+                bond = Some(BondOrder::Single);
+                None
+            }
+            '=' => {
+                bond = Some(BondOrder::Double);
+                None
+            }
+            '#' => {
+                bond = Some(BondOrder::Triple);
+                None
+            }
+            ':' => {
+                bond = Some(BondOrder::Aromatic);
+                None
+            }
+            '(' => {
+                branches.push(previous.ok_or_else(|| smiles_error(smiles, "branch without atom"))?);
+                None
+            }
+            ')' => {
+                previous = Some(
+                    branches
+                        .pop()
+                        .ok_or_else(|| smiles_error(smiles, "unbalanced parentheses"))?,
+                );
+                None
+            }
+            '.' => {
+                return Err(smiles_error(
+                    smiles,
+                    "disconnected structures are not supported",
+                ))
+            }
+            '0'..='9' | '%' => {
+                let label = if c == '%' {
+                    let digits: String = chars.iter().skip(k).take(2).collect();
+                    k += 2;
+                    digits
+                        .parse()
+                        .map_err(|_| smiles_error(smiles, "invalid ring closure"))?
+                } else {
+                    c.to_digit(10).unwrap() as usize
+                };
+                let current =
+                    previous.ok_or_else(|| smiles_error(smiles, "ring closure without atom"))?;
+                match ring_closures.remove(&label) {
+                    Some((other, order)) => {
+                        let order = bond
+                            .or(order)
+                            .unwrap_or_else(|| default_order(&atoms, other, current));
+                        bonds.push((other, current, order));
+                    }
+                    None => {
+                        ring_closures.insert(label, (current, bond));
+                    }
+                }
+                bond = None;
+                None
+            }
+            '[' => {
+                let end = chars[k..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .ok_or_else(|| smiles_error(smiles, "unclosed bracket atom"))?;
+                let bracket: String = chars[k..k + end].iter().collect();
+                k += end + 1;
+                Some(parse_bracket_atom(smiles, &bracket)?)
+            }
+            'B' | 'C' if chars.get(k) == Some(&if c == 'B' { 'r' } else { 'l' }) => {
+                k += 1;
+                Some(Atom {
+                    element: if c == 'B' { "Br" } else { "Cl" }.into(),
+                    aromatic: false,
+                    hydrogens: None,
+                })
+            }
+            'B' | 'C' | 'N' | 'O' | 'P' | 'S' | 'F' | 'I' => Some(Atom {
+                element: c.to_string(),
+                aromatic: false,
+                hydrogens: None,
+            }),
+            'b' | 'c' | 'n' | 'o' | 'p' | 's' => Some(Atom {
+                element: c.to_ascii_uppercase().to_string(),
+                aromatic: true,
+                hydrogens: None,
+            }),
+            _ => {
+                return Err(smiles_error(
+                    smiles,
+                    &format!("unexpected character '{}'", c),
+                ))
+            }
+        };
+
+        if let Some(atom) = atom {
+            atoms.push(atom);
+            let current = atoms.len() - 1;
+            if let Some(p) = previous {
+                let order = bond.unwrap_or_else(|| default_order(&atoms, p, current));
+                bonds.push((p, current, order));
+            }
+            previous = Some(current);
+            bond = None;
+        }
+    }
+
+    if atoms.is_empty() {
+        return Err(smiles_error(smiles, "no atoms found"));
+    }
+    if !branches.is_empty() {
+        return Err(smiles_error(smiles, "unbalanced parentheses"));
+    }
+    if !ring_closures.is_empty() {
+        return Err(smiles_error(smiles, "unclosed ring"));
+    }
+
+    // implicit hydrogens
+    let mut valence = vec![0; atoms.len()];
+    for &(a, b, order) in bonds.iter() {
+        valence[a] += order.valence();
+        valence[b] += order.valence();
+    }
+    for (atom, v) in atoms.iter_mut().zip(valence) {
+        if atom.hydrogens.is_none() {
+            let v = v + atom.aromatic as usize;
+            let h = default_valences(&atom.element)
+                .iter()
+                .find(|&&d| d >= v)
+                .map_or(0, |d| d - v);
+            atom.hydrogens = Some(h);
+        }
+    }
+
+    Ok(Molecule { atoms, bonds })
+}
+
+/// Parse the content of a bracket atom, e.g. `C@@H` or `nH`.
+fn parse_bracket_atom(smiles: &str, bracket: &str) -> Result<Atom, ParameterError> {
+    let chars: Vec<char> = bracket.chars().skip_while(|c| c.is_ascii_digit()).collect();
+    let first = *chars
+        .first()
+        .ok_or_else(|| smiles_error(smiles, "empty bracket atom"))?;
+    let aromatic = first.is_ascii_lowercase();
+    let mut element = first.to_ascii_uppercase().to_string();
+    let mut k = 1;
+    if !aromatic && chars.get(1).map_or(false, |c| c.is_ascii_lowercase()) {
+        element.push(chars[1]);
+        k = 2;
+    }
+    while chars.get(k) == Some(&'@') {
+        k += 1;
+    }
+    let mut hydrogens = 0;
+    if chars.get(k) == Some(&'H') {
+        k += 1;
+        hydrogens = 1;
+        if let Some(d) = chars.get(k).and_then(|c| c.to_digit(10)) {
+            hydrogens = d as usize;
+        }
+    }
+    Ok(Atom {
+        element,
+        aromatic,
+        hydrogens: Some(hydrogens),
+    })
+}
+
+/// Try to map the atoms of a group definition onto unassigned atoms of the molecule.
+fn match_group(
+    group: &GroupDefinition,
+    fits: &[Vec<bool>],
+    neighbors: &[Vec<(usize, BondOrder)>],
+    assigned: &[Option<usize>],
+    mapping: &mut Vec<usize>,
+) -> bool {
+    let i = mapping.len();
+    if i == group.atoms.len() {
+        return true;
+    }
+    let required: Vec<_> = group
+        .bonds
+        .iter()
+        .filter_map(|&(a, b, order)| match (a == i, b == i) {
+            (true, false) if b < i => Some((mapping[b], order)),
+            (false, true) if a < i => Some((mapping[a], order)),
+            _ => None,
+        })
+        .collect();
+    let (anchor, _) = required[0];
+    for &(candidate, _) in neighbors[anchor].iter() {
+        if assigned[candidate].is_some() || mapping.contains(&candidate) || !fits[i][candidate] {
+            continue;
+        }
+        let bonded = required
+            .iter()
+            .all(|&(j, order)| neighbors[candidate].contains(&(j, order)));
+        if bonded {
+            mapping.push(candidate);
+            if match_group(group, fits, neighbors, assigned, mapping) {
+                return true;
+            }
+            mapping.pop();
+        }
+    }
+    false
+}
+
+/// Create a [ChemicalRecord] by fragmenting the molecule given by its SMILES
+/// into the groups in `groups`.
+///
+/// The groups are matched greedily in the order in which they are provided,
+/// therefore, larger groups should precede smaller ones. Only the heavy atoms
+/// of the molecule are considered; an error is returned if an atom cannot be
+/// assigned to any group.
+pub fn chemical_record_from_smiles(
+    identifier: Identifier,
+    smiles: &str,
+    groups: &[GroupDefinition],
+) -> Result<ChemicalRecord, ParameterError> {
+    for group in groups.iter() {
+        group.check()?;
+    }
+
+    let mut molecule = parse_smiles(smiles)?;
+    let ring_size = molecule.perceive_rings();
+    let neighbors = molecule.neighbors();
+    let (double_bonds, triple_bonds): (Vec<_>, Vec<_>) = neighbors
+        .iter()
+        .map(|n| {
+            let count = |o| n.iter().filter(|&&(_, order)| order == o).count();
+            (count(BondOrder::Double), count(BondOrder::Triple))
+        })
+        .unzip();
+
+    let n = molecule.atoms.len();
+    let mut assigned = vec![None; n];
+    let mut fragments = Vec::new();
+    for group in groups.iter() {
+        let fits: Vec<Vec<bool>> = group
+            .atoms
+            .iter()
+            .map(|p| {
+                (0..n)
+                    .map(|i| {
+                        let atom = &molecule.atoms[i];
+                        atom.element == p.element
+                            && atom.aromatic == p.aromatic
+                            && atom.hydrogens == Some(p.hydrogens)
+                            && double_bonds[i] == p.double_bonds
+                            && triple_bonds[i] == p.triple_bonds
+                            && ring_size[i] == p.ring_size
+                    })
+                    .collect()
+            })
+            .collect();
+        for i in 0..n {
+            if assigned[i].is_some() || !fits[0][i] {
+                continue;
+            }
+            let mut mapping = vec![i];
+            if match_group(group, &fits, &neighbors, &assigned, &mut mapping) {
+                for &j in mapping.iter() {
+                    assigned[j] = Some(fragments.len());
+                }
+                fragments.push((mapping, group.identifier.clone()));
+            }
+        }
+    }
+
+    if let Some(i) = assigned.iter().position(|a| a.is_none()) {
+        return Err(ParameterError::IncompatibleParameters(format!(
+            "Atom {} ({}) of '{}' could not be assigned to any group.",
+            i, molecule.atoms[i].element, smiles
+        )));
+    }
+
+    // order the segments by their first appearance in the SMILES
+    let mut order: Vec<_> = (0..fragments.len()).collect();
+    order.sort_by_key(|&f| fragments[f].0.iter().min().copied());
+    let mut index = vec![0; fragments.len()];
+    for (k, &f) in order.iter().enumerate() {
+        index[f] = k;
+    }
+    let segments = order.iter().map(|&f| fragments[f].1.clone()).collect();
+
+    let mut bonds: Vec<[usize; 2]> = Vec::new();
+    for &(a, b, _) in molecule.bonds.iter() {
+        let (i, j) = (index[assigned[a].unwrap()], index[assigned[b].unwrap()]);
+        let bond = [i.min(j), i.max(j)];
+        if i != j && !bonds.contains(&bond) {
+            bonds.push(bond);
+        }
+    }
+    bonds.sort_unstable();
+
+    Ok(ChemicalRecord::new(identifier, segments, Some(bonds)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{GcPcSaftEosParameters, GcPcSaftRecord};
+    use feos_core::joback::JobackRecord;
+    use feos_core::parameter::{IdentifierOption, SegmentRecord};
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn fragment(smiles: &str) -> (Vec<String>, Vec<[usize; 2]>) {
+        let identifier = Identifier::new("", None, None, Some(smiles), None, None);
+        let record =
+            chemical_record_from_smiles(identifier, smiles, &GroupDefinition::sauer2014()).unwrap();
+        let (segments, bonds) = record.segment_and_bond_list().unwrap();
+        (segments.clone(), bonds.clone())
+    }
+
+    /// Segments together with the sorted list of their bonded neighbors.
+    fn topology(record: &ChemicalRecord) -> Vec<(String, Vec<String>)> {
+        let (segments, bonds) = record.segment_and_bond_list().unwrap();
+        let mut topology: Vec<_> = segments
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let mut neighbors: Vec<_> = bonds
+                    .iter()
+                    .filter_map(|&[a, b]| match (a == i, b == i) {
+                        (true, _) => Some(segments[b].clone()),
+                        (_, true) => Some(segments[a].clone()),
+                        _ => None,
+                    })
+                    .collect();
+                neighbors.sort();
+                (s.clone(), neighbors)
+            })
+            .collect();
+        topology.sort();
+        topology
+    }
+
+    #[test]
+    fn test_linear_and_branched() {
+        let (segments, bonds) = fragment("CCO");
+        assert_eq!(segments, vec!["CH3", "CH2", "OH"]);
+        assert_eq!(bonds, vec![[0, 1], [1, 2]]);
+
+        let (segments, bonds) = fragment("CC(C)O");
+        assert_eq!(segments, vec!["CH3", ">CH", "CH3", "OH"]);
+        assert_eq!(bonds, vec![[0, 1], [1, 2], [1, 3]]);
+    }
+
+    #[test]
+    fn test_functional_groups() {
+        assert_eq!(fragment("CC#C").0, vec!["CH3", "C≡CH"]);
+        assert_eq!(fragment("CC(C)=C").0, vec!["CH3", "=C<", "CH3", "=CH2"]);
+        assert_eq!(fragment("CCOC(C)=O").0, vec!["CH3", "CH2", "COO", "CH3"]);
+        assert_eq!(fragment("CCOC=O").0, vec!["CH3", "CH2", "HCOO"]);
+        assert_eq!(fragment("CC(O)=O").0, vec!["CH3", "HCOO"]);
+        assert_eq!(fragment("CCC(C)=O").0, vec!["CH3", "CH2", ">C=O", "CH3"]);
+        assert_eq!(fragment("[CH3][CH2][NH2]").0, vec!["CH3", "CH2", "NH2"]);
+    }
+
+    #[test]
+    fn test_rings() {
+        let (segments, bonds) = fragment("Cc1ccccc1");
+        assert_eq!(segments[0], "CH3");
+        assert_eq!(segments[1], "C_arom");
+        assert_eq!(segments.iter().filter(|&s| s == "CH_arom").count(), 5);
+        assert_eq!(bonds.len(), 7);
+
+        // Kekulé structures are aromatized
+        let (segments, _) = fragment("CC1=CC=CC=C1");
+        assert_eq!(segments, fragment("Cc1ccccc1").0);
+
+        let (segments, bonds) = fragment("CC1CCCC1");
+        assert_eq!(segments[1], "CH_pent");
+        assert_eq!(bonds.len(), 6);
+    }
+
+    #[test]
+    fn test_valence() {
+        let valences: HashMap<_, _> = GroupDefinition::sauer2014()
+            .into_iter()
+            .map(|g| {
+                let valence = g.valence().unwrap();
+                ((g.identifier.clone(), g.atoms[0].double_bonds), valence)
+            })
+            .collect();
+        for (identifier, double_bonds, valence) in [
+            ("CH3", 0, 1),
+            (">C<", 0, 4),
+            ("=CH2", 1, 1),
+            ("=C<", 1, 3),
+            ("C≡CH", 0, 1),
+            ("COO", 1, 2),
+            ("HCOO", 1, 1),
+            ("OCH3", 0, 1),
+            ("C_arom", 0, 3),
+            ("CH_arom", 0, 2),
+            ("CH_pent", 0, 3),
+            ("CH_pent", 1, 2),
+        ] {
+            assert_eq!(valences[&(identifier.to_string(), double_bonds)], valence);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let groups = GroupDefinition::sauer2014();
+        let identifier = Identifier::new("", None, None, None, None, None);
+        for smiles in ["CC(O", "C1CC", "CC.O", "CCX", "ClCCl"] {
+            assert!(chemical_record_from_smiles(identifier.clone(), smiles, &groups).is_err());
+        }
+        let disconnected = GroupDefinition::new(
+            "CH3",
+            vec![AtomPattern::chain("C", 3), AtomPattern::chain("C", 3)],
+            vec![],
+        );
+        assert!(chemical_record_from_smiles(identifier.clone(), "CC", &[disconnected]).is_err());
+
+        // the double bond within the group is not part of the atom patterns
+        let carbonyl = GroupDefinition::new(
+            ">C=O",
+            vec![AtomPattern::chain("C", 0), AtomPattern::chain("O", 0)],
+            vec![(0, 1, BondOrder::Double)],
+        );
+        assert!(carbonyl.valence().is_err());
+        assert!(chemical_record_from_smiles(identifier, "CC(C)=O", &[carbonyl]).is_err());
+    }
+
+    #[test]
+    fn test_gc_substances() {
+        let reader = BufReader::new(File::open("parameters/gc_substances.json").unwrap());
+        let records: Vec<ChemicalRecord> = serde_json::from_reader(reader).unwrap();
+        let groups = GroupDefinition::sauer2014();
+        for record in records {
+            let identifier = record.identifier().clone();
+            let smiles = identifier.smiles.clone().unwrap();
+            let fragmented = chemical_record_from_smiles(identifier, &smiles, &groups);
+            match smiles.as_str() {
+                // the substance file lists a CH2 instead of the >CH group
+                "CC(C)OC(C)=O" => assert_eq!(
+                    fragmented.unwrap().segment_and_bond_list().unwrap().0,
+                    &vec!["CH3", ">CH", "CH3", "COO", "CH3"]
+                ),
+                _ => assert_eq!(
+                    topology(&fragmented.unwrap()),
+                    topology(&record),
+                    "{}",
+                    smiles
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parameters_from_smiles() {
+        // butanone with the atoms in the order of the segments in the substance file
+        let smiles = "CC(=O)CC";
+        let identifier = Identifier::new("", None, None, Some(smiles), None, None);
+        let record =
+            chemical_record_from_smiles(identifier, smiles, &GroupDefinition::sauer2014()).unwrap();
+        let reader = BufReader::new(File::open("parameters/sauer2014_hetero.json").unwrap());
+        let segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> =
+            serde_json::from_reader(reader).unwrap();
+        let params =
+            GcPcSaftEosParameters::from_segments(vec![record], segment_records, None).unwrap();
+        let reference = GcPcSaftEosParameters::from_json_segments(
+            &["butanone"],
+            "parameters/gc_substances.json",
+            "parameters/sauer2014_hetero.json",
+            None,
+            IdentifierOption::Name,
+        )
+        .unwrap();
+        assert_eq!(params.molarweight, reference.molarweight);
+        assert_eq!(params.m, reference.m);
+        assert_eq!(params.bonds, reference.bonds);
+    }
+}
@@ -2,12 +2,30 @@
 #![allow(clippy::too_many_arguments)]
 mod dft;
 mod eos;
+mod estimator;
+mod fragmentation;
 #[cfg(feature = "micelles")]
 pub mod micelles;
 mod record;
+mod second_order;
+mod validation;
 pub use dft::{GcPcSaftFunctional, GcPcSaftFunctionalParameters};
-pub use eos::{GcPcSaft, GcPcSaftEosParameters, GcPcSaftOptions};
-pub use record::GcPcSaftRecord;
+pub use eos::{
+    EquivalentPcSaft, GcIdealGas, GcPcSaft, GcPcSaftEosParameters, GcPcSaftOptions, IdealGasModel,
+    Permittivity, SegmentContributions, VaporPressureComparison,
+};
+pub use estimator::{
+    BinaryDataSet, BinaryEstimation, BinaryProperty, BinaryRecordParameter, BinarySegmentEstimator,
+    BinarySegmentParameter, DataSet, Estimation, EstimatorOptions, MixtureData, MoleculeData,
+    Property, RecordParameter, SegmentEstimator, SegmentParameter,
+};
+pub use fragmentation::{chemical_record_from_smiles, AtomPattern, BondOrder, GroupDefinition};
+pub use record::{
+    AssociationRecord, AssociationSite, GcPcSaftBinaryRecord, GcPcSaftRecord, HeatCapacityRecord,
+    PcSaftRecord, SiteInteraction,
+};
+pub use second_order::SecondOrderRecord;
+pub use validation::{validate_chemical_records, ChemicalRecordIssue, ValidationReport};
 
 #[cfg(feature = "python")]
 pub mod python;
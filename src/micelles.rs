@@ -1,32 +1,167 @@
-use feos_core::{Contributions, EosError, EosResult, EosUnit, SolverOptions, State, StateBuilder};
+use crate::eos::ionic::BJERRUM_LENGTH_VACUUM;
+use feos_core::parameter::ParameterError;
+use feos_core::{
+    Contributions, DensityInitialization, EosError, EosResult, EosUnit, EquationOfState,
+    SolverOptions, State, StateBuilder,
+};
 use feos_dft::{
     Axis, ConvolverFFT, DFTProfile, DFTSolver, DFTSpecification, Geometry, Grid,
     HelmholtzEnergyFunctional, WeightFunctionInfo, DFT,
 };
 use ndarray::prelude::*;
+use ndarray::Zip;
 use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
 use std::rc::Rc;
 
+/// Helmholtz energy functionals with charged segments that interact
+/// through a mean-field electrostatic potential.
+pub trait Electrostatics {
+    /// Charge numbers of all segments.
+    fn segment_charges(&self) -> Array1<f64>;
+
+    /// Whether the functional contains the mean-field Coulomb interaction
+    /// of the charged segments.
+    fn mean_field_coulomb(&self) -> bool;
+
+    /// Relative permittivity for the given (reduced) densities of the components.
+    fn relative_permittivity(&self, partial_density: &Array1<f64>) -> f64;
+}
+
 pub enum MicelleInitialization<U> {
+    /// Peak and width of a gaussian external potential acting on segment 0.
     ExternalPotential(f64, f64),
+    /// Reduced external potentials of the given shapes acting on individual
+    /// segments. Potentials acting on the same segment are added.
+    SegmentPotentials(Vec<(usize, PotentialShape)>),
+    /// Reduced external potential of all segments on the grid.
+    ExternalPotentialArray(Array2<f64>),
     Density(QuantityArray2<U>),
 }
 
 impl<U> MicelleInitialization<U> {
     fn density(&self) -> Option<&QuantityArray2<U>> {
         match self {
-            Self::ExternalPotential(_, _) => None,
             Self::Density(density) => Some(density),
+            _ => None,
+        }
+    }
+
+    /// Reduced external potential of all segments for the given grid and
+    /// center of the aggregate.
+    fn external_potential(
+        &self,
+        segments: usize,
+        grid: &Array1<f64>,
+        center: f64,
+    ) -> EosResult<Array2<f64>> {
+        let mut external_potential = Array2::zeros((segments, grid.len()));
+        match self {
+            Self::ExternalPotential(peak, width) => {
+                let shape = PotentialShape::Gaussian {
+                    peak: *peak,
+                    width: *width,
+                };
+                external_potential
+                    .row_mut(0)
+                    .assign(&grid.mapv(|r| shape.evaluate(r - center)));
+            }
+            Self::SegmentPotentials(potentials) => {
+                for (s, shape) in potentials {
+                    if *s >= segments {
+                        return Err(ParameterError::IncompatibleParameters(format!(
+                            "segment {} does not exist in a system with {} segments",
+                            s, segments
+                        ))
+                        .into());
+                    }
+                    let mut v = external_potential.row_mut(*s);
+                    v += &grid.mapv(|r| shape.evaluate(r - center));
+                }
+            }
+            Self::ExternalPotentialArray(potential) => {
+                if potential.dim() != external_potential.dim() {
+                    return Err(ParameterError::IncompatibleParameters(format!(
+                        "the external potential has shape {:?} instead of {:?}",
+                        potential.dim(),
+                        external_potential.dim()
+                    ))
+                    .into());
+                }
+                external_potential.assign(potential);
+            }
+            Self::Density(_) => (),
+        }
+        Ok(external_potential)
+    }
+}
+
+/// Shape of a reduced external potential $\frac{V(r)}{k_\mathrm{B}T}$ used to
+/// initialize micelles. Positive values repel and negative values attract
+/// the segments they act on.
+#[derive(Clone, Copy, Debug)]
+pub enum PotentialShape {
+    /// Gaussian $V(r)=V_0\exp\left(-\frac{r^2}{2w^2}\right)$
+    Gaussian { peak: f64, width: f64 },
+    /// Constant potential inside the given distance from the center
+    Step { height: f64, radius: f64 },
+    /// Smoothed step $V(r)=\frac{V_0}{2}\left(1-\tanh\frac{r-R}{w}\right)$
+    Tanh {
+        height: f64,
+        radius: f64,
+        width: f64,
+    },
+}
+
+impl PotentialShape {
+    /// Value of the potential at the given distance from the center.
+    fn evaluate(&self, r: f64) -> f64 {
+        let r = r.abs();
+        match *self {
+            Self::Gaussian { peak, width } => peak * (-0.5 * r * r / (width * width)).exp(),
+            Self::Step { height, radius } => {
+                if r < radius {
+                    height
+                } else {
+                    0.0
+                }
+            }
+            Self::Tanh {
+                height,
+                radius,
+                width,
+            } => 0.5 * height * (1.0 - ((r - radius) / width).tanh()),
         }
     }
 }
 
 pub enum MicelleSpecification<U> {
     ChemicalPotential,
+    /// Excess number of surfactant molecules (component 1) in water
+    /// (component 0) at a given pressure.
+    ///
+    /// For planar bilayers, the excess numbers refer to the whole bilayer,
+    /// like `MicelleProfile::delta_n`.
     Size {
         delta_n_surfactant: f64,
         pressure: QuantityScalar<U>,
     },
+    /// Excess numbers of molecules of several surfactants at a given pressure.
+    ///
+    /// The chemical potentials of all solvents are shifted by the same amount
+    /// so that the bulk phase has the given pressure. The chemical potentials
+    /// of all other components are fixed. As for `Size`, the excess numbers
+    /// of planar bilayers refer to the whole bilayer.
+    MixedSize {
+        surfactants: Vec<usize>,
+        delta_n_surfactants: Vec<f64>,
+        solvents: Vec<usize>,
+        pressure: QuantityScalar<U>,
+    },
 }
 
 impl<U: EosUnit, F: HelmholtzEnergyFunctional> DFTSpecification<U, Ix1, F>
@@ -39,32 +174,137 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional> DFTSpecification<U, Ix1, F>
         z: &Array1<f64>,
         bulk: &State<U, DFT<F>>,
     ) -> EosResult<Array1<f64>> {
-        Ok(match self {
-            Self::ChemicalPotential => chemical_potential.clone(),
+        match self {
+            Self::ChemicalPotential => Ok(chemical_potential.clone()),
             Self::Size {
                 delta_n_surfactant,
                 pressure,
-            } => {
-                let m: &Array1<_> = &profile.dft.m();
-                let rho_s_bulk = bulk.partial_density.get(1);
-                let rho_w_bulk = bulk.partial_density.get(0);
-                let f_bulk = bulk.helmholtz_energy(Contributions::Total) / bulk.volume;
-                let mu_s_bulk = bulk.chemical_potential(Contributions::Total).get(1);
-                let n_s_bulk = (rho_s_bulk * profile.volume()).to_reduced(U::reference_moles())?;
-                let mut spec = ((delta_n_surfactant + n_s_bulk) / z).mapv(f64::ln) * m;
-                spec[0] = ((pressure + f_bulk - rho_s_bulk * mu_s_bulk) / rho_w_bulk)
-                    .to_reduced(U::reference_molar_energy())?
-                    / bulk.temperature.to_reduced(U::reference_temperature())?;
-                spec
-            }
-        })
+            } => size_chemical_potential(
+                profile,
+                chemical_potential,
+                z,
+                bulk,
+                &[1],
+                &[*delta_n_surfactant],
+                &[0],
+                *pressure,
+            ),
+            Self::MixedSize {
+                surfactants,
+                delta_n_surfactants,
+                solvents,
+                pressure,
+            } => size_chemical_potential(
+                profile,
+                chemical_potential,
+                z,
+                bulk,
+                surfactants,
+                delta_n_surfactants,
+                solvents,
+                *pressure,
+            ),
+        }
     }
 }
 
+/// Chemical potentials of all segments for given excess numbers of the
+/// surfactants and a given pressure.
+fn size_chemical_potential<U: EosUnit, F: HelmholtzEnergyFunctional>(
+    profile: &DFTProfile<U, Ix1, F>,
+    chemical_potential: &Array1<f64>,
+    z: &Array1<f64>,
+    bulk: &State<U, DFT<F>>,
+    surfactants: &[usize],
+    delta_n_surfactants: &[f64],
+    solvents: &[usize],
+    pressure: QuantityScalar<U>,
+) -> EosResult<Array1<f64>> {
+    if surfactants.len() != delta_n_surfactants.len() {
+        return Err(EosError::IncompatibleComponents(
+            surfactants.len(),
+            delta_n_surfactants.len(),
+        ));
+    }
+    check_components(bulk.eos.components(), surfactants, solvents)?;
+    if solvents.is_empty() {
+        return Err(ParameterError::IncompatibleParameters(
+            "at least one solvent is required to specify the pressure".into(),
+        )
+        .into());
+    }
+
+    let m: &Array1<_> = &profile.dft.m();
+    let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+    let volume = profile.volume();
+    let mu_bulk = bulk
+        .chemical_potential(Contributions::Total)
+        .to_reduced(U::reference_molar_energy())?
+        / t;
+
+    // common shift of the solvent chemical potentials that corrects the
+    // bulk pressure (Gibbs-Duhem equation at constant temperature)
+    let rho_solvents = solvents
+        .iter()
+        .map(|&j| bulk.partial_density.get(j))
+        .fold(0.0 * U::reference_density(), |acc, rho| acc + rho);
+    let delta_mu = ((pressure - bulk.pressure(Contributions::Total)) / rho_solvents)
+        .to_reduced(U::reference_molar_energy())?
+        / t;
+
+    // the chemical potential of a component is distributed over its
+    // segments proportionally to their chain lengths
+    let component_index = profile.dft.component_index();
+    let mut m_components = Array1::zeros(bulk.eos.components());
+    for (&m_s, &c) in m.iter().zip(component_index.iter()) {
+        m_components[c] += m_s;
+    }
+
+    // only one leaflet of a planar bilayer is resolved
+    let leaflets = match profile.grid.axes()[0].geometry {
+        Geometry::Cartesian => 2.0,
+        _ => 1.0,
+    };
+
+    let mut spec = chemical_potential.clone();
+    for (s, &c) in component_index.iter().enumerate() {
+        if let Some(k) = surfactants.iter().position(|&i| i == c) {
+            let n_bulk = (bulk.partial_density.get(c) * volume).to_reduced(U::reference_moles())?;
+            spec[s] = ((delta_n_surfactants[k] / leaflets + n_bulk) / z[s]).ln() * m[s];
+        } else if solvents.contains(&c) {
+            spec[s] = (mu_bulk[c] + delta_mu) * m[s] / m_components[c];
+        }
+    }
+    Ok(spec)
+}
+
+/// Check that surfactants and solvents are disjoint sets of valid component indices.
+fn check_components(components: usize, surfactants: &[usize], solvents: &[usize]) -> EosResult<()> {
+    for (k, &i) in surfactants.iter().chain(solvents).enumerate() {
+        if i >= components {
+            return Err(ParameterError::IncompatibleParameters(format!(
+                "component {} does not exist in a system with {} components",
+                i, components
+            ))
+            .into());
+        }
+        if surfactants.iter().chain(solvents).take(k).any(|&j| j == i) {
+            return Err(ParameterError::IncompatibleParameters(format!(
+                "component {} is specified more than once",
+                i
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
 pub struct MicelleProfile<U: EosUnit, F: HelmholtzEnergyFunctional> {
     pub profile: DFTProfile<U, Ix1, F>,
     pub delta_omega: Option<QuantityScalar<U>>,
     pub delta_n: Option<QuantityArray1<U>>,
+    /// Reduced mean-field electrostatic potential $\frac{e\psi}{k_\mathrm{B}T}$
+    pub electrostatic_potential: Option<Array1<f64>>,
 }
 
 impl<U: EosUnit, F: HelmholtzEnergyFunctional> Clone for MicelleProfile<U, F> {
@@ -73,6 +313,7 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional> Clone for MicelleProfile<U, F> {
             profile: self.profile.clone(),
             delta_omega: self.delta_omega,
             delta_n: self.delta_n.clone(),
+            electrostatic_potential: self.electrostatic_potential.clone(),
         }
     }
 }
@@ -123,14 +364,42 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
         self.delta_n =
             Some(self.profile.moles() - &self.profile.bulk.partial_density * self.profile.volume());
 
+        // only one leaflet of a planar bilayer is resolved
+        if matches!(self.profile.grid.axes()[0].geometry, Geometry::Cartesian) {
+            self.delta_omega = self.delta_omega.map(|omega| omega * 2.0);
+            self.delta_n = self.delta_n.as_ref().map(|n| n * 2.0);
+        }
+
         Ok(())
     }
+
+    /// Excess grand potential per excess molecule of the given surfactants.
+    ///
+    /// In contrast to `delta_omega`, the result does not depend on the size
+    /// of the aggregate or the geometry, so that micelles, bilayers and
+    /// vesicles can be compared at equal chemical potential.
+    pub fn delta_omega_per_molecule(
+        &self,
+        surfactants: &[usize],
+    ) -> EosResult<Option<QuantityScalar<U>>> {
+        check_components(self.profile.bulk.eos.components(), surfactants, &[])?;
+        Ok(self
+            .delta_omega
+            .zip(self.delta_n.as_ref())
+            .map(|(omega, n)| {
+                let n_surfactants = surfactants
+                    .iter()
+                    .fold(0.0 * U::reference_moles(), |acc, &i| acc + n.get(i));
+                omega / n_surfactants
+            }))
+    }
 }
 
 impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
     fn new(
         bulk: &State<U, DFT<F>>,
         axis: Axis,
+        center: f64,
         initialization: MicelleInitialization<U>,
         specification: MicelleSpecification<U>,
     ) -> EosResult<Self> {
@@ -138,20 +407,14 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
 
         // calculate external potential
         let t = bulk.temperature.to_reduced(U::reference_temperature())?;
-        let mut external_potential = Array2::zeros((dft.component_index().len(), axis.grid.len()));
-        if let MicelleInitialization::ExternalPotential(peak, width) = initialization {
-            external_potential.index_axis_mut(Axis(0), 0).assign(
-                &axis
-                    .grid
-                    .mapv(|r| peak * (-0.5 * r * r / (width * width)).exp()),
-            );
-        }
+        let external_potential =
+            initialization.external_potential(dft.component_index().len(), &axis.grid, center)?;
 
         // initialize convolver
         let grid = match axis.geometry {
             Geometry::Spherical => Grid::Spherical(axis),
             Geometry::Cylindrical => Grid::Polar(axis),
-            _ => unreachable!(),
+            Geometry::Cartesian => Grid::Cartesian1(axis),
         };
         let contributions = dft.contributions();
         let weight_functions: Vec<WeightFunctionInfo<f64>> = contributions
@@ -176,6 +439,7 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
             profile,
             delta_omega: None,
             delta_n: None,
+            electrostatic_potential: None,
         })
     }
 
@@ -189,6 +453,7 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
         Self::new(
             bulk,
             Axis::new_spherical(n_grid, width)?,
+            0.0,
             initialization,
             specification,
         )
@@ -204,6 +469,57 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
         Self::new(
             bulk,
             Axis::new_polar(n_grid, width)?,
+            0.0,
+            initialization,
+            specification,
+        )
+    }
+
+    /// Planar bilayer on a cartesian axis.
+    ///
+    /// The midplane of the bilayer is located at the origin, where the profile
+    /// is symmetric, so that only one leaflet is resolved. The excess
+    /// properties are given per area of the full bilayer.
+    pub fn new_planar(
+        bulk: &State<U, DFT<F>>,
+        n_grid: usize,
+        width: QuantityScalar<U>,
+        initialization: MicelleInitialization<U>,
+        specification: MicelleSpecification<U>,
+    ) -> EosResult<Self> {
+        Self::new(
+            bulk,
+            Axis::new_cartesian(n_grid, width, None)?,
+            0.0,
+            initialization,
+            specification,
+        )
+    }
+
+    /// Spherical vesicle with a core of bulk solvent.
+    ///
+    /// The external potential used for the initialization is centered at the
+    /// given radius of the shell instead of the origin.
+    pub fn new_vesicle(
+        bulk: &State<U, DFT<F>>,
+        n_grid: usize,
+        width: QuantityScalar<U>,
+        radius: QuantityScalar<U>,
+        initialization: MicelleInitialization<U>,
+        specification: MicelleSpecification<U>,
+    ) -> EosResult<Self> {
+        let r = radius.to_reduced(U::reference_length())?;
+        if r <= 0.0 || r >= width.to_reduced(U::reference_length())? {
+            return Err(EosError::InvalidState(
+                "MicelleProfile::new_vesicle".into(),
+                "radius".into(),
+                r,
+            ));
+        }
+        Self::new(
+            bulk,
+            Axis::new_spherical(n_grid, width)?,
+            r,
             initialization,
             specification,
         )
@@ -222,19 +538,53 @@ const MAX_ITER_MICELLE: usize = 50;
 const TOL_MICELLE: f64 = 1e-5;
 
 impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
+    /// Critical micelle of a surfactant (component 1) in water (component 0)
+    /// at the temperature and pressure of the bulk phase.
     pub fn critical_micelle(
+        self,
+        solver: Option<&DFTSolver>,
+        options: SolverOptions,
+    ) -> EosResult<Self> {
+        self.critical_mixed_micelle(&[1], solver, options)
+    }
+
+    /// Critical micelle at the temperature and pressure of the bulk phase.
+    ///
+    /// The total mole fraction of the given surfactants in the bulk phase is
+    /// varied until the excess grand potential of the micelle vanishes. The
+    /// relative compositions among the surfactants and among all other
+    /// components are kept at their values in the initial bulk phase.
+    pub fn critical_mixed_micelle(
         mut self,
+        surfactants: &[usize],
         solver: Option<&DFTSolver>,
         options: SolverOptions,
     ) -> EosResult<Self> {
-        let n_grid = self.profile.r().len();
         let temperature = self.profile.bulk.temperature;
         let t = temperature.to_reduced(U::reference_temperature())?;
         let pressure = self.profile.bulk.pressure(Contributions::Total);
         let eos = self.profile.bulk.eos.clone();
-        let indices = self.profile.bulk.eos.component_index().into_owned();
         self.profile.specification = Rc::new(MicelleSpecification::ChemicalPotential);
 
+        // relative compositions of the surfactants and of all other components
+        check_components(eos.components(), surfactants, &[])?;
+        let molefracs = &self.profile.bulk.molefracs;
+        let mut x = surfactants.iter().map(|&i| molefracs[i]).sum::<f64>();
+        if x <= 0.0 || x >= 1.0 {
+            return Err(EosError::InvalidState(
+                "MicelleProfile::critical_mixed_micelle".into(),
+                "surfactant mole fraction".into(),
+                x,
+            ));
+        }
+        let mut x_surfactants = Array1::zeros(molefracs.len());
+        let mut x_others = molefracs / (1.0 - x);
+        for &i in surfactants {
+            x_surfactants[i] = molefracs[i] / x;
+            x_others[i] = 0.0;
+        }
+        let direction = &x_surfactants - &x_others;
+
         for _ in 0..options.max_iter.unwrap_or(MAX_ITER_MICELLE) {
             // check for convergence
             if self
@@ -247,37 +597,36 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
                 return Ok(self);
             }
 
-            let bulk = &mut self.profile.bulk;
-            let mut x = bulk.molefracs[1];
-
-            // Calculate Newton step
-            let delta_n = self.delta_n.as_ref().unwrap();
-            let dp_drho = bulk.dp_dni(Contributions::Total) * bulk.volume;
-            let dmu_drho = bulk.dmu_dni(Contributions::Total) * bulk.volume;
-            let p_term = (dp_drho.get(1) - dp_drho.get(0))
-                / (dp_drho.get(1) * x + dp_drho.get(0) * (1.0 - x));
-            let a = delta_n.get(1) * dmu_drho.get((1, 1)) + delta_n.get(0) * dmu_drho.get((1, 0));
-            let b = delta_n.get(1) * dmu_drho.get((0, 1)) + delta_n.get(0) * dmu_drho.get((0, 0));
-            let domega_dx = -(((a - b) * x + b) * p_term + a - b) * bulk.density;
-            x -= self.delta_omega.unwrap().to_reduced(domega_dx)?;
-
-            // udpate bulk and chemical potential
-            *bulk = StateBuilder::new(&eos)
+            let bulk = &self.profile.bulk;
+
+            // Calculate Newton step: the change of the excess grand potential
+            // follows from the Gibbs adsorption equation at constant pressure
+            let delta_n = self
+                .delta_n
+                .as_ref()
+                .unwrap()
+                .to_reduced(U::reference_moles())?;
+            let rho = bulk.density.to_reduced(U::reference_density())?;
+            let dp_drho = (bulk.dp_dni(Contributions::Total) * bulk.volume)
+                .to_reduced(U::reference_pressure() / U::reference_density())?;
+            let dmu_drho = (bulk.dmu_dni(Contributions::Total) * bulk.volume)
+                .to_reduced(U::reference_molar_energy() / U::reference_density())?;
+            let p_term = dp_drho.dot(&direction) / dp_drho.dot(&bulk.molefracs);
+            let drho_dx = (&direction - &(&bulk.molefracs * p_term)) * rho;
+            let domega_dx = -delta_n.dot(&dmu_drho.dot(&drho_dx));
+            x -= self
+                .delta_omega
+                .unwrap()
+                .to_reduced(U::reference_energy())?
+                / domega_dx;
+
+            // udpate bulk, chemical potential and density profile
+            let bulk = StateBuilder::new(&eos)
                 .temperature(temperature)
                 .pressure(pressure)
-                .molefracs(&arr1(&[1.0 - x, x]))
+                .molefracs(&(&x_others * (1.0 - x) + &x_surfactants * x))
                 .build()?;
-
-            // update density profile
-            for (i, &j) in indices.iter().enumerate() {
-                let rho_bulk = self.profile.density.get((i, n_grid - 1));
-                for k in 0..n_grid {
-                    let rho_old = self.profile.density.get((i, k));
-                    self.profile
-                        .density
-                        .try_set((i, k), rho_old + bulk.partial_density.get(j) - rho_bulk)?;
-                }
-            }
+            self.update_bulk(bulk)?;
 
             // solve profile
             self = self.solve(solver)?;
@@ -287,4 +636,1316 @@ impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
             "MicelleProfile::criticelle_micelle".into(),
         ))
     }
+
+    /// Replace the bulk phase and shift the density profile by the change of
+    /// the bulk densities.
+    fn update_bulk(&mut self, bulk: State<U, DFT<F>>) -> EosResult<()> {
+        let n_grid = self.profile.r().len();
+        let indices = bulk.eos.component_index().into_owned();
+        for (i, &j) in indices.iter().enumerate() {
+            let rho_bulk = self.profile.density.get((i, n_grid - 1));
+            for k in 0..n_grid {
+                let rho_old = self.profile.density.get((i, k));
+                self.profile
+                    .density
+                    .try_set((i, k), rho_old + bulk.partial_density.get(j) - rho_bulk)?;
+            }
+        }
+        self.profile.bulk = bulk;
+        Ok(())
+    }
+
+    /// Reduced chemical potentials of all components as sums over the
+    /// chemical potentials of their segments.
+    fn component_chemical_potential(&self) -> Array1<f64> {
+        let mut mu = Array1::zeros(self.profile.bulk.eos.components());
+        for (&mu_s, &c) in self
+            .profile
+            .chemical_potential
+            .iter()
+            .zip(self.profile.dft.component_index().iter())
+        {
+            mu[c] += mu_s;
+        }
+        mu
+    }
+}
+
+/// Work of formation and equilibrium size distribution of the micelles of a
+/// surfactant.
+///
+/// The mole fractions of the aggregates follow from the law of mass action
+/// $x_N=\exp\left(-\frac{W(N)}{k_\mathrm{B}T}\right)$ in terms of the work
+/// of formation $W(N)$ of an aggregate with aggregation number $N$ in the
+/// bulk phase.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MicelleSizeDistribution {
+    /// Reduced temperature
+    pub temperature: f64,
+    /// Mole fraction of the surfactant in the bulk phase
+    pub bulk_mole_fraction: f64,
+    /// Aggregation numbers (excess numbers of surfactant molecules)
+    pub aggregation_number: Vec<f64>,
+    /// Reduced work of formation $\frac{W(N)}{k_\mathrm{B}T}$
+    pub work_of_formation: Vec<f64>,
+    /// Mole fractions of the aggregates in the bulk phase
+    pub mole_fraction: Vec<f64>,
+    /// Number-averaged aggregation number
+    pub mean_aggregation_number: f64,
+    /// Mole fraction of free surfactant molecules at which the same number
+    /// of surfactant molecules is bound in micelles, i.e., in aggregates with
+    /// an aggregation number larger than 1
+    pub critical_micelle_concentration: f64,
+}
+
+impl MicelleSizeDistribution {
+    fn new(
+        temperature: f64,
+        bulk_mole_fraction: f64,
+        aggregation_number: Vec<f64>,
+        work_of_formation: Vec<f64>,
+    ) -> EosResult<Self> {
+        if aggregation_number.is_empty() || aggregation_number.len() != work_of_formation.len() {
+            return Err(EosError::Error(format!(
+                "the size distribution requires the same non-zero number of aggregation numbers ({}) and works of formation ({})",
+                aggregation_number.len(),
+                work_of_formation.len()
+            )));
+        }
+        if aggregation_number
+            .iter()
+            .chain(&work_of_formation)
+            .any(|v| !v.is_finite())
+        {
+            return Err(EosError::Error(
+                "the size distribution requires finite aggregation numbers and works of formation"
+                    .into(),
+            ));
+        }
+        let mole_fraction: Vec<_> = work_of_formation.iter().map(|w| (-w).exp()).collect();
+
+        // the mole fractions are weighted relative to the most probable
+        // aggregate to avoid underflows
+        let w_min = work_of_formation
+            .iter()
+            .cloned()
+            .fold(f64::INFINITY, f64::min);
+        let weights: Vec<_> = work_of_formation
+            .iter()
+            .map(|w| (w_min - w).exp())
+            .collect();
+        let mean_aggregation_number = aggregation_number
+            .iter()
+            .zip(&weights)
+            .map(|(n, x)| n * x)
+            .sum::<f64>()
+            / weights.iter().sum::<f64>();
+
+        // a change of the free surfactant mole fraction by a factor exp(s)
+        // changes the work of formation of an aggregate by -N*s; find the
+        // mole fraction for which bound and free surfactant molecules balance.
+        // Only aggregates with N > 1 are micelles, for which the logarithm of
+        // the bound mole fraction increases faster than that of the free
+        // surfactant, so that the balance is unique.
+        let micelles: Vec<_> = aggregation_number
+            .iter()
+            .zip(&work_of_formation)
+            .filter(|(&n, _)| n > 1.0)
+            .map(|(&n, &w)| (n, w))
+            .collect();
+        if micelles.is_empty() {
+            return Err(EosError::Error(
+                "the size distribution does not contain aggregates with an aggregation number larger than 1".into(),
+            ));
+        }
+        let mut s = 0.0;
+        for _ in 0..MAX_ITER_MICELLE {
+            // log-sum-exp of ln(N) + N*s - W(N)
+            let a: Vec<_> = micelles.iter().map(|(n, w)| n.ln() + n * s - w).collect();
+            let a_max = a.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let (mut sum, mut sum_n) = (0.0, 0.0);
+            for (&(n, _), a) in micelles.iter().zip(&a) {
+                let x = (a - a_max).exp();
+                sum += x;
+                sum_n += n * x;
+            }
+            let f = a_max + sum.ln() - bulk_mole_fraction.ln() - s;
+            if f.abs() < TOL_MICELLE {
+                return Ok(Self {
+                    temperature,
+                    bulk_mole_fraction,
+                    aggregation_number,
+                    work_of_formation,
+                    mole_fraction,
+                    mean_aggregation_number,
+                    critical_micelle_concentration: bulk_mole_fraction * s.exp(),
+                });
+            }
+            s -= f / (sum_n / sum - 1.0);
+        }
+        Err(EosError::NotConverged(
+            "MicelleSizeDistribution::critical_micelle_concentration".into(),
+        ))
+    }
+
+    /// Write the size distribution to a JSON file.
+    pub fn to_json<P: AsRef<Path>>(&self, file: P) -> Result<(), ParameterError> {
+        let writer = BufWriter::new(File::create(file)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
+    /// Scan the size of micelles of the given surfactant by solving the
+    /// profile with `MicelleSpecification::MixedSize` at the pressure of the
+    /// bulk phase for every given excess number of surfactant molecules,
+    /// starting from the current profile. The chemical potentials of the
+    /// solvents are shifted to keep the pressure, those of all other
+    /// components are fixed.
+    ///
+    /// The work of formation in the bulk phase is obtained from the excess
+    /// grand potential of every aggregate and the difference between its
+    /// chemical potentials and those of the bulk phase.
+    pub fn size_distribution(
+        &self,
+        surfactant: usize,
+        solvents: &[usize],
+        delta_n_surfactant: &[f64],
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<MicelleSizeDistribution> {
+        let bulk = &self.profile.bulk;
+        check_components(bulk.eos.components(), &[surfactant], solvents)?;
+        let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+        let pressure = bulk.pressure(Contributions::Total);
+        let mu_bulk = bulk
+            .chemical_potential(Contributions::Total)
+            .to_reduced(U::reference_molar_energy())?
+            / t;
+
+        let mut profile = self.clone();
+        let mut aggregation_number = Vec::with_capacity(delta_n_surfactant.len());
+        let mut work_of_formation = Vec::with_capacity(delta_n_surfactant.len());
+        for &delta_n in delta_n_surfactant {
+            profile = profile
+                .update_specification(MicelleSpecification::MixedSize {
+                    surfactants: vec![surfactant],
+                    delta_n_surfactants: vec![delta_n],
+                    solvents: solvents.to_vec(),
+                    pressure,
+                })
+                .solve(solver)?;
+
+            let mu = profile.component_chemical_potential();
+            let delta_n = profile
+                .delta_n
+                .as_ref()
+                .unwrap()
+                .to_reduced(U::reference_moles())?;
+            let delta_omega = profile
+                .delta_omega
+                .unwrap()
+                .to_reduced(U::reference_energy())?
+                / t;
+            aggregation_number.push(delta_n[surfactant]);
+            work_of_formation.push(delta_omega + delta_n.dot(&(mu - &mu_bulk)));
+        }
+
+        MicelleSizeDistribution::new(
+            t,
+            bulk.molefracs[surfactant],
+            aggregation_number,
+            work_of_formation,
+        )
+    }
+}
+
+/// Micelles along a branch traced by `MicelleProfile::trace_branch`.
+pub struct MicelleBranch<U: EosUnit, F: HelmholtzEnergyFunctional> {
+    /// Index of the surfactant that parametrizes the branch
+    pub surfactant: usize,
+    /// All converged profiles in the order of the branch
+    pub profiles: Vec<MicelleProfile<U, F>>,
+    /// Indices of the profiles at which the bulk surfactant mole fraction
+    /// passes through an extremum
+    pub turning_points: Vec<usize>,
+    /// Whether the branch reached `delta_n_max` within the maximum number
+    /// of continuation steps
+    pub completed: bool,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> MicelleBranch<U, F> {
+    /// Mole fractions of the surfactant in the bulk phase along the branch.
+    pub fn bulk_mole_fraction(&self) -> Array1<f64> {
+        self.profiles
+            .iter()
+            .map(|p| p.profile.bulk.molefracs[self.surfactant])
+            .collect()
+    }
+
+    /// Excess numbers of surfactant molecules along the branch.
+    pub fn delta_n_surfactant(&self) -> EosResult<Array1<f64>> {
+        self.profiles
+            .iter()
+            .map(|p| {
+                Ok(p.delta_n
+                    .as_ref()
+                    .unwrap()
+                    .get(self.surfactant)
+                    .to_reduced(U::reference_moles())?)
+            })
+            .collect()
+    }
+}
+
+const MAX_STEPS_CONTINUATION: usize = 200;
+const MIN_STEP_CONTINUATION: f64 = 1e-3;
+
+/// Bulk compositions along a branch: the surfactant mole fraction is varied
+/// and the relative composition of all other components is kept.
+struct BranchComposition {
+    surfactant: usize,
+    solvents: Vec<usize>,
+    x_others: Array1<f64>,
+}
+
+impl BranchComposition {
+    fn molefracs(&self, x: f64) -> Array1<f64> {
+        let mut molefracs = &self.x_others * (1.0 - x);
+        molefracs[self.surfactant] = x;
+        molefracs
+    }
+}
+
+impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional> MicelleProfile<U, F> {
+    /// Trace the branch of micelles of the given surfactant at the
+    /// temperature and pressure of the bulk phase with a pseudo-arclength
+    /// continuation, starting from the current profile until the excess
+    /// number of surfactant molecules exceeds `delta_n_max`.
+    ///
+    /// The branch is parametrized by the logarithms of the bulk surfactant
+    /// mole fraction and of the excess number of surfactant molecules. The
+    /// relative composition of all other components is kept at its value in
+    /// the initial bulk phase. Every step is predicted along the secant of
+    /// the last two profiles with the given arclength. The corrector solves
+    /// the profile with `MicelleSpecification::MixedSize` on the hyperplane
+    /// normal to the secant, until the surfactant chemical potential of the
+    /// micelle matches that of the bulk phase. Failed steps are repeated with
+    /// half the arclength. If `delta_n_max` is not reached within the
+    /// maximum number of steps, the branch is returned with `completed` set
+    /// to `false`.
+    pub fn trace_branch(
+        self,
+        surfactant: usize,
+        solvents: &[usize],
+        delta_n_max: f64,
+        step: f64,
+        solver: Option<&DFTSolver>,
+        options: SolverOptions,
+    ) -> EosResult<MicelleBranch<U, F>> {
+        let tol = options.tol.unwrap_or(TOL_MICELLE);
+        let max_iter = options.max_iter.unwrap_or(MAX_ITER_MICELLE);
+
+        check_components(self.profile.bulk.eos.components(), &[surfactant], solvents)?;
+        let molefracs = &self.profile.bulk.molefracs;
+        let x = molefracs[surfactant];
+        if x <= 0.0 || x >= 1.0 {
+            return Err(EosError::InvalidState(
+                "MicelleProfile::trace_branch".into(),
+                "surfactant mole fraction".into(),
+                x,
+            ));
+        }
+        let mut x_others = molefracs / (1.0 - x);
+        x_others[surfactant] = 0.0;
+        let composition = BranchComposition {
+            surfactant,
+            solvents: solvents.to_vec(),
+            x_others,
+        };
+
+        let first = self.solve(solver)?;
+        let delta_n = first
+            .delta_n
+            .as_ref()
+            .unwrap()
+            .get(surfactant)
+            .to_reduced(U::reference_moles())?;
+        if delta_n <= 0.0 {
+            return Err(EosError::InvalidState(
+                "MicelleProfile::trace_branch".into(),
+                "delta_n_surfactant".into(),
+                delta_n,
+            ));
+        }
+        let mut point = arr1(&[x.ln(), delta_n.ln()]);
+        let mut profiles = vec![first];
+        let mut turning_points = Vec::new();
+
+        // the first step increases the size of the aggregate at constant
+        // bulk composition
+        let mut tangent = arr1(&[0.0, 1.0]);
+        let mut h = step;
+        for _ in 0..MAX_STEPS_CONTINUATION {
+            if point[1].exp() >= delta_n_max {
+                break;
+            }
+
+            let last = profiles.last().unwrap();
+            let prediction = &point + &(&tangent * h);
+            let normal = arr1(&[-tangent[1], tangent[0]]);
+            match last.arclength_corrector(
+                &prediction,
+                &normal,
+                &composition,
+                solver,
+                tol,
+                max_iter,
+            ) {
+                Ok((profile, point_new)) => {
+                    let secant = &point_new - &point;
+                    let secant = &secant / secant.dot(&secant).sqrt();
+                    if profiles.len() > 1 && secant[0] * tangent[0] < 0.0 {
+                        turning_points.push(profiles.len() - 1);
+                    }
+                    tangent = secant;
+                    point = point_new;
+                    profiles.push(profile);
+                    h = (2.0 * h).min(step);
+                }
+                Err(_) => {
+                    h *= 0.5;
+                    if h < MIN_STEP_CONTINUATION * step {
+                        return Err(EosError::NotConverged(
+                            "MicelleProfile::trace_branch".into(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(MicelleBranch {
+            surfactant,
+            profiles,
+            turning_points,
+            completed: point[1].exp() >= delta_n_max,
+        })
+    }
+
+    /// Secant iteration along the normal of the predicted point until the
+    /// surfactant chemical potentials of micelle and bulk phase agree.
+    fn arclength_corrector(
+        &self,
+        prediction: &Array1<f64>,
+        normal: &Array1<f64>,
+        composition: &BranchComposition,
+        solver: Option<&DFTSolver>,
+        tol: f64,
+        max_iter: usize,
+    ) -> EosResult<(Self, Array1<f64>)> {
+        let mut a0 = 0.0;
+        let (mut profile, mut r0) = self.branch_residual(prediction, composition, solver)?;
+        if r0.abs() < tol {
+            return Ok((profile, prediction.clone()));
+        }
+        let mut a1 = 1e-2;
+        for _ in 0..max_iter {
+            let point = prediction + &(normal * a1);
+            let (profile_new, r1) = profile.branch_residual(&point, composition, solver)?;
+            profile = profile_new;
+            if r1.abs() < tol {
+                return Ok((profile, point));
+            }
+            let a2 = a1 - r1 * (a1 - a0) / (r1 - r0);
+            a0 = a1;
+            r0 = r1;
+            a1 = a2;
+        }
+        Err(EosError::NotConverged(
+            "MicelleProfile::arclength_corrector".into(),
+        ))
+    }
+
+    /// Solve the profile for the given logarithms of the bulk surfactant mole
+    /// fraction and the excess number of surfactant molecules and return the
+    /// difference between the surfactant chemical potentials of micelle and
+    /// bulk phase.
+    ///
+    /// The density of the current bulk phase is used as initial value, so
+    /// that metastable bulk phases are followed along the branch.
+    fn branch_residual(
+        &self,
+        point: &Array1<f64>,
+        composition: &BranchComposition,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<(Self, f64)> {
+        let bulk = &self.profile.bulk;
+        let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+        let pressure = bulk.pressure(Contributions::Total);
+        let moles = composition.molefracs(point[0].exp()) * U::reference_moles();
+        let bulk = State::new_npt(
+            &bulk.eos,
+            bulk.temperature,
+            pressure,
+            &moles,
+            DensityInitialization::InitialDensity(bulk.density),
+        )?;
+        let mu_bulk = bulk
+            .chemical_potential(Contributions::Total)
+            .to_reduced(U::reference_molar_energy())?
+            / t;
+
+        let surfactant = composition.surfactant;
+        let mut profile = self.update_specification(MicelleSpecification::MixedSize {
+            surfactants: vec![surfactant],
+            delta_n_surfactants: vec![point[1].exp()],
+            solvents: composition.solvents.clone(),
+            pressure,
+        });
+        profile.update_bulk(bulk)?;
+        let profile = profile.solve(solver)?;
+        let residual = profile.component_chemical_potential()[surfactant] - mu_bulk[surfactant];
+        Ok((profile, residual))
+    }
+}
+
+impl<U: EosUnit + 'static, F: HelmholtzEnergyFunctional + Electrostatics> MicelleProfile<U, F> {
+    /// Solve the profile of charged segments and calculate the reduced
+    /// mean-field electrostatic potential $\phi=\frac{e\psi}{k_\mathrm{B}T}$
+    /// of the resulting charge density.
+    ///
+    /// The Coulomb interaction of the charged segments is a contribution of
+    /// the functional (see `GcPcSaftOptions::coulomb_cutoff`), so that it is
+    /// included in the Euler-Lagrange equation and, exactly once, in the
+    /// grand potential `delta_omega`. The potential is obtained from the
+    /// Poisson equation with the permittivity of the bulk phase and vanishes
+    /// at the outer boundary of the system. For planar profiles, the charge
+    /// density is taken relative to its mean, consistent with the kernel of
+    /// the functional.
+    pub fn solve_electrostatics_inplace(
+        &mut self,
+        solver: Option<&DFTSolver>,
+        debug: bool,
+    ) -> EosResult<()> {
+        if !self.profile.dft.mean_field_coulomb() {
+            return Err(EosError::Error(
+                "The functional does not contain the mean-field Coulomb interaction of charged segments.".into(),
+            ));
+        }
+        self.profile.solve(solver, debug)?;
+
+        let t = self
+            .profile
+            .temperature
+            .to_reduced(U::reference_temperature())?;
+        let bulk_density = self
+            .profile
+            .bulk
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let bjerrum_length =
+            BJERRUM_LENGTH_VACUUM / (self.profile.dft.relative_permittivity(&bulk_density) * t);
+        let rho = self.profile.density.to_reduced(U::reference_density())?;
+        let mut charge_density = rho.t().dot(&self.profile.dft.segment_charges());
+        let axis = &self.profile.grid.axes()[0];
+        if matches!(axis.geometry, Geometry::Cartesian) {
+            let mean = charge_density.mean().unwrap();
+            charge_density -= mean;
+        }
+        self.electrostatic_potential = Some(poisson(
+            axis,
+            bjerrum_length,
+            &charge_density,
+            &Array1::zeros(charge_density.len()),
+        ));
+        self.post_process()
+    }
+
+    pub fn solve_electrostatics(mut self, solver: Option<&DFTSolver>) -> EosResult<Self> {
+        self.solve_electrostatics_inplace(solver, false)?;
+        Ok(self)
+    }
+}
+
+/// Solve $\nabla^2\phi-4\pi l_\mathrm{B}s\phi=-4\pi l_\mathrm{B}q$ for the
+/// reduced electrostatic potential $\phi$ on a cartesian, cylindrical or
+/// spherical axis with a finite volume scheme, given the Bjerrum length
+/// $l_\mathrm{B}$, the charge density $q$ and the screening density $s$.
+///
+/// The potential is symmetric at the origin and vanishes at the outer edge.
+fn poisson(
+    axis: &Axis,
+    bjerrum_length: f64,
+    charge_density: &Array1<f64>,
+    screening: &Array1<f64>,
+) -> Array1<f64> {
+    let n = axis.grid.len();
+    let d = axis.geometry.dimension();
+    let r = &axis.grid;
+    let e = &axis.edges;
+    let area = e.mapv(|e| e.powi(d - 1));
+    let volume = Array1::from_shape_fn(n, |k| (e[k + 1].powi(d) - e[k].powi(d)) / d as f64);
+
+    // coefficients of the tridiagonal system
+    let lower = Array1::from_shape_fn(n, |k| {
+        if k == 0 {
+            0.0
+        } else {
+            area[k] / (r[k] - r[k - 1])
+        }
+    });
+    let upper = Array1::from_shape_fn(n, |k| {
+        if k == n - 1 {
+            area[n] / (e[n] - r[n - 1])
+        } else {
+            area[k + 1] / (r[k + 1] - r[k])
+        }
+    });
+    let mut diagonal = Zip::from(&lower)
+        .and(&upper)
+        .and(screening)
+        .and(&volume)
+        .map_collect(|&l, &u, &s, &v| -l - u - 4.0 * PI * bjerrum_length * s * v);
+    let mut rhs = -(charge_density * &volume) * 4.0 * PI * bjerrum_length;
+
+    // Thomas algorithm
+    for k in 1..n {
+        let w = lower[k] / diagonal[k - 1];
+        diagonal[k] -= w * upper[k - 1];
+        rhs[k] -= w * rhs[k - 1];
+    }
+    let mut phi = Array1::zeros(n);
+    phi[n - 1] = rhs[n - 1] / diagonal[n - 1];
+    for k in (0..n - 1).rev() {
+        phi[k] = (rhs[k] - upper[k] * phi[k + 1]) / diagonal[k];
+    }
+    phi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GcPcSaftFunctional, GcPcSaftFunctionalParameters, GcPcSaftOptions, GcPcSaftRecord,
+        Permittivity,
+    };
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{ChemicalRecord, Identifier, IdentifierOption, SegmentRecord};
+    use feos_core::DensityInitialization;
+    use feos_dft::fundamental_measure_theory::FMTVersion;
+    use quantity::si::{SIUnit, ANGSTROM, BAR, KELVIN, MOL};
+    use std::sync::Arc;
+
+    fn micelle_profile(
+        components: &[&str],
+    ) -> EosResult<MicelleProfile<SIUnit, GcPcSaftFunctional>> {
+        let moles: Vec<_> = (0..components.len()).map(|i| 1.0 + i as f64).collect();
+        micelle_profile_with_moles(components, &moles)
+    }
+
+    fn micelle_profile_with_moles(
+        components: &[&str],
+        moles: &[f64],
+    ) -> EosResult<MicelleProfile<SIUnit, GcPcSaftFunctional>> {
+        let parameters = GcPcSaftFunctionalParameters::from_json_segments(
+            components,
+            "parameters/gc_substances.json",
+            "parameters/sauer2014_hetero.json",
+            None,
+            IdentifierOption::Name,
+        )?;
+        let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters)));
+        let bulk = State::new_npt(
+            &func,
+            300.0 * KELVIN,
+            BAR,
+            &(arr1(moles) * MOL),
+            DensityInitialization::Liquid,
+        )?;
+        MicelleProfile::new_spherical(
+            &bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotential(0.0, 1.0),
+            MicelleSpecification::ChemicalPotential,
+        )
+    }
+
+    /// Check the chemical potentials of a size specification against the
+    /// excess numbers of surfactant molecules and the Gibbs-Duhem equation
+    /// for the shift of the solvent chemical potentials.
+    fn check_size_specification(
+        profile: &DFTProfile<SIUnit, Ix1, GcPcSaftFunctional>,
+        spec: &MicelleSpecification<SIUnit>,
+        surfactants: &[usize],
+        delta_n_surfactants: &[f64],
+        solvents: &[usize],
+        pressure: QuantityScalar<SIUnit>,
+    ) -> EosResult<()> {
+        let bulk = &profile.bulk;
+        let t = bulk
+            .temperature
+            .to_reduced(SIUnit::reference_temperature())?;
+        let mu_bulk = bulk
+            .chemical_potential(Contributions::Total)
+            .to_reduced(SIUnit::reference_molar_energy())?
+            / t;
+        let rho = bulk
+            .partial_density
+            .to_reduced(SIUnit::reference_density())?;
+        let segments = profile.dft.component_index().len();
+        let mu = Array1::from_elem(segments, -3.0);
+        let z = Array1::from_shape_fn(segments, |s| 500.0 + 10.0 * s as f64);
+        let m = profile.dft.m();
+        let volume = profile.volume();
+        let spec = spec.calculate_chemical_potential(profile, &mu, &z, bulk)?;
+
+        let mut mu_components = Array1::zeros(bulk.eos.components());
+        for (s, &c) in profile.dft.component_index().iter().enumerate() {
+            mu_components[c] += spec[s];
+            if let Some(k) = surfactants.iter().position(|&i| i == c) {
+                // the segment density integrates to the given excess
+                let n = z[s] * (spec[s] / m[s]).exp();
+                let n_bulk =
+                    (bulk.partial_density.get(c) * volume).to_reduced(SIUnit::reference_moles())?;
+                assert_relative_eq!(n - n_bulk, delta_n_surfactants[k], max_relative = 1e-10);
+            } else if !solvents.contains(&c) {
+                assert_eq!(spec[s], mu[s]);
+            }
+        }
+
+        // d(p/kT) = sum_i rho_i d(mu_i/kT) with a common shift of the solvents
+        let dp = (pressure - bulk.pressure(Contributions::Total))
+            .to_reduced(SIUnit::reference_pressure())?
+            / t;
+        let dmu = mu_components[solvents[0]] - mu_bulk[solvents[0]];
+        assert!(dmu > 0.0);
+        let mut rho_dmu = 0.0;
+        for &j in solvents {
+            assert_relative_eq!(mu_components[j] - mu_bulk[j], dmu, max_relative = 1e-10);
+            rho_dmu += rho[j] * (mu_components[j] - mu_bulk[j]);
+        }
+        assert_relative_eq!(rho_dmu, dp, max_relative = 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mixed_size_specification() -> EosResult<()> {
+        // binary specification
+        let micelle = micelle_profile(&["hexane", "1-octanol"])?;
+        let profile = &micelle.profile;
+        let pressure = 2.0 * BAR;
+        let size = MicelleSpecification::Size {
+            delta_n_surfactant: 3.0,
+            pressure,
+        };
+        check_size_specification(profile, &size, &[1], &[3.0], &[0], pressure)?;
+
+        // two surfactants, two solvents and a component with a fixed
+        // chemical potential
+        let micelle = micelle_profile(&["hexane", "1-octanol", "1-hexanol", "heptane", "ethanol"])?;
+        let profile = &micelle.profile;
+        let mixed = MicelleSpecification::MixedSize {
+            surfactants: vec![1, 2],
+            delta_n_surfactants: vec![3.0, 1.5],
+            solvents: vec![0, 3],
+            pressure,
+        };
+        check_size_specification(profile, &mixed, &[1, 2], &[3.0, 1.5], &[0, 3], pressure)?;
+
+        // the segments of the solvents add up to their bulk chemical potentials
+        // at the bulk pressure, components that are neither surfactants nor
+        // solvents are unchanged
+        let micelle = micelle_profile(&["hexane", "1-octanol", "ethanol"])?;
+        let profile = &micelle.profile;
+        let bulk = &profile.bulk;
+        let t = bulk
+            .temperature
+            .to_reduced(SIUnit::reference_temperature())?;
+        let mu_bulk = bulk
+            .chemical_potential(Contributions::Total)
+            .to_reduced(SIUnit::reference_molar_energy())?
+            / t;
+        let segments = profile.dft.component_index().len();
+        let mu = Array1::from_elem(segments, -3.0);
+        let z = Array1::from_elem(segments, 500.0);
+        let mixed = MicelleSpecification::MixedSize {
+            surfactants: vec![1],
+            delta_n_surfactants: vec![3.0],
+            solvents: vec![0],
+            pressure: bulk.pressure(Contributions::Total),
+        };
+        let spec = mixed.calculate_chemical_potential(profile, &mu, &z, bulk)?;
+        let mut mu_solvent = 0.0;
+        for (s, &c) in profile.dft.component_index().iter().enumerate() {
+            match c {
+                0 => mu_solvent += spec[s],
+                1 => assert!(spec[s] != mu[s]),
+                _ => assert_eq!(spec[s], mu[s]),
+            }
+        }
+        assert_relative_eq!(mu_solvent, mu_bulk[0], max_relative = 1e-10);
+
+        // invalid index sets
+        for (surfactants, solvents) in [(vec![1], vec![1]), (vec![3], vec![0]), (vec![1], vec![])] {
+            let spec = MicelleSpecification::MixedSize {
+                delta_n_surfactants: vec![3.0; surfactants.len()],
+                surfactants,
+                solvents,
+                pressure: BAR,
+            };
+            assert!(spec
+                .calculate_chemical_potential(profile, &mu, &z, bulk)
+                .is_err());
+        }
+        Ok(())
+    }
+
+    fn critical_octanol_micelle(
+        octanol: usize,
+        moles: &[f64],
+        surfactants: &[usize],
+    ) -> EosResult<MicelleProfile<SIUnit, GcPcSaftFunctional>> {
+        let chemical_record = |name: &str, segments: Vec<&str>| {
+            ChemicalRecord::new(
+                Identifier::new("", Some(name), None, None, None, None),
+                segments.into_iter().map(String::from).collect(),
+                None,
+            )
+        };
+        let mut chemical_records = vec![chemical_record(
+            "hexane",
+            vec!["CH3", "CH2", "CH2", "CH2", "CH2", "CH3"],
+        )];
+        for _ in 0..octanol {
+            let mut segments = vec!["CH3"];
+            segments.extend(vec!["CH2"; 7]);
+            segments.push("OH");
+            chemical_records.push(chemical_record("1-octanol", segments));
+        }
+        let parameters = GcPcSaftFunctionalParameters::from_segments(
+            chemical_records,
+            SegmentRecord::from_json("parameters/sauer2014_hetero.json")?,
+            None,
+        )?;
+        let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters)));
+        let bulk = State::new_npt(
+            &func,
+            300.0 * KELVIN,
+            BAR,
+            &(arr1(moles) * MOL),
+            DensityInitialization::Liquid,
+        )?;
+        MicelleProfile::new_spherical(
+            &bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotential(5.0, 3.0),
+            MicelleSpecification::ChemicalPotential,
+        )?
+        .solve_micelle(None, None)?
+        .critical_mixed_micelle(surfactants, None, Default::default())
+    }
+
+    #[test]
+    fn test_critical_mixed_micelle() -> EosResult<()> {
+        // splitting the surfactant into two identical components does not
+        // change the critical micelle
+        let single = critical_octanol_micelle(1, &[8.0, 0.2], &[1])?;
+        let split = critical_octanol_micelle(2, &[8.0, 0.05, 0.15], &[1, 2])?;
+        for micelle in &[&single, &split] {
+            let bulk = &micelle.profile.bulk;
+            let t = bulk
+                .temperature
+                .to_reduced(SIUnit::reference_temperature())?;
+            let delta_omega = micelle
+                .delta_omega
+                .unwrap()
+                .to_reduced(SIUnit::reference_energy())?;
+            assert!(delta_omega.abs() < 1e-5 * t);
+            assert_relative_eq!(
+                bulk.pressure(Contributions::Total).to_reduced(BAR)?,
+                1.0,
+                max_relative = 1e-8
+            );
+        }
+        let x_single = single.profile.bulk.molefracs[1];
+        let x_split = &split.profile.bulk.molefracs;
+        assert_relative_eq!(x_split[1] + x_split[2], x_single, max_relative = 1e-4);
+        assert_relative_eq!(x_split[2] / x_split[1], 3.0, max_relative = 1e-10);
+        let n_single = single.delta_n.as_ref().unwrap().to_reduced(MOL)?;
+        let n_split = split.delta_n.as_ref().unwrap().to_reduced(MOL)?;
+        assert_relative_eq!(n_split[1] + n_split[2], n_single[1], max_relative = 1e-3);
+
+        // the surfactants cannot make up the whole bulk phase
+        let micelle = micelle_profile(&["hexane", "1-octanol"])?;
+        assert!(micelle
+            .clone()
+            .critical_mixed_micelle(&[0, 1], None, Default::default())
+            .is_err());
+        assert!(micelle
+            .critical_mixed_micelle(&[2], None, Default::default())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_planar_and_vesicle() -> EosResult<()> {
+        let micelle = micelle_profile(&["hexane", "1-octanol"])?;
+        let bulk = &micelle.profile.bulk;
+
+        // the bilayer is symmetric around the origin
+        let bilayer = MicelleProfile::new_planar(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotential(5.0, 3.0),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        let v = &bilayer.profile.external_potential;
+        assert!((1..64).all(|k| v[(0, k)] < v[(0, k - 1)]));
+
+        // the excess of a size specification refers to the whole bilayer
+        let bilayer = bilayer
+            .update_specification(MicelleSpecification::Size {
+                delta_n_surfactant: 0.02,
+                pressure: bulk.pressure(Contributions::Total),
+            })
+            .solve(None)?;
+        let delta_n = bilayer
+            .delta_n
+            .as_ref()
+            .unwrap()
+            .get(1)
+            .to_reduced(SIUnit::reference_moles())?;
+        assert_relative_eq!(delta_n, 0.02, max_relative = 1e-5);
+
+        // the potential of the vesicle is centered at the shell
+        let vesicle = MicelleProfile::new_vesicle(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            15.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotential(5.0, 3.0),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        let v = &vesicle.profile.external_potential;
+        let r = &vesicle.profile.grid.axes()[0].grid;
+        let k_max = (0..64).fold(
+            0,
+            |k_max, k| if v[(0, k)] > v[(0, k_max)] { k } else { k_max },
+        );
+        assert!((r[k_max] - 15.0).abs() < 0.5);
+        assert!(v[(0, 0)] < 1e-3);
+
+        // the shell has to be inside the system
+        assert!(MicelleProfile::new_vesicle(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            40.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotential(5.0, 3.0),
+            MicelleSpecification::ChemicalPotential,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_segment_potentials() -> EosResult<()> {
+        let micelle = micelle_profile(&["hexane", "1-octanol"])?;
+        let bulk = &micelle.profile.bulk;
+        let segments = bulk.eos.component_index().len();
+        let tail = PotentialShape::Tanh {
+            height: -2.0,
+            radius: 10.0,
+            width: 1.0,
+        };
+        let head = PotentialShape::Step {
+            height: 3.0,
+            radius: 5.0,
+        };
+        let micelle = MicelleProfile::new_spherical(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::SegmentPotentials(vec![(1, tail), (2, tail), (2, head)]),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        let r = &micelle.profile.grid.axes()[0].grid;
+        let v = &micelle.profile.external_potential;
+        for k in 0..64 {
+            assert_eq!(v[(0, k)], 0.0);
+            assert_eq!(v[(1, k)], tail.evaluate(r[k]));
+            assert_eq!(v[(2, k)], tail.evaluate(r[k]) + head.evaluate(r[k]));
+        }
+        assert_relative_eq!(v[(1, 0)], -2.0, max_relative = 1e-6);
+        assert_relative_eq!(v[(2, 0)], 1.0, max_relative = 1e-5);
+
+        // invalid segments and shapes
+        assert!(MicelleProfile::new_spherical(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::SegmentPotentials(vec![(segments, head)]),
+            MicelleSpecification::ChemicalPotential,
+        )
+        .is_err());
+        assert!(MicelleProfile::new_spherical(
+            bulk,
+            64,
+            30.0 * ANGSTROM,
+            MicelleInitialization::ExternalPotentialArray(Array2::zeros((segments, 32))),
+            MicelleSpecification::ChemicalPotential,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_distribution() -> EosResult<()> {
+        // W(N) = a*N^2 - b*N + c
+        let aggregation_number: Vec<_> = (1..=100).map(|n| n as f64).collect();
+        let work_of_formation: Vec<_> = aggregation_number
+            .iter()
+            .map(|n| 0.01 * (n - 50.0).powi(2) + 15.0)
+            .collect();
+        let x = 1e-4;
+        let distribution =
+            MicelleSizeDistribution::new(300.0, x, aggregation_number, work_of_formation)?;
+        assert_relative_eq!(distribution.mean_aggregation_number, 50.0, epsilon = 1e-6);
+        assert_relative_eq!(
+            distribution.mole_fraction[49],
+            (-15.0f64).exp(),
+            max_relative = 1e-12
+        );
+
+        // the aggregates are dilute
+        assert!(distribution
+            .mole_fraction
+            .iter()
+            .all(|&x| x > 0.0 && x < 1.0));
+        let bound: f64 = distribution
+            .aggregation_number
+            .iter()
+            .zip(&distribution.mole_fraction)
+            .map(|(n, x)| n * x)
+            .sum();
+        assert!(bound < 1e-3);
+
+        // at the cmc, the same number of molecules is free and bound in micelles
+        let cmc = distribution.critical_micelle_concentration;
+        let s = (cmc / x).ln();
+        let bound: f64 = distribution
+            .aggregation_number
+            .iter()
+            .zip(&distribution.work_of_formation)
+            .filter(|(&n, _)| n > 1.0)
+            .map(|(n, w)| n * (n * s - w).exp())
+            .sum();
+        assert_relative_eq!(bound, cmc, max_relative = 1e-4);
+        assert!(cmc < x);
+
+        // serialization
+        let json = serde_json::to_string(&distribution).unwrap();
+        let distribution: MicelleSizeDistribution = serde_json::from_str(&json).unwrap();
+        assert_eq!(distribution.critical_micelle_concentration, cmc);
+
+        // the mean aggregation number and the cmc do not suffer from
+        // underflowing mole fractions
+        let shifted = MicelleSizeDistribution::new(
+            300.0,
+            x,
+            distribution.aggregation_number.clone(),
+            distribution
+                .work_of_formation
+                .iter()
+                .map(|w| w + 1000.0)
+                .collect(),
+        )?;
+        assert!(shifted.mole_fraction.iter().all(|&x| x == 0.0));
+        assert_relative_eq!(shifted.mean_aggregation_number, 50.0, epsilon = 1e-6);
+        assert!(shifted.critical_micelle_concentration.is_finite());
+        assert!(shifted.critical_micelle_concentration > cmc);
+
+        // empty and degenerate scans
+        assert!(MicelleSizeDistribution::new(300.0, x, vec![], vec![]).is_err());
+        assert!(MicelleSizeDistribution::new(300.0, x, vec![10.0, 20.0], vec![1.0]).is_err());
+        assert!(MicelleSizeDistribution::new(300.0, x, vec![0.5, 1.0], vec![1.0, 2.0]).is_err());
+        assert!(MicelleSizeDistribution::new(300.0, x, vec![10.0], vec![f64::NAN]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_distribution_scan() -> EosResult<()> {
+        let delta_n = [0.5, 1.0, 2.0];
+        let micelle = micelle_profile_with_moles(&["hexane", "1-octanol"], &[3.0, 1.0])?;
+        let distribution = micelle
+            .solve(None)?
+            .size_distribution(1, &[0], &delta_n, None)?;
+
+        // every aggregate has the specified size and the work of formation
+        // of small fluctuations around the stable bulk phase is positive
+        for (n, &n_spec) in distribution.aggregation_number.iter().zip(&delta_n) {
+            assert_relative_eq!(*n, n_spec, max_relative = 1e-6);
+        }
+        let w = &distribution.work_of_formation;
+        assert!(w[0] > 0.0 && w[1] > w[0] && w[2] > w[1]);
+        assert!(distribution
+            .mole_fraction
+            .iter()
+            .all(|&x| x > 0.0 && x < 1.0));
+        assert_relative_eq!(distribution.bulk_mole_fraction, 0.25, max_relative = 1e-10);
+
+        // the result does not depend on the order of the components
+        let micelle = micelle_profile_with_moles(&["1-octanol", "hexane"], &[1.0, 3.0])?;
+        let reversed = micelle
+            .clone()
+            .solve(None)?
+            .size_distribution(0, &[1], &delta_n, None)?;
+        for (w, w_reversed) in w.iter().zip(&reversed.work_of_formation) {
+            assert_relative_eq!(w, w_reversed, max_relative = 1e-5);
+        }
+
+        // the surfactant has to exist and cannot be a solvent
+        assert!(micelle.size_distribution(2, &[1], &delta_n, None).is_err());
+        assert!(micelle.size_distribution(0, &[0], &delta_n, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace_branch() -> EosResult<()> {
+        // hexane condensing from a subsaturated vapor of propane onto an
+        // attractive seed: the adsorbed film grows until it becomes a
+        // critical droplet beyond which the supersaturation decreases
+        let parameters = GcPcSaftFunctionalParameters::from_json_segments(
+            &["propane", "hexane"],
+            "parameters/gc_substances.json",
+            "parameters/sauer2014_hetero.json",
+            None,
+            IdentifierOption::Name,
+        )?;
+        let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters)));
+        let bulk = State::new_npt(
+            &func,
+            300.0 * KELVIN,
+            BAR,
+            &(arr1(&[0.9, 0.1]) * MOL),
+            DensityInitialization::Vapor,
+        )?;
+        let seed = PotentialShape::Tanh {
+            height: -1.0,
+            radius: 6.0,
+            width: 1.0,
+        };
+        let micelle = MicelleProfile::new_spherical(
+            &bulk,
+            128,
+            40.0 * ANGSTROM,
+            MicelleInitialization::SegmentPotentials((3..9).map(|s| (s, seed)).collect()),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        let branch = micelle
+            .clone()
+            .trace_branch(1, &[0], 100.0, 0.5, None, Default::default())?;
+        assert!(branch.completed);
+
+        let x = branch.bulk_mole_fraction();
+        let delta_n = branch.delta_n_surfactant()?;
+        assert!(delta_n[delta_n.len() - 1] >= 100.0);
+
+        // the supersaturation passes through a maximum
+        assert!(!branch.turning_points.is_empty());
+        let k = branch.turning_points[0];
+        assert!(x[k] >= x[k - 1] && x[k] >= x[k + 1]);
+        assert!(x[k] > x[0] && x[k] > x[x.len() - 1]);
+
+        // the branch never reverses its direction: the arclength and the
+        // size of the aggregate increase monotonically through the turning
+        // point
+        let secants: Vec<_> = (1..x.len())
+            .map(|i| arr1(&[(x[i] / x[i - 1]).ln(), (delta_n[i] / delta_n[i - 1]).ln()]))
+            .collect();
+        for s in secants.windows(2) {
+            assert!(s[0].dot(&s[1]) > 0.0);
+        }
+        for i in 1..delta_n.len() {
+            assert!(delta_n[i] > delta_n[i - 1]);
+        }
+
+        // the surfactant has to exist and cannot be a solvent
+        assert!(micelle
+            .clone()
+            .trace_branch(2, &[0], 100.0, 0.5, None, Default::default())
+            .is_err());
+        assert!(micelle
+            .trace_branch(1, &[1], 100.0, 0.5, None, Default::default())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_poisson() -> EosResult<()> {
+        let (a, l, q, l_b) = (10.0, 50.0, 1e-3, 7.0);
+
+        // uniformly charged sphere
+        let axis = Axis::new_spherical(500, l * ANGSTROM)?;
+        let charge_density = axis.grid.mapv(|r| if r < a { q } else { 0.0 });
+        let phi = poisson(&axis, l_b, &charge_density, &Array1::zeros(500));
+        let charge = 4.0 / 3.0 * PI * a.powi(3) * q;
+        for &k in &[0, 50, 99, 100, 250, 499] {
+            let r = axis.grid[k];
+            let phi_analytic = if r < a {
+                l_b * charge * (3.0 * a * a - r * r) / (2.0 * a.powi(3))
+            } else {
+                l_b * charge / r
+            } - l_b * charge / l;
+            assert_relative_eq!(phi[k], phi_analytic, max_relative = 1e-3);
+        }
+
+        // uniformly charged slab
+        let axis = Axis::new_cartesian(500, l * ANGSTROM, None)?;
+        let charge_density = axis.grid.mapv(|z| if z < a { q } else { 0.0 });
+        let phi = poisson(&axis, l_b, &charge_density, &Array1::zeros(500));
+        for &k in &[0, 50, 99, 100, 250, 499] {
+            let z = axis.grid[k];
+            let phi_analytic = if z < a {
+                4.0 * PI * l_b * q * (a * (l - a) + 0.5 * (a * a - z * z))
+            } else {
+                4.0 * PI * l_b * q * a * (l - z)
+            };
+            assert_relative_eq!(phi[k], phi_analytic, max_relative = 1e-3);
+        }
+
+        // a constant screening without charges does not create a potential
+        let phi = poisson(&axis, l_b, &Array1::zeros(500), &Array1::ones(500));
+        assert!(phi.iter().all(|&p| p == 0.0));
+        Ok(())
+    }
+
+    fn salt_functional(options: GcPcSaftOptions) -> EosResult<Rc<GcPcSaftFunctional>> {
+        let ion = |identifier: &str, sigma, epsilon_k, z| {
+            SegmentRecord::new(
+                identifier.into(),
+                20.0,
+                GcPcSaftRecord {
+                    m: 1.0,
+                    sigma,
+                    epsilon_k,
+                    z: Some(z),
+                    ..Default::default()
+                },
+                None,
+            )
+        };
+        let segment_records = vec![
+            SegmentRecord::new(
+                "H2O".into(),
+                18.015,
+                GcPcSaftRecord {
+                    m: 1.2047,
+                    sigma: 2.7927,
+                    epsilon_k: 353.95,
+                    kappa_ab: Some(0.0451),
+                    epsilon_k_ab: Some(2425.67),
+                    ..Default::default()
+                },
+                None,
+            ),
+            ion("Na+", 2.8232, 230.0, 1.0),
+            ion("Cl-", 2.7560, 170.0, -1.0),
+        ];
+        let water = ChemicalRecord::new(
+            Identifier::new("7732-18-5", Some("water"), None, None, None, None),
+            vec!["H2O".into()],
+            None,
+        );
+        let salt = ChemicalRecord::new(
+            Identifier::new("7647-14-5", Some("sodium chloride"), None, None, None, None),
+            vec!["Na+".into(), "Cl-".into()],
+            Some(vec![]),
+        );
+        let parameters =
+            GcPcSaftFunctionalParameters::from_segments(vec![water, salt], segment_records, None)?;
+        Ok(Rc::new(GcPcSaftFunctional::with_permittivity(
+            Arc::new(parameters),
+            FMTVersion::WhiteBear,
+            options,
+            Permittivity::Constant(78.4),
+        )?))
+    }
+
+    #[test]
+    fn test_solve_electrostatics() -> EosResult<()> {
+        // double layer at a planar wall that adsorbs cations
+        let (n_grid, width) = (240, 60.0);
+        let options = GcPcSaftOptions {
+            coulomb_cutoff: Some(width),
+            ..Default::default()
+        };
+        let func = salt_functional(options)?;
+        let t = 298.15;
+        let bulk = State::new_npt(
+            &func,
+            t * KELVIN,
+            BAR,
+            &(arr1(&[55.5, 0.1]) * MOL),
+            DensityInitialization::Liquid,
+        )?;
+        let external_potential = Array2::from_shape_fn((3, n_grid), |(s, k)| {
+            if s == 1 && (k as f64 + 0.5) * width / (n_grid as f64) < 3.0 {
+                -3.0
+            } else {
+                0.0
+            }
+        });
+        let mut micelle = MicelleProfile::new_planar(
+            &bulk,
+            n_grid,
+            width * ANGSTROM,
+            MicelleInitialization::ExternalPotentialArray(external_potential),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        micelle.solve_electrostatics_inplace(None, false)?;
+
+        // the potential decays with the Debye length of the linearized
+        // Poisson-Boltzmann equation, i.e., proportional to
+        // cosh(kappa (L - z)) - 1 for a vanishing field and potential at z = L
+        let rho_salt = bulk
+            .partial_density
+            .get(1)
+            .to_reduced(SIUnit::reference_density())?;
+        let bjerrum_length = BJERRUM_LENGTH_VACUUM / (78.4 * t);
+        let kappa = (8.0 * PI * bjerrum_length * rho_salt).sqrt();
+        let phi = micelle.electrostatic_potential.as_ref().unwrap();
+        let z = &micelle.profile.grid.axes()[0].grid;
+        let reference = z.mapv(|z| (kappa * (width - z)).cosh() - 1.0);
+        assert!(phi[0] > 0.0);
+        for &(i, j) in &[(60, 100), (100, 140)] {
+            assert_relative_eq!(
+                phi[i] / phi[j],
+                reference[i] / reference[j],
+                max_relative = 0.1
+            );
+        }
+
+        // the mean-field Coulomb interaction is part of the functional
+        let func = salt_functional(Default::default())?;
+        let bulk = State::new_npt(
+            &func,
+            t * KELVIN,
+            BAR,
+            &(arr1(&[55.5, 0.1]) * MOL),
+            DensityInitialization::Liquid,
+        )?;
+        let mut micelle = MicelleProfile::new_planar(
+            &bulk,
+            n_grid,
+            width * ANGSTROM,
+            MicelleInitialization::ExternalPotential(0.0, 1.0),
+            MicelleSpecification::ChemicalPotential,
+        )?;
+        assert!(micelle.solve_electrostatics_inplace(None, false).is_err());
+        Ok(())
+    }
 }
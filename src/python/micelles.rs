@@ -1,7 +1,8 @@
 #[macro_export]
 macro_rules! impl_micelle_profile {
     ($func:ty) => {
-        /// A one-dimensional profile of a spherical or cylindrical micelle.
+        /// A one-dimensional profile of a spherical or cylindrical micelle, a planar
+        /// bilayer or a vesicle.
         #[pyclass(name = "MicelleProfile", unsendable)]
         pub struct PyMicelleProfile(MicelleProfile<SIUnit, $func>);
 
@@ -19,12 +20,15 @@ macro_rules! impl_micelle_profile {
             ///     The number of grid points.
             /// width: SINumber
             ///     The width of the system.
-            /// initialization: {(float, float), SIArray2}
+            /// initialization: {(float, float), [(int, str, float, float)], numpy.ndarray[float], SIArray2}
             ///     Either peak and width of an external potential used to initialize
-            ///     the micelle or a density profile directly.
-            /// specification: (float, SINumber), optional
-            ///     Excess number of surfactant molecules and pressure. If None, the
-            ///     chemical potential of the system is fixed.
+            ///     the micelle, a list of segment potentials (segment, 'gaussian', peak, width),
+            ///     (segment, 'step', height, radius) or (segment, 'tanh', height, radius, width),
+            ///     the reduced external potential of all segments or a density profile directly.
+            /// specification: {(float, SINumber), ([int], [float], [int], SINumber)}, optional
+            ///     Excess number of surfactant molecules and pressure, or indices of the
+            ///     surfactants, their excess numbers of molecules, indices of the solvents
+            ///     and pressure. If None, the chemical potential of the system is fixed.
             ///
             /// Returns
             /// -------
@@ -59,12 +63,15 @@ macro_rules! impl_micelle_profile {
             ///     The number of grid points.
             /// width: SINumber
             ///     The width of the system.
-            /// initialization: {(float, float), SIArray2}
+            /// initialization: {(float, float), [(int, str, float, float)], numpy.ndarray[float], SIArray2}
             ///     Either peak and width of an external potential used to initialize
-            ///     the micelle or a density profile directly.
-            /// specification: (float, SINumber), optional
-            ///     Excess number of surfactant molecules and pressure. If None, the
-            ///     chemical potential of the system is fixed.
+            ///     the micelle, a list of segment potentials (segment, 'gaussian', peak, width),
+            ///     (segment, 'step', height, radius) or (segment, 'tanh', height, radius, width),
+            ///     the reduced external potential of all segments or a density profile directly.
+            /// specification: {(float, SINumber), ([int], [float], [int], SINumber)}, optional
+            ///     Excess number of surfactant molecules and pressure, or indices of the
+            ///     surfactants, their excess numbers of molecules, indices of the solvents
+            ///     and pressure. If None, the chemical potential of the system is fixed.
             ///
             /// Returns
             /// -------
@@ -89,6 +96,97 @@ macro_rules! impl_micelle_profile {
                 Ok(PyMicelleProfile(profile))
             }
 
+            /// Crate an initial density profile of a planar bilayer.
+            ///
+            /// Only one leaflet of the symmetric bilayer is resolved, the
+            /// excess properties are given per area of the full bilayer.
+            ///
+            /// Parameters
+            /// ----------
+            /// bulk: State
+            ///     The bulk state in equilibrium with the bilayer.
+            /// n_grid: int
+            ///     The number of grid points.
+            /// width: SINumber
+            ///     The width of the system.
+            /// initialization: {(float, float), [(int, str, float, float)], numpy.ndarray[float], SIArray2}
+            ///     Either peak and width of an external potential used to initialize
+            ///     the bilayer, a list of segment potentials, the reduced external
+            ///     potential of all segments or a density profile directly.
+            /// specification: {(float, SINumber), ([int], [float], [int], SINumber)}, optional
+            ///     Excess number of surfactant molecules and pressure, or indices of the
+            ///     surfactants, their excess numbers of molecules, indices of the solvents
+            ///     and pressure. If None, the chemical potential of the system is fixed.
+            ///
+            /// Returns
+            /// -------
+            /// MicelleProfile
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(bulk, n_grid, width, initialization, specification=None)")]
+            fn new_planar(
+                bulk: PyState,
+                n_grid: usize,
+                width: PySINumber,
+                initialization: &PyAny,
+                specification: Option<&PyAny>,
+            ) -> PyResult<Self> {
+                let profile = MicelleProfile::new_planar(
+                    &bulk.0,
+                    n_grid,
+                    width.into(),
+                    parse_micelle_initialization(initialization)?,
+                    parse_micelle_specification(specification)?,
+                )?;
+                Ok(PyMicelleProfile(profile))
+            }
+
+            /// Crate an initial density profile of a spherical vesicle.
+            ///
+            /// Parameters
+            /// ----------
+            /// bulk: State
+            ///     The bulk state in equilibrium with the vesicle.
+            /// n_grid: int
+            ///     The number of grid points.
+            /// width: SINumber
+            ///     The width of the system.
+            /// radius: SINumber
+            ///     The radius of the shell of the vesicle.
+            /// initialization: {(float, float), [(int, str, float, float)], numpy.ndarray[float], SIArray2}
+            ///     Either peak and width of an external potential or a list of segment
+            ///     potentials centered at the shell used to initialize the vesicle, the
+            ///     reduced external potential of all segments or a density profile directly.
+            /// specification: {(float, SINumber), ([int], [float], [int], SINumber)}, optional
+            ///     Excess number of surfactant molecules and pressure, or indices of the
+            ///     surfactants, their excess numbers of molecules, indices of the solvents
+            ///     and pressure. If None, the chemical potential of the system is fixed.
+            ///
+            /// Returns
+            /// -------
+            /// MicelleProfile
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(bulk, n_grid, width, radius, initialization, specification=None)")]
+            fn new_vesicle(
+                bulk: PyState,
+                n_grid: usize,
+                width: PySINumber,
+                radius: PySINumber,
+                initialization: &PyAny,
+                specification: Option<&PyAny>,
+            ) -> PyResult<Self> {
+                let profile = MicelleProfile::new_vesicle(
+                    &bulk.0,
+                    n_grid,
+                    width.into(),
+                    radius.into(),
+                    parse_micelle_initialization(initialization)?,
+                    parse_micelle_specification(specification)?,
+                )?;
+                Ok(PyMicelleProfile(profile))
+            }
+
             /// Create a new micelle profile with a given specification.
             ///
             /// Parameters
@@ -139,6 +237,34 @@ macro_rules! impl_micelle_profile {
                 )?;
                 Ok(slf)
             }
+
+            /// Solve the micelle profile in-place with the mean-field Coulomb
+            /// interaction of the charged segments and calculate the
+            /// electrostatic potential.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used to solve the profile.
+            /// debug: bool, optional
+            ///     If True, do not check for convergence.
+            ///
+            /// Returns
+            /// -------
+            /// MicelleProfile
+            ///
+            #[pyo3(text_signature = "(solver=None, debug=False)")]
+            #[args(solver = "None", debug = "false")]
+            fn solve_electrostatics(
+                slf: &PyCell<Self>,
+                solver: Option<PyDFTSolver>,
+                debug: bool,
+            ) -> PyResult<&PyCell<Self>> {
+                slf.borrow_mut()
+                    .0
+                    .solve_electrostatics_inplace(solver.map(|s| s.0).as_ref(), debug)?;
+                Ok(slf)
+            }
         }
 
         #[pymethods]
@@ -153,6 +279,36 @@ macro_rules! impl_micelle_profile {
                 self.0.delta_n.clone().map(PySIArray1::from)
             }
 
+            /// Excess grand potential per excess molecule of the given surfactants.
+            ///
+            /// Parameters
+            /// ----------
+            /// surfactants : [int]
+            ///     The indices of the surfactants.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber, optional
+            ///
+            #[pyo3(text_signature = "(surfactants)")]
+            fn delta_omega_per_molecule(&self, surfactants: Vec<usize>) -> PyResult<Option<PySINumber>> {
+                Ok(self
+                    .0
+                    .delta_omega_per_molecule(&surfactants)?
+                    .map(PySINumber::from))
+            }
+
+            #[getter]
+            fn get_electrostatic_potential<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> Option<&'py numpy::PyArray1<f64>> {
+                self.0
+                    .electrostatic_potential
+                    .as_ref()
+                    .map(|phi| numpy::ToPyArray::to_pyarray(phi, py))
+            }
+
             /// Use the converged micelle to calculate the critical micelle for the given
             /// temperature and pressure.
             ///
@@ -185,6 +341,196 @@ macro_rules! impl_micelle_profile {
                     )?,
                 ))
             }
+
+            /// Use the converged micelle to calculate the critical micelle of a mixture
+            /// of surfactants for the given temperature and pressure.
+            ///
+            /// Parameters
+            /// ----------
+            /// surfactants : [int]
+            ///     The indices of the surfactants.
+            /// solver : DFTSolver, optional
+            ///     The solver used to solve the profile.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations of the Newton solver.
+            /// tol: float, optional
+            ///     The tolerance of the Newton solver.
+            /// verbosity: Verbosity, optional
+            ///     The verbosity of the Newton solver.
+            ///
+            /// Returns
+            /// -------
+            /// MicelleProfileResult
+            ///
+            #[pyo3(text_signature = "(surfactants, solver=None, max_iter=None, tol=None, verbosity=None)")]
+            fn critical_mixed_micelle(
+                &self,
+                surfactants: Vec<usize>,
+                solver: Option<PyDFTSolver>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<Self> {
+                Ok(Self(self.0.clone().critical_mixed_micelle(
+                        &surfactants,
+                        solver.map(|s| s.0).as_ref(),
+                        (max_iter, tol, verbosity).into(),
+                    )?,
+                ))
+            }
+
+            /// Use the converged micelle to trace the branch of micelles with a
+            /// pseudo-arclength continuation in the bulk surfactant mole fraction
+            /// and the excess number of surfactant molecules.
+            ///
+            /// Parameters
+            /// ----------
+            /// surfactant : int
+            ///     The index of the surfactant.
+            /// solvents : [int]
+            ///     The indices of the solvents.
+            /// delta_n_max : float
+            ///     The excess number of surfactant molecules at which the
+            ///     continuation stops.
+            /// step : float
+            ///     The (maximum) arclength of a step.
+            /// solver : DFTSolver, optional
+            ///     The solver used to solve the profiles.
+            /// max_iter : int, optional
+            ///     The maximum number of iterations of the corrector.
+            /// tol: float, optional
+            ///     The tolerance of the corrector.
+            /// verbosity: Verbosity, optional
+            ///     The verbosity of the corrector.
+            ///
+            /// Returns
+            /// -------
+            /// ([MicelleProfile], [int], bool)
+            ///     All converged profiles, the indices of the turning points
+            ///     and whether delta_n_max was reached.
+            ///
+            #[pyo3(text_signature = "(surfactant, solvents, delta_n_max, step, solver=None, max_iter=None, tol=None, verbosity=None)")]
+            fn trace_branch(
+                &self,
+                surfactant: usize,
+                solvents: Vec<usize>,
+                delta_n_max: f64,
+                step: f64,
+                solver: Option<PyDFTSolver>,
+                max_iter: Option<usize>,
+                tol: Option<f64>,
+                verbosity: Option<Verbosity>,
+            ) -> PyResult<(Vec<Self>, Vec<usize>, bool)> {
+                let branch = self.0.clone().trace_branch(
+                    surfactant,
+                    &solvents,
+                    delta_n_max,
+                    step,
+                    solver.map(|s| s.0).as_ref(),
+                    (max_iter, tol, verbosity).into(),
+                )?;
+                Ok((
+                    branch.profiles.into_iter().map(Self).collect(),
+                    branch.turning_points,
+                    branch.completed,
+                ))
+            }
+
+            /// Scan the size of micelles of the given surfactant and calculate the
+            /// work of formation and the equilibrium size distribution.
+            ///
+            /// Parameters
+            /// ----------
+            /// surfactant : int
+            ///     The index of the surfactant.
+            /// solvents : [int]
+            ///     The indices of the solvents.
+            /// delta_n_surfactant : [float]
+            ///     The excess numbers of surfactant molecules of all aggregates.
+            /// solver : DFTSolver, optional
+            ///     The solver used to solve the profiles.
+            ///
+            /// Returns
+            /// -------
+            /// MicelleSizeDistribution
+            ///
+            #[pyo3(text_signature = "(surfactant, solvents, delta_n_surfactant, solver=None)")]
+            fn size_distribution(
+                &self,
+                surfactant: usize,
+                solvents: Vec<usize>,
+                delta_n_surfactant: Vec<f64>,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<PyMicelleSizeDistribution> {
+                Ok(PyMicelleSizeDistribution(self.0.size_distribution(
+                    surfactant,
+                    &solvents,
+                    &delta_n_surfactant,
+                    solver.map(|s| s.0).as_ref(),
+                )?))
+            }
+        }
+
+        /// Work of formation and equilibrium size distribution of the micelles
+        /// of a surfactant.
+        #[pyclass(name = "MicelleSizeDistribution")]
+        #[derive(Clone)]
+        pub struct PyMicelleSizeDistribution(MicelleSizeDistribution);
+
+        #[pymethods]
+        impl PyMicelleSizeDistribution {
+            /// Reduced temperature.
+            #[getter]
+            fn get_temperature(&self) -> f64 {
+                self.0.temperature
+            }
+
+            /// Mole fraction of the surfactant in the bulk phase.
+            #[getter]
+            fn get_bulk_mole_fraction(&self) -> f64 {
+                self.0.bulk_mole_fraction
+            }
+
+            /// Aggregation numbers.
+            #[getter]
+            fn get_aggregation_number<'py>(&self, py: Python<'py>) -> &'py numpy::PyArray1<f64> {
+                numpy::PyArray1::from_slice(py, &self.0.aggregation_number)
+            }
+
+            /// Reduced work of formation.
+            #[getter]
+            fn get_work_of_formation<'py>(&self, py: Python<'py>) -> &'py numpy::PyArray1<f64> {
+                numpy::PyArray1::from_slice(py, &self.0.work_of_formation)
+            }
+
+            /// Mole fractions of the aggregates in the bulk phase.
+            #[getter]
+            fn get_mole_fraction<'py>(&self, py: Python<'py>) -> &'py numpy::PyArray1<f64> {
+                numpy::PyArray1::from_slice(py, &self.0.mole_fraction)
+            }
+
+            /// Number-averaged aggregation number.
+            #[getter]
+            fn get_mean_aggregation_number(&self) -> f64 {
+                self.0.mean_aggregation_number
+            }
+
+            /// Critical micelle concentration.
+            #[getter]
+            fn get_critical_micelle_concentration(&self) -> f64 {
+                self.0.critical_micelle_concentration
+            }
+
+            /// Write the size distribution to a JSON file.
+            ///
+            /// Parameters
+            /// ----------
+            /// path : str
+            ///     The path of the file.
+            #[pyo3(text_signature = "($self, path)")]
+            fn to_json(&self, path: &str) -> PyResult<()> {
+                Ok(self.0.to_json(path)?)
+            }
         }
 
         pub fn parse_micelle_initialization(
@@ -194,13 +540,54 @@ macro_rules! impl_micelle_profile {
                 Ok(MicelleInitialization::ExternalPotential(peak, width))
             } else if let Ok(density) = initialization.extract::<PySIArray2>() {
                 Ok(MicelleInitialization::Density(density.into()))
+            } else if let Ok(potential) = initialization.extract::<numpy::PyReadonlyArray2<f64>>() {
+                Ok(MicelleInitialization::ExternalPotentialArray(
+                    potential.as_array().to_owned(),
+                ))
+            } else if let Ok(potentials) = initialization.extract::<Vec<&PyAny>>() {
+                Ok(MicelleInitialization::SegmentPotentials(
+                    potentials
+                        .into_iter()
+                        .map(parse_potential_shape)
+                        .collect::<PyResult<_>>()?,
+                ))
             } else {
                 Err(PyErr::new::<PyValueError, _>(format!(
-                    "`initialization` must be (peak, width) or an SIArray2 containing the initial densities."
+                    "`initialization` must be (peak, width), a list of segment potentials, a numpy array containing the external potential or an SIArray2 containing the initial densities."
                 )))
             }
         }
 
+        fn parse_potential_shape(potential: &PyAny) -> PyResult<(usize, PotentialShape)> {
+            if let Ok((segment, shape, a, b)) = potential.extract::<(usize, &str, f64, f64)>() {
+                match shape {
+                    "gaussian" => {
+                        return Ok((segment, PotentialShape::Gaussian { peak: a, width: b }))
+                    }
+                    "step" => {
+                        return Ok((segment, PotentialShape::Step { height: a, radius: b }))
+                    }
+                    _ => (),
+                }
+            } else if let Ok((segment, shape, height, radius, width)) =
+                potential.extract::<(usize, &str, f64, f64, f64)>()
+            {
+                if shape == "tanh" {
+                    return Ok((
+                        segment,
+                        PotentialShape::Tanh {
+                            height,
+                            radius,
+                            width,
+                        },
+                    ));
+                }
+            }
+            Err(PyErr::new::<PyValueError, _>(format!(
+                "segment potentials must be (segment, 'gaussian', peak, width), (segment, 'step', height, radius) or (segment, 'tanh', height, radius, width)."
+            )))
+        }
+
         pub fn parse_micelle_specification(
             specification: Option<&PyAny>,
         ) -> PyResult<MicelleSpecification<SIUnit>> {
@@ -212,9 +599,18 @@ macro_rules! impl_micelle_profile {
                             delta_n_surfactant,
                             pressure: pressure.into(),
                         })
+                    } else if let Ok((surfactants, delta_n_surfactants, solvents, pressure)) =
+                        specification.extract::<(Vec<usize>, Vec<f64>, Vec<usize>, PySINumber)>()
+                    {
+                        Ok(MicelleSpecification::MixedSize {
+                            surfactants,
+                            delta_n_surfactants,
+                            solvents,
+                            pressure: pressure.into(),
+                        })
                     } else {
                         Err(PyErr::new::<PyValueError, _>(format!(
-                            "`specification` must be (delta_n_surfactant, pressure) or None."
+                            "`specification` must be (delta_n_surfactant, pressure), (surfactants, delta_n_surfactants, solvents, pressure) or None."
                         )))
                     }
                 }
@@ -1,22 +1,66 @@
 use crate::dft::GcPcSaftFunctionalParameters;
-use crate::eos::GcPcSaftEosParameters;
-use crate::record::GcPcSaftRecord;
+use crate::eos::{GcPcSaftEosParameters, Permittivity};
+use crate::record::{
+    AssociationRecord, AssociationSite, GcPcSaftBinaryRecord, GcPcSaftRecord, SiteInteraction,
+};
 use feos_core::joback::JobackRecord;
 use feos_core::parameter::{BinaryRecord, IdentifierOption, ParameterError, SegmentRecord};
 use feos_core::python::joback::PyJobackRecord;
 use feos_core::python::parameter::{PyBinarySegmentRecord, PyChemicalRecord};
-use feos_core::{impl_json_handling, impl_parameter_from_segments, impl_segment_record};
+use feos_core::{impl_json_handling, impl_segment_record};
 use numpy::{PyArray2, ToPyArray};
 use pyo3::prelude::*;
 use std::convert::TryFrom;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[cfg(feature = "micelles")]
 mod micelles;
 
-#[pyclass(name = "GcPcSaftRecord", unsendable)]
+/// Association sites and site-site interactions of a segment.
+///
+/// Parameters
+/// ----------
+/// sites : [(str, float)]
+///     The label and the number of sites of every site type.
+/// interactions : [(str, str, float, float)], optional
+///     The labels of two site types together with their association
+///     volume and association energy (in units of Kelvin).
+#[pyclass(name = "AssociationRecord")]
+#[pyo3(text_signature = "(sites, interactions=None)")]
+#[derive(Clone)]
+pub struct PyAssociationRecord(AssociationRecord);
+
+#[pymethods]
+impl PyAssociationRecord {
+    #[new]
+    fn new(
+        sites: Vec<(String, f64)>,
+        interactions: Option<Vec<(String, String, f64, f64)>>,
+    ) -> Self {
+        Self(AssociationRecord::new(
+            sites
+                .into_iter()
+                .map(|(id, n)| AssociationSite::new(id, n))
+                .collect(),
+            interactions.map_or_else(Vec::new, |interactions| {
+                interactions
+                    .into_iter()
+                    .map(|(id1, id2, kappa_ab, epsilon_k_ab)| {
+                        SiteInteraction::new(id1, id2, kappa_ab, epsilon_k_ab)
+                    })
+                    .collect()
+            }),
+        ))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
+
+#[pyclass(name = "GcPcSaftRecord")]
 #[pyo3(
-    text_signature = "(m, sigma, epsilon_k, mu=None, q=None, kappa_ab=None, epsilon_k_ab=None, na=None, nb=None)"
+    text_signature = "(m, sigma, epsilon_k, mu=None, q=None, kappa_ab=None, epsilon_k_ab=None, na=None, nb=None, psi_dft=None, association_record=None, viscosity=None, diffusion=None, thermal_conductivity=None, z=None)"
 )]
 #[derive(Clone)]
 pub struct PyGcPcSaftRecord(GcPcSaftRecord);
@@ -29,23 +73,37 @@ impl PyGcPcSaftRecord {
         sigma: f64,
         epsilon_k: f64,
         mu: Option<f64>,
+        q: Option<f64>,
         kappa_ab: Option<f64>,
         epsilon_k_ab: Option<f64>,
         na: Option<f64>,
         nb: Option<f64>,
         psi_dft: Option<f64>,
+        association_record: Option<PyAssociationRecord>,
+        viscosity: Option<[f64; 4]>,
+        diffusion: Option<[f64; 5]>,
+        thermal_conductivity: Option<[f64; 4]>,
+        z: Option<f64>,
     ) -> Self {
-        Self(GcPcSaftRecord::new(
-            m,
-            sigma,
-            epsilon_k,
-            mu,
-            kappa_ab,
-            epsilon_k_ab,
-            na,
-            nb,
-            psi_dft,
-        ))
+        Self(GcPcSaftRecord {
+            q,
+            association_record: association_record.map(|r| r.0),
+            viscosity,
+            diffusion,
+            thermal_conductivity,
+            z,
+            ..GcPcSaftRecord::new(
+                m,
+                sigma,
+                epsilon_k,
+                mu,
+                kappa_ab,
+                epsilon_k_ab,
+                na,
+                nb,
+                psi_dft,
+            )
+        })
     }
 
     fn __repr__(&self) -> PyResult<String> {
@@ -62,17 +120,264 @@ impl_segment_record!(
     PyJobackRecord
 );
 
-#[pyclass(name = "GcPcSaftEosParameters", unsendable)]
+#[pyclass(name = "GcPcSaftBinaryRecord")]
+#[pyo3(
+    text_signature = "(k_ij=0.0, k_ij_t=0.0, k_ij_ln_t=0.0, l_ij=0.0, kappa_ab=None, epsilon_k_ab=None)"
+)]
+#[derive(Clone)]
+pub struct PyGcPcSaftBinaryRecord(GcPcSaftBinaryRecord);
+
+#[pymethods]
+impl PyGcPcSaftBinaryRecord {
+    #[new]
+    #[args(k_ij = "0.0", k_ij_t = "0.0", k_ij_ln_t = "0.0", l_ij = "0.0")]
+    fn new(
+        k_ij: f64,
+        k_ij_t: f64,
+        k_ij_ln_t: f64,
+        l_ij: f64,
+        kappa_ab: Option<f64>,
+        epsilon_k_ab: Option<f64>,
+    ) -> Self {
+        Self(GcPcSaftBinaryRecord::new(
+            k_ij,
+            k_ij_t,
+            k_ij_ln_t,
+            l_ij,
+            kappa_ab,
+            epsilon_k_ab,
+        ))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(self.0.to_string())
+    }
+}
+
+impl_json_handling!(PyGcPcSaftBinaryRecord);
+
+/// Binary segment-segment parameters with a temperature dependent $k_{ij}$,
+/// an $l_{ij}$ and optional cross-association parameters.
+#[pyclass(name = "GcPcSaftBinarySegmentRecord")]
+#[pyo3(text_signature = "(id1, id2, model_record)")]
+#[derive(Clone)]
+pub struct PyGcPcSaftBinarySegmentRecord(BinaryRecord<String, GcPcSaftBinaryRecord>);
+
+#[pymethods]
+impl PyGcPcSaftBinarySegmentRecord {
+    #[new]
+    fn new(id1: String, id2: String, model_record: PyGcPcSaftBinaryRecord) -> Self {
+        Self(BinaryRecord::new(id1, id2, model_record.0))
+    }
+
+    #[getter]
+    fn get_id1(&self) -> String {
+        self.0.id1.clone()
+    }
+
+    #[getter]
+    fn get_id2(&self) -> String {
+        self.0.id2.clone()
+    }
+
+    #[getter]
+    fn get_model_record(&self) -> PyGcPcSaftBinaryRecord {
+        PyGcPcSaftBinaryRecord(self.0.model_record)
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "GcPcSaftBinarySegmentRecord(id1={}, id2={}, model_record={})",
+            self.0.id1, self.0.id2, self.0.model_record
+        ))
+    }
+}
+
+impl_json_handling!(PyGcPcSaftBinarySegmentRecord);
+
+/// Extract binary segment records that are either `BinarySegmentRecord`s
+/// with a constant $k_{ij}$ or `GcPcSaftBinarySegmentRecord`s.
+fn extract_binary_segment_records(
+    records: Vec<&PyAny>,
+) -> PyResult<Vec<BinaryRecord<String, GcPcSaftBinaryRecord>>> {
+    records
+        .into_iter()
+        .map(|r| {
+            if let Ok(r) = r.extract::<PyGcPcSaftBinarySegmentRecord>() {
+                Ok(r.0)
+            } else {
+                let r = r.extract::<PyBinarySegmentRecord>()?;
+                Ok(BinaryRecord::new(r.0.id1, r.0.id2, r.0.model_record.into()))
+            }
+        })
+        .collect()
+}
+
+/// Same as `feos_core::impl_parameter_from_segments` but for parameters
+/// that are shared between threads using an `Arc`.
+macro_rules! impl_shared_parameter_from_segments {
+    ($parameter:ty, $py_parameter:ty) => {
+        #[pymethods]
+        impl $py_parameter {
+            /// Creates parameters from segment records.
+            ///
+            /// Parameters
+            /// ----------
+            /// chemical_records : [ChemicalRecord]
+            ///     A list of pure component parameters.
+            /// segment_records : [SegmentRecord]
+            ///     A list of records containing the parameters of
+            ///     all individual segments.
+            /// binary_segment_records : [BinarySegmentRecord | GcPcSaftBinarySegmentRecord], optional
+            ///     A list of binary segment-segment parameters, either with a
+            ///     constant k_ij or with the full gc-PC-SAFT binary record.
+            #[staticmethod]
+            #[pyo3(text_signature = "(chemical_records, segment_records, binary_segment_records=None)")]
+            fn from_segments(
+                chemical_records: Vec<PyChemicalRecord>,
+                segment_records: Vec<PySegmentRecord>,
+                binary_segment_records: Option<Vec<&PyAny>>,
+            ) -> PyResult<Self> {
+                Ok(Self(Arc::new(<$parameter>::from_segments(
+                    chemical_records.into_iter().map(|cr| cr.0).collect(),
+                    segment_records.into_iter().map(|sr| sr.0).collect(),
+                    binary_segment_records
+                        .map(extract_binary_segment_records)
+                        .transpose()?,
+                )?)))
+            }
+
+            /// Creates parameters using segments from json file.
+            ///
+            /// Parameters
+            /// ----------
+            /// substances : List[str]
+            ///     The substances to search.
+            /// pure_path : str
+            ///     Path to file containing pure substance parameters.
+            /// segments_path : str
+            ///     Path to file containing segment parameters.
+            /// binary_path : str, optional
+            ///     Path to file containing binary segment-segment parameters.
+            /// search_option : str, optional, defaults to "Name"
+            ///     Identifier that is used to search substance.
+            ///     One of 'Name', 'Cas', 'Inchi', 'IupacName', 'Formula', 'Smiles'
+            #[staticmethod]
+            #[pyo3(
+                text_signature = "(substances, pure_path, segments_path, binary_path=None, search_option='Name')"
+            )]
+            fn from_json_segments(
+                substances: Vec<&str>,
+                pure_path: String,
+                segments_path: String,
+                binary_path: Option<String>,
+                search_option: Option<&str>,
+            ) -> Result<Self, ParameterError> {
+                let io = match search_option {
+                    Some(o) => IdentifierOption::try_from(o)?,
+                    None => IdentifierOption::Name,
+                };
+                Ok(Self(Arc::new(<$parameter>::from_json_segments(
+                    &substances,
+                    pure_path,
+                    segments_path,
+                    binary_path,
+                    io,
+                )?)))
+            }
+        }
+    };
+}
+
+/// Model for the relative permittivity of the solvent used in the
+/// electrostatic contributions.
+#[pyclass(name = "Permittivity")]
+#[derive(Clone)]
+pub struct PyPermittivity(pub Permittivity);
+
+#[pymethods]
+impl PyPermittivity {
+    /// Constant relative permittivity.
+    ///
+    /// Parameters
+    /// ----------
+    /// epsilon_r : float
+    ///     The relative permittivity.
+    ///
+    /// Returns
+    /// -------
+    /// Permittivity
+    #[staticmethod]
+    #[pyo3(text_signature = "(epsilon_r)")]
+    fn constant(epsilon_r: f64) -> Self {
+        Self(Permittivity::Constant(epsilon_r))
+    }
+
+    /// Relative permittivities of all components averaged with the salt-free
+    /// mole fractions.
+    ///
+    /// Parameters
+    /// ----------
+    /// epsilon_r : [float]
+    ///     The relative permittivities of all components.
+    ///
+    /// Returns
+    /// -------
+    /// Permittivity
+    #[staticmethod]
+    #[pyo3(text_signature = "(epsilon_r)")]
+    fn salt_free_mixing(epsilon_r: Vec<f64>) -> Self {
+        Self(Permittivity::SaltFreeMixing(epsilon_r))
+    }
+
+    /// Relative permittivity of the salt-free mixture that decreases
+    /// linearly with the molality of all components with charged segments.
+    ///
+    /// Parameters
+    /// ----------
+    /// epsilon_r : [float]
+    ///     The relative permittivities of all components.
+    /// decrement : float
+    ///     The decrement in kg/mol.
+    ///
+    /// Returns
+    /// -------
+    /// Permittivity
+    #[staticmethod]
+    #[pyo3(text_signature = "(epsilon_r, decrement)")]
+    fn salt_free_mixing_decrement(epsilon_r: Vec<f64>, decrement: f64) -> Self {
+        Self(Permittivity::SaltFreeMixingDecrement {
+            epsilon_r,
+            decrement,
+        })
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self.0))
+    }
+}
+
+#[pyclass(name = "GcPcSaftEosParameters")]
 #[pyo3(
     text_signature = "(pure_records, segmentbinary_records=None, substances=None, search_option='Name')"
 )]
 #[derive(Clone)]
-pub struct PyGcPcSaftEosParameters(pub Rc<GcPcSaftEosParameters>);
+pub struct PyGcPcSaftEosParameters(pub Arc<GcPcSaftEosParameters>);
 
-impl_parameter_from_segments!(GcPcSaftEosParameters, PyGcPcSaftEosParameters);
+impl_shared_parameter_from_segments!(GcPcSaftEosParameters, PyGcPcSaftEosParameters);
 
 #[pymethods]
 impl PyGcPcSaftEosParameters {
+    /// Parameter set of the homosegmented gc-PC-SAFT model.
+    ///
+    /// Returns
+    /// -------
+    /// GcPcSaftEosParameters
+    #[pyo3(text_signature = "($self)")]
+    fn homosegmented(&self) -> Self {
+        Self(Arc::new(self.0.homosegmented()))
+    }
+
     fn _repr_markdown_(&self) -> String {
         self.0.to_markdown()
     }
@@ -82,14 +387,14 @@ impl PyGcPcSaftEosParameters {
     }
 }
 
-#[pyclass(name = "GcPcSaftFunctionalParameters", unsendable)]
+#[pyclass(name = "GcPcSaftFunctionalParameters")]
 #[pyo3(
     text_signature = "(pure_records, segmentbinary_records=None, substances=None, search_option='Name')"
 )]
 #[derive(Clone)]
-pub struct PyGcPcSaftFunctionalParameters(pub Rc<GcPcSaftFunctionalParameters>);
+pub struct PyGcPcSaftFunctionalParameters(pub Arc<GcPcSaftFunctionalParameters>);
 
-impl_parameter_from_segments!(GcPcSaftFunctionalParameters, PyGcPcSaftFunctionalParameters);
+impl_shared_parameter_from_segments!(GcPcSaftFunctionalParameters, PyGcPcSaftFunctionalParameters);
 
 #[pymethods]
 impl PyGcPcSaftFunctionalParameters {
@@ -13,6 +13,10 @@ pub struct GcPcSaftRecord {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mu: Option<f64>,
+    /// Quadrupole moment in units of Debye * Angstrom
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<f64>,
     /// association volume parameter
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -22,6 +26,10 @@ pub struct GcPcSaftRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub epsilon_k_ab: Option<f64>,
     /// \# of association sites of type A
+    ///
+    /// If `na` or `nb` is given without `kappa_ab` and `epsilon_k_ab`, the segment
+    /// does not self-associate, but its sites can cross-associate (induced association).
+    /// If only one of `na` and `nb` is non-zero, the sites associate with each other.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub na: Option<f64>,
@@ -33,9 +41,32 @@ pub struct GcPcSaftRecord {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub psi_dft: Option<f64>,
+    /// association site types and site-site interactions
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub association_record: Option<AssociationRecord>,
+    /// Entropy scaling coefficients for the viscosity
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viscosity: Option<[f64; 4]>,
+    /// Entropy scaling coefficients for the diffusion coefficient
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diffusion: Option<[f64; 5]>,
+    /// Entropy scaling coefficients for the thermal conductivity
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thermal_conductivity: Option<[f64; 4]>,
+    /// Charge in units of the elementary charge
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<f64>,
 }
 
 impl GcPcSaftRecord {
+    /// Create a record without the optional quadrupole moment, association
+    /// records, entropy scaling coefficients and charge. These can be set
+    /// using the struct update syntax with `..Default::default()`.
     pub fn new(
         m: f64,
         sigma: f64,
@@ -57,6 +88,53 @@ impl GcPcSaftRecord {
             na,
             nb,
             psi_dft,
+            ..Default::default()
+        }
+    }
+
+    /// All association sites of the segment, including the site types
+    /// specified by `kappa_ab`, `epsilon_k_ab`, `na` and `nb`.
+    ///
+    /// Sites of type A associate with sites of type B. If only one of the
+    /// two site types is present, its sites associate with each other.
+    pub fn association_record(&self) -> Option<AssociationRecord> {
+        let mut record = AssociationRecord::default();
+        if let (Some(kappa_ab), Some(epsilon_k_ab)) = (self.kappa_ab, self.epsilon_k_ab) {
+            let na = self.na.unwrap_or(1.0);
+            let nb = self.nb.unwrap_or(1.0);
+            let (id1, id2) = if nb == 0.0 {
+                ("A", "A")
+            } else if na == 0.0 {
+                ("B", "B")
+            } else {
+                ("A", "B")
+            };
+            record.add_sites("A", na);
+            record.add_sites("B", nb);
+            record.interactions.push(SiteInteraction::new(
+                id1.into(),
+                id2.into(),
+                kappa_ab,
+                epsilon_k_ab,
+            ));
+        } else {
+            // induced association: no self-association, but the sites
+            // can cross-associate with other associating segments
+            record.add_sites("A", self.na.unwrap_or(0.0));
+            record.add_sites("B", self.nb.unwrap_or(0.0));
+        }
+        if let Some(association_record) = &self.association_record {
+            for site in &association_record.sites {
+                record.add_sites(&site.id, site.n);
+            }
+            record
+                .interactions
+                .extend(association_record.interactions.iter().cloned());
+        }
+        if record.sites.is_empty() {
+            None
+        } else {
+            Some(record)
         }
     }
 }
@@ -69,6 +147,390 @@ impl std::fmt::Display for GcPcSaftRecord {
         if let Some(n) = &self.mu {
             write!(f, ", mu={}", n)?;
         }
+        if let Some(n) = &self.q {
+            write!(f, ", q={}", n)?;
+        }
+        if let Some(n) = &self.kappa_ab {
+            write!(f, ", kappa_ab={}", n)?;
+        }
+        if let Some(n) = &self.epsilon_k_ab {
+            write!(f, ", epsilon_k_ab={}", n)?;
+        }
+        if let Some(n) = &self.na {
+            write!(f, ", na={}", n)?;
+        }
+        if let Some(n) = &self.nb {
+            write!(f, ", nb={}", n)?;
+        }
+        if let Some(n) = &self.association_record {
+            write!(f, ", association_record={}", n)?;
+        }
+        if let Some(n) = &self.viscosity {
+            write!(f, ", viscosity={:?}", n)?;
+        }
+        if let Some(n) = &self.diffusion {
+            write!(f, ", diffusion={:?}", n)?;
+        }
+        if let Some(n) = &self.thermal_conductivity {
+            write!(f, ", thermal_conductivity={:?}", n)?;
+        }
+        if let Some(n) = &self.z {
+            write!(f, ", z={}", n)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Association sites and site-site interactions of a segment.
+///
+/// Every site type is identified by a label. Two site types associate if
+/// parameters are given for the pair, either by the record of one of the
+/// segments or by a binary segment record. Pairs of equal labels describe
+/// sites that associate with each other, e.g., the 4C water model, which
+/// can be combined with 1A carboxylic acid and 3B amine groups in one
+/// mixture.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssociationRecord {
+    /// association site types
+    pub sites: Vec<AssociationSite>,
+    /// association parameters of pairs of site types
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub interactions: Vec<SiteInteraction>,
+}
+
+impl AssociationRecord {
+    pub fn new(sites: Vec<AssociationSite>, interactions: Vec<SiteInteraction>) -> Self {
+        Self {
+            sites,
+            interactions,
+        }
+    }
+
+    /// The interaction between two site types, if it is specified.
+    pub fn interaction(&self, id1: &str, id2: &str) -> Option<&SiteInteraction> {
+        self.interactions
+            .iter()
+            .find(|i| i.id1 == id1 && i.id2 == id2 || i.id1 == id2 && i.id2 == id1)
+    }
+
+    fn add_sites(&mut self, id: &str, n: f64) {
+        if n == 0.0 {
+            return;
+        }
+        match self.sites.iter_mut().find(|s| s.id == id) {
+            Some(site) => site.n += n,
+            None => self.sites.push(AssociationSite::new(id.into(), n)),
+        }
+    }
+}
+
+impl std::fmt::Display for AssociationRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AssociationRecord(sites=[")?;
+        for (i, site) in self.sites.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", site)?;
+        }
+        write!(f, "]")?;
+        if !self.interactions.is_empty() {
+            write!(f, ", interactions=[")?;
+            for (i, interaction) in self.interactions.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", interaction)?;
+            }
+            write!(f, "]")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A type of association sites on a segment.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssociationSite {
+    /// label of the site type
+    pub id: String,
+    /// \# of sites of this type
+    pub n: f64,
+}
+
+impl AssociationSite {
+    pub fn new(id: String, n: f64) -> Self {
+        Self { id, n }
+    }
+}
+
+impl std::fmt::Display for AssociationSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AssociationSite(id={}, n={})", self.id, self.n)
+    }
+}
+
+/// Association parameters of a pair of site types.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SiteInteraction {
+    /// label of the first site type
+    pub id1: String,
+    /// label of the second site type
+    pub id2: String,
+    /// association volume parameter
+    pub kappa_ab: f64,
+    /// association energy parameter in units of Kelvin
+    pub epsilon_k_ab: f64,
+}
+
+impl SiteInteraction {
+    pub fn new(id1: String, id2: String, kappa_ab: f64, epsilon_k_ab: f64) -> Self {
+        Self {
+            id1,
+            id2,
+            kappa_ab,
+            epsilon_k_ab,
+        }
+    }
+}
+
+impl std::fmt::Display for SiteInteraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SiteInteraction(id1={}, id2={}, kappa_ab={}, epsilon_k_ab={})",
+            self.id1, self.id2, self.kappa_ab, self.epsilon_k_ab
+        )
+    }
+}
+
+/// gc-PC-SAFT binary segment-segment parameters.
+///
+/// The binary interaction parameter of the dispersion energy can be
+/// temperature dependent according to
+/// $k_{ij}(T)=k_{ij}+\frac{k_{ij,T}}{T}+k_{ij,\ln T}\ln T$.
+/// Optionally, the association parameters of a pair of associating segments
+/// can be specified, replacing the combining rules for the cross-association.
+/// For backwards compatibility, a single number is deserialized as a
+/// constant $k_{ij}$.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(from = "GcPcSaftBinaryRecordSerde")]
+pub struct GcPcSaftBinaryRecord {
+    /// Constant part of the binary dispersion interaction parameter
+    pub k_ij: f64,
+    /// Coefficient of the $1/T$ term of the binary dispersion interaction parameter in units of Kelvin
+    pub k_ij_t: f64,
+    /// Coefficient of the $\ln T$ term of the binary dispersion interaction parameter
+    pub k_ij_ln_t: f64,
+    /// Binary interaction parameter for the segment diameter
+    pub l_ij: f64,
+    /// Cross-association volume parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kappa_ab: Option<f64>,
+    /// Cross-association energy parameter in units of Kelvin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epsilon_k_ab: Option<f64>,
+}
+
+impl GcPcSaftBinaryRecord {
+    pub fn new(
+        k_ij: f64,
+        k_ij_t: f64,
+        k_ij_ln_t: f64,
+        l_ij: f64,
+        kappa_ab: Option<f64>,
+        epsilon_k_ab: Option<f64>,
+    ) -> Self {
+        Self {
+            k_ij,
+            k_ij_t,
+            k_ij_ln_t,
+            l_ij,
+            kappa_ab,
+            epsilon_k_ab,
+        }
+    }
+}
+
+impl From<f64> for GcPcSaftBinaryRecord {
+    fn from(k_ij: f64) -> Self {
+        Self {
+            k_ij,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GcPcSaftBinaryRecordSerde {
+    Constant(f64),
+    Record {
+        #[serde(default)]
+        k_ij: f64,
+        #[serde(default)]
+        k_ij_t: f64,
+        #[serde(default)]
+        k_ij_ln_t: f64,
+        #[serde(default)]
+        l_ij: f64,
+        #[serde(default)]
+        kappa_ab: Option<f64>,
+        #[serde(default)]
+        epsilon_k_ab: Option<f64>,
+    },
+}
+
+impl From<GcPcSaftBinaryRecordSerde> for GcPcSaftBinaryRecord {
+    fn from(record: GcPcSaftBinaryRecordSerde) -> Self {
+        match record {
+            GcPcSaftBinaryRecordSerde::Constant(k_ij) => k_ij.into(),
+            GcPcSaftBinaryRecordSerde::Record {
+                k_ij,
+                k_ij_t,
+                k_ij_ln_t,
+                l_ij,
+                kappa_ab,
+                epsilon_k_ab,
+            } => Self::new(k_ij, k_ij_t, k_ij_ln_t, l_ij, kappa_ab, epsilon_k_ab),
+        }
+    }
+}
+
+impl std::fmt::Display for GcPcSaftBinaryRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GcPcSaftBinaryRecord(k_ij={}", self.k_ij)?;
+        write!(f, ", k_ij_t={}", self.k_ij_t)?;
+        write!(f, ", k_ij_ln_t={}", self.k_ij_ln_t)?;
+        write!(f, ", l_ij={}", self.l_ij)?;
+        if let Some(n) = &self.kappa_ab {
+            write!(f, ", kappa_ab={}", n)?;
+        }
+        if let Some(n) = &self.epsilon_k_ab {
+            write!(f, ", epsilon_k_ab={}", n)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Group contribution to the ideal gas heat capacity
+/// $c_p^\mathrm{ig}=a+bT+cT^2+dT^3+eT^4$ in units of J/(mol K) with $T$ in units of Kelvin.
+///
+/// The identifier does not need to correspond to a gc-PC-SAFT segment, so that
+/// ideal gas groups (e.g. second-order groups) can be chosen independently.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HeatCapacityRecord {
+    /// Identifier of the group
+    pub identifier: String,
+    pub a: f64,
+    #[serde(default)]
+    pub b: f64,
+    #[serde(default)]
+    pub c: f64,
+    #[serde(default)]
+    pub d: f64,
+    #[serde(default)]
+    pub e: f64,
+}
+
+impl HeatCapacityRecord {
+    pub fn new(identifier: String, a: f64, b: f64, c: f64, d: f64, e: f64) -> Self {
+        Self {
+            identifier,
+            a,
+            b,
+            c,
+            d,
+            e,
+        }
+    }
+}
+
+impl std::fmt::Display for HeatCapacityRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "HeatCapacityRecord(identifier={}, a={}, b={}, c={}, d={}, e={})",
+            self.identifier, self.a, self.b, self.c, self.d, self.e
+        )
+    }
+}
+
+/// Pure-component parameters of the (homosegmented) PC-SAFT equation of state.
+///
+/// The fields agree with the pure records of PC-SAFT implementations that
+/// do not use group contributions.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PcSaftRecord {
+    /// Segment number
+    pub m: f64,
+    /// Segment diameter in units of Angstrom
+    pub sigma: f64,
+    /// Energetic parameter in units of Kelvin
+    pub epsilon_k: f64,
+    /// Dipole moment in units of Debye
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mu: Option<f64>,
+    /// Quadrupole moment in units of Debye * Angstrom
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<f64>,
+    /// association volume parameter
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kappa_ab: Option<f64>,
+    /// association energy parameter
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epsilon_k_ab: Option<f64>,
+    /// \# of association sites of type A
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub na: Option<f64>,
+    /// \# of association sites of type B
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nb: Option<f64>,
+}
+
+impl PcSaftRecord {
+    pub fn new(
+        m: f64,
+        sigma: f64,
+        epsilon_k: f64,
+        mu: Option<f64>,
+        q: Option<f64>,
+        kappa_ab: Option<f64>,
+        epsilon_k_ab: Option<f64>,
+        na: Option<f64>,
+        nb: Option<f64>,
+    ) -> Self {
+        Self {
+            m,
+            sigma,
+            epsilon_k,
+            mu,
+            q,
+            kappa_ab,
+            epsilon_k_ab,
+            na,
+            nb,
+        }
+    }
+}
+
+impl std::fmt::Display for PcSaftRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PcSaftRecord(m={}", self.m)?;
+        write!(f, ", sigma={}", self.sigma)?;
+        write!(f, ", epsilon_k={}", self.epsilon_k)?;
+        if let Some(n) = &self.mu {
+            write!(f, ", mu={}", n)?;
+        }
+        if let Some(n) = &self.q {
+            write!(f, ", q={}", n)?;
+        }
         if let Some(n) = &self.kappa_ab {
             write!(f, ", kappa_ab={}", n)?;
         }
@@ -0,0 +1,387 @@
+//! Second-order group corrections.
+//!
+//! Second-order groups describe proximity effects between segments, e.g.,
+//! a hydroxyl group next to a branched carbon. They are detected in the bond
+//! graph of a chemical record and correct the parameters of the first segment
+//! of every occurrence.
+use crate::record::GcPcSaftRecord;
+use feos_core::joback::JobackRecord;
+use feos_core::parameter::{ChemicalRecord, SegmentRecord};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Second-order group given by a pattern of bonded segments and corrections
+/// to the parameters of the first segment of the pattern.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecondOrderRecord {
+    /// Identifier of the second-order group
+    pub identifier: String,
+    /// Segment identifiers of the pattern
+    pub segments: Vec<String>,
+    /// Bonds between the segments of the pattern
+    #[serde(default)]
+    pub bonds: Vec<[usize; 2]>,
+    /// Correction of the segment shape factor
+    #[serde(default)]
+    pub m: f64,
+    /// Correction of the segment diameter in units of Angstrom
+    #[serde(default)]
+    pub sigma: f64,
+    /// Correction of the energetic parameter in units of Kelvin
+    #[serde(default)]
+    pub epsilon_k: f64,
+    /// Correction of the dipole moment in units of Debye
+    #[serde(default)]
+    pub mu: f64,
+}
+
+impl SecondOrderRecord {
+    pub fn new(
+        identifier: String,
+        segments: Vec<String>,
+        bonds: Vec<[usize; 2]>,
+        m: f64,
+        sigma: f64,
+        epsilon_k: f64,
+        mu: f64,
+    ) -> Self {
+        Self {
+            identifier,
+            segments,
+            bonds,
+            m,
+            sigma,
+            epsilon_k,
+            mu,
+        }
+    }
+
+    /// All distinct occurrences of the pattern in the bond graph given as
+    /// the segment indices of the pattern segments.
+    fn occurrences(&self, segments: &[String], bonds: &[[usize; 2]]) -> Vec<Vec<usize>> {
+        let mut neighbors = vec![HashSet::new(); segments.len()];
+        for &[a, b] in bonds.iter() {
+            if a < segments.len() && b < segments.len() {
+                neighbors[a].insert(b);
+                neighbors[b].insert(a);
+            }
+        }
+        let mut occurrences = Vec::new();
+        let mut found = HashSet::new();
+        self.match_pattern(segments, &neighbors, &mut vec![], &mut |mapping| {
+            // matches that only differ by symmetry of the pattern are counted once
+            let mut key = mapping.to_vec();
+            key[1..].sort_unstable();
+            if found.insert(key) {
+                occurrences.push(mapping.to_vec());
+            }
+        });
+        occurrences
+    }
+
+    fn match_pattern<F: FnMut(&[usize])>(
+        &self,
+        segments: &[String],
+        neighbors: &[HashSet<usize>],
+        mapping: &mut Vec<usize>,
+        found: &mut F,
+    ) {
+        let i = mapping.len();
+        if i == self.segments.len() {
+            found(mapping);
+            return;
+        }
+        for candidate in 0..segments.len() {
+            if segments[candidate] != self.segments[i] || mapping.contains(&candidate) {
+                continue;
+            }
+            let bonded = self.bonds.iter().all(|&[a, b]| {
+                let other = match (a == i, b == i) {
+                    (true, false) => b,
+                    (false, true) => a,
+                    _ => return true,
+                };
+                other > i || neighbors[candidate].contains(&mapping[other])
+            });
+            if bonded {
+                mapping.push(candidate);
+                self.match_pattern(segments, neighbors, mapping, found);
+                mapping.pop();
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SecondOrderRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SecondOrderRecord(identifier={}, segments={:?}, bonds={:?}",
+            self.identifier, self.segments, self.bonds
+        )?;
+        write!(f, ", m={}", self.m)?;
+        write!(f, ", sigma={}", self.sigma)?;
+        write!(f, ", epsilon_k={}", self.epsilon_k)?;
+        write!(f, ", mu={})", self.mu)
+    }
+}
+
+/// Indices of the second-order groups that correct every segment of a
+/// chemical record (including multiple occurrences).
+pub(crate) fn segment_corrections(
+    segments: &[String],
+    bonds: &[[usize; 2]],
+    records: &[SecondOrderRecord],
+) -> Vec<Vec<usize>> {
+    let mut corrections = vec![Vec::new(); segments.len()];
+    for (r, record) in records.iter().enumerate() {
+        for occurrence in record.occurrences(segments, bonds) {
+            corrections[occurrence[0]].push(r);
+        }
+    }
+    corrections
+}
+
+/// Segment record with the parameters corrected by the given second-order groups.
+pub(crate) fn corrected_segment(
+    segment: &SegmentRecord<GcPcSaftRecord, JobackRecord>,
+    corrections: &[usize],
+    records: &[SecondOrderRecord],
+) -> SegmentRecord<GcPcSaftRecord, JobackRecord> {
+    let mut segment = segment.clone();
+    let model_record = &mut segment.model_record;
+    for record in corrections.iter().map(|&r| &records[r]) {
+        model_record.m += record.m;
+        model_record.sigma += record.sigma;
+        model_record.epsilon_k += record.epsilon_k;
+        if record.mu != 0.0 {
+            model_record.mu = Some(model_record.mu.unwrap_or(0.0) + record.mu);
+        }
+    }
+    segment
+}
+
+/// Counts of the segments and bonds of a chemical record.
+///
+/// Segments with the same identifier are only counted together, if they are
+/// corrected by the same second-order groups. Bonds refer to the positions
+/// in the list of segments.
+#[allow(clippy::type_complexity)]
+pub(crate) fn segment_and_bond_count(
+    chemical_record: &ChemicalRecord,
+    records: &[SecondOrderRecord],
+) -> (Vec<(String, Vec<usize>, f64)>, IndexMap<[usize; 2], f64>) {
+    let corrections = chemical_record
+        .segment_and_bond_list()
+        .ok()
+        .filter(|_| !records.is_empty())
+        .map(|(segments, bonds)| {
+            (
+                segments,
+                bonds,
+                segment_corrections(segments, bonds, records),
+            )
+        })
+        .filter(|(_, _, corrections)| corrections.iter().any(|c| !c.is_empty()));
+
+    let mut bond_counts = IndexMap::new();
+    let mut add_bond = |a: usize, b: usize, count: f64| {
+        let indices = if a > b { [b, a] } else { [a, b] };
+        *bond_counts.entry(indices).or_insert(0.0) += count;
+    };
+
+    match corrections {
+        Some((segments, bonds, mut corrections)) => {
+            let mut segment_counts: IndexMap<(String, Vec<usize>), f64> = IndexMap::new();
+            let mut index = Vec::with_capacity(segments.len());
+            for (segment, correction) in segments.iter().zip(corrections.iter_mut()) {
+                correction.sort_unstable();
+                let entry = segment_counts.entry((segment.clone(), correction.clone()));
+                index.push(entry.index());
+                *entry.or_insert(0.0) += 1.0;
+            }
+            for &[a, b] in bonds.iter() {
+                if let (Some(&a), Some(&b)) = (index.get(a), index.get(b)) {
+                    add_bond(a, b, 1.0);
+                }
+            }
+            let segment_counts = segment_counts
+                .into_iter()
+                .map(|((segment, correction), count)| (segment, correction, count))
+                .collect();
+            (segment_counts, bond_counts)
+        }
+        None => {
+            let (segment_counts, bonds) = chemical_record.segment_and_bond_count();
+            let segment_counts: IndexMap<_, _> = segment_counts
+                .iter()
+                .map(|(s, &c)| (s.clone(), c))
+                .collect();
+            for ([s1, s2], &count) in bonds.iter() {
+                if let (Some(a), Some(b)) = (
+                    segment_counts.get_index_of(s1),
+                    segment_counts.get_index_of(s2),
+                ) {
+                    add_bond(a, b, count);
+                }
+            }
+            let segment_counts = segment_counts
+                .into_iter()
+                .map(|(segment, count)| (segment, vec![], count))
+                .collect();
+            (segment_counts, bond_counts)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dft::GcPcSaftFunctionalParameters;
+    use crate::eos::GcPcSaftEosParameters;
+    use approx::assert_relative_eq;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn ids(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn ch3_ch_oh() -> SecondOrderRecord {
+        SecondOrderRecord::new(
+            "CH3-CH(OH)".into(),
+            ids(&[">CH", "CH3", "OH"]),
+            vec![[0, 1], [0, 2]],
+            0.0,
+            0.0,
+            5.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let record = ch3_ch_oh();
+        // 2-propanol: both methyl groups are adjacent to the CH(OH) group
+        let segments = ids(&["CH3", ">CH", "OH", "CH3"]);
+        let bonds = vec![[0, 1], [1, 2], [1, 3]];
+        assert_eq!(record.occurrences(&segments, &bonds).len(), 2);
+        // 2-butanol
+        let segments = ids(&["CH3", ">CH", "OH", "CH2", "CH3"]);
+        let bonds = vec![[0, 1], [1, 2], [1, 3], [3, 4]];
+        assert_eq!(record.occurrences(&segments, &bonds), vec![vec![1, 0, 2]]);
+        // 1-propanol
+        let segments = ids(&["CH3", "CH2", "CH2", "OH"]);
+        let bonds = vec![[0, 1], [1, 2], [2, 3]];
+        assert!(record.occurrences(&segments, &bonds).is_empty());
+
+        // symmetric pattern
+        let record = SecondOrderRecord::new(
+            "(CH3)2CH".into(),
+            ids(&[">CH", "CH3", "CH3"]),
+            vec![[0, 1], [0, 2]],
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        );
+        let segments = ids(&["CH3", ">CH", "CH3", "CH3"]);
+        let bonds = vec![[0, 1], [1, 2], [1, 3]];
+        assert_eq!(record.occurrences(&segments, &bonds).len(), 3);
+    }
+
+    #[test]
+    fn test_segment_and_bond_count() {
+        let record = ChemicalRecord::new(
+            feos_core::parameter::Identifier::new("", None, None, None, None, None),
+            ids(&["CH3", ">CH", "OH", "CH2", "CH3"]),
+            None,
+        );
+        let (segments, bonds) = segment_and_bond_count(&record, &[ch3_ch_oh()]);
+        assert_eq!(
+            segments,
+            vec![
+                ("CH3".into(), vec![], 2.0),
+                (">CH".into(), vec![0], 1.0),
+                ("OH".into(), vec![], 1.0),
+                ("CH2".into(), vec![], 1.0),
+            ]
+        );
+        assert_eq!(bonds.len(), 4);
+        assert_eq!(bonds.values().sum::<f64>(), 4.0);
+        assert_eq!(bonds[&[0, 1]], 1.0);
+        assert_eq!(bonds[&[0, 3]], 1.0);
+
+        // without second-order groups, segments are only counted by identifier
+        let (segments, bonds) = segment_and_bond_count(&record, &[]);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(bonds.values().sum::<f64>(), 4.0);
+    }
+
+    #[test]
+    fn test_isomers() {
+        let reader = BufReader::new(File::open("parameters/gc_substances.json").unwrap());
+        let chemical_records: Vec<ChemicalRecord> = serde_json::from_reader(reader).unwrap();
+        let chemical_records: Vec<_> = ["1-propanol", "2-propanol"]
+            .iter()
+            .map(|&name| {
+                chemical_records
+                    .iter()
+                    .find(|r| r.identifier().name.as_deref() == Some(name))
+                    .unwrap()
+                    .clone()
+            })
+            .collect();
+        let reader = BufReader::new(File::open("parameters/sauer2014_hetero.json").unwrap());
+        let segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> =
+            serde_json::from_reader(reader).unwrap();
+
+        let first_order = GcPcSaftEosParameters::from_segments(
+            chemical_records.clone(),
+            segment_records.clone(),
+            None,
+        )
+        .unwrap();
+        let eos = GcPcSaftEosParameters::from_segments_with_second_order(
+            chemical_records.clone(),
+            segment_records.clone(),
+            None,
+            vec![ch3_ch_oh()],
+        )
+        .unwrap();
+        let dft = GcPcSaftFunctionalParameters::from_segments_with_second_order(
+            chemical_records,
+            segment_records,
+            None,
+            vec![ch3_ch_oh()],
+        )
+        .unwrap();
+
+        // 1-propanol is not affected
+        assert_relative_eq!(eos.m_mix[0], first_order.m_mix[0]);
+        assert_relative_eq!(eos.epsilon_k_mix[0], first_order.epsilon_k_mix[0]);
+
+        // the >CH group of 2-propanol is corrected once for every methyl group
+        let m_ch = 0.52235;
+        assert_relative_eq!(eos.m_mix[1], first_order.m_mix[1]);
+        assert_relative_eq!(
+            eos.epsilon_k_mix[1],
+            first_order.epsilon_k_mix[1] + m_ch * 2.0 * 5.0 / eos.m_mix[1],
+            max_relative = 1e-12
+        );
+
+        // the functional uses the same corrections
+        for i in 0..2 {
+            let segments = dft.component_index.iter().map(|&c| c == i);
+            let (m, m_epsilon_k) = segments
+                .zip(dft.m.iter().zip(dft.epsilon_k.iter()))
+                .filter(|(s, _)| *s)
+                .fold((0.0, 0.0), |(m, me), (_, (&mi, &ei))| {
+                    (m + mi, me + mi * ei)
+                });
+            assert_relative_eq!(m, eos.m_mix[i], max_relative = 1e-12);
+            assert_relative_eq!(m_epsilon_k / m, eos.epsilon_k_mix[i], max_relative = 1e-12);
+        }
+    }
+}
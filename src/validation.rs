@@ -0,0 +1,302 @@
+//! Validation of the structural information in chemical records.
+//!
+//! The equation of state only uses bonds between existing segments and
+//! silently ignores all other bonds, whereas the functional requires a
+//! consistent bond graph. [validate_chemical_records] reports all problems
+//! per component before parameters are constructed.
+use crate::fragmentation::GroupDefinition;
+use feos_core::parameter::{ChemicalRecord, Identifier, ParameterError, SegmentRecord};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Problem in the chemical record of a single component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChemicalRecordIssue {
+    /// The segment is not contained in the segment records.
+    MissingSegment(String),
+    /// The bond refers to a segment index that does not exist or bonds a segment to itself.
+    InvalidBond([usize; 2]),
+    /// The bond graph consists of several unconnected parts (given as lists of segment indices).
+    Disconnected(Vec<Vec<usize>>),
+    /// The number of bonds of a segment is not compatible with the group definitions.
+    Valence {
+        segment: usize,
+        identifier: String,
+        bonds: usize,
+        expected: Vec<usize>,
+    },
+}
+
+impl fmt::Display for ChemicalRecordIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSegment(identifier) => {
+                write!(
+                    f,
+                    "segment '{}' not found in the segment records",
+                    identifier
+                )
+            }
+            Self::InvalidBond(bond) => write!(f, "invalid bond {:?}", bond),
+            Self::Disconnected(parts) => write!(f, "disconnected bond graph {:?}", parts),
+            Self::Valence {
+                segment,
+                identifier,
+                bonds,
+                expected,
+            } => write!(
+                f,
+                "segment {} ({}) has {} bond(s), expected {:?}",
+                segment, identifier, bonds, expected
+            ),
+        }
+    }
+}
+
+/// Result of the validation of a list of chemical records.
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    /// Identifiers and issues of every component in the order of the chemical records
+    pub components: Vec<(Identifier, Vec<ChemicalRecordIssue>)>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no issues were found for any component.
+    pub fn is_valid(&self) -> bool {
+        self.components.iter().all(|(_, issues)| issues.is_empty())
+    }
+
+    /// Convert the report into an error if any issues were found.
+    pub fn into_result(self) -> Result<(), ParameterError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(ParameterError::IncompatibleParameters(self.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (identifier, issues)) in self.components.iter().enumerate() {
+            if issues.is_empty() {
+                continue;
+            }
+            writeln!(f, "component {} ({}):", i, identifier)?;
+            for issue in issues {
+                writeln!(f, "  - {}", issue)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validate the chemical records of a system against segment records and group definitions.
+///
+/// For every component, the following problems are reported:
+/// - segments that are missing in `segment_records`,
+/// - bonds that refer to non-existent segment indices or connect a segment to itself,
+/// - bond graphs that are not connected,
+/// - segments whose number of bonds does not agree with the valence of any
+///   definition of the corresponding group in `groups`. Segments without a group
+///   definition are not checked.
+///
+/// Chemical records that only contain segment and bond counts are only checked for
+/// missing segments. An error is returned if any of the group definitions is invalid.
+pub fn validate_chemical_records<M, I>(
+    chemical_records: &[ChemicalRecord],
+    segment_records: &[SegmentRecord<M, I>],
+    groups: &[GroupDefinition],
+) -> Result<ValidationReport, ParameterError> {
+    let available: HashSet<_> = segment_records.iter().map(|s| &s.identifier).collect();
+    let mut valences: HashMap<&String, Vec<usize>> = HashMap::new();
+    for group in groups.iter() {
+        let valence = group.valence()?;
+        let v = valences.entry(&group.identifier).or_default();
+        if !v.contains(&valence) {
+            v.push(valence);
+        }
+    }
+    for v in valences.values_mut() {
+        v.sort_unstable();
+    }
+
+    let components = chemical_records
+        .iter()
+        .map(|record| {
+            let mut issues = Vec::new();
+            let mut missing: Vec<_> = record
+                .segment_id_count()
+                .keys()
+                .filter(|s| !available.contains(s))
+                .cloned()
+                .collect();
+            missing.sort();
+            issues.extend(missing.into_iter().map(ChemicalRecordIssue::MissingSegment));
+
+            if let Ok((segments, bonds)) = record.segment_and_bond_list() {
+                let n = segments.len();
+                let mut neighbors = vec![Vec::new(); n];
+                for &[a, b] in bonds.iter() {
+                    if a >= n || b >= n || a == b {
+                        issues.push(ChemicalRecordIssue::InvalidBond([a, b]));
+                    } else {
+                        neighbors[a].push(b);
+                        neighbors[b].push(a);
+                    }
+                }
+
+                let parts = connected_parts(&neighbors);
+                if parts.len() > 1 {
+                    issues.push(ChemicalRecordIssue::Disconnected(parts));
+                }
+
+                for (i, (identifier, neighbors)) in segments.iter().zip(&neighbors).enumerate() {
+                    if let Some(expected) = valences.get(identifier) {
+                        if !expected.contains(&neighbors.len()) {
+                            issues.push(ChemicalRecordIssue::Valence {
+                                segment: i,
+                                identifier: identifier.clone(),
+                                bonds: neighbors.len(),
+                                expected: expected.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            (record.identifier().clone(), issues)
+        })
+        .collect();
+    Ok(ValidationReport { components })
+}
+
+/// Connected parts of a graph given by its adjacency lists.
+fn connected_parts(neighbors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; neighbors.len()];
+    let mut parts = Vec::new();
+    for start in 0..neighbors.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut part = vec![start];
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            for &j in neighbors[i].iter() {
+                if !visited[j] {
+                    visited[j] = true;
+                    part.push(j);
+                    stack.push(j);
+                }
+            }
+        }
+        part.sort_unstable();
+        parts.push(part);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GcPcSaftRecord;
+    use feos_core::joback::JobackRecord;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn segment_records() -> Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>> {
+        let reader = BufReader::new(File::open("parameters/sauer2014_hetero.json").unwrap());
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    fn record(name: &str, segments: &[&str], bonds: Vec<[usize; 2]>) -> ChemicalRecord {
+        let identifier = Identifier::new("", Some(name), None, None, None, None);
+        let segments = segments.iter().map(|s| s.to_string()).collect();
+        ChemicalRecord::new(identifier, segments, Some(bonds))
+    }
+
+    #[test]
+    fn test_gc_substances() {
+        let reader = BufReader::new(File::open("parameters/gc_substances.json").unwrap());
+        let records: Vec<ChemicalRecord> = serde_json::from_reader(reader).unwrap();
+        let report =
+            validate_chemical_records(&records, &segment_records(), &GroupDefinition::sauer2014())
+                .unwrap();
+        for (identifier, issues) in report.components {
+            match identifier.name.as_deref().unwrap() {
+                // isopropyl ethanoate is listed with a CH2 instead of a >CH group
+                "isopropyl ethanoate" => assert_eq!(
+                    issues,
+                    vec![ChemicalRecordIssue::Valence {
+                        segment: 1,
+                        identifier: "CH2".into(),
+                        bonds: 3,
+                        expected: vec![2]
+                    }]
+                ),
+                _ => assert!(issues.is_empty(), "{}: {:?}", identifier, issues),
+            }
+        }
+    }
+
+    #[test]
+    fn test_issues() {
+        let records = vec![
+            record(
+                "propanol",
+                &["CH3", "CH2", "CH2", "OH"],
+                vec![[0, 1], [1, 2], [2, 3]],
+            ),
+            record("missing", &["CH3", "CH4"], vec![[0, 1]]),
+            record("invalid", &["CH3", "CH3"], vec![[0, 1], [1, 2], [0, 0]]),
+            record(
+                "disconnected",
+                &["CH3", "CH3", "CH3", "CH3"],
+                vec![[0, 1], [2, 3]],
+            ),
+            record("valence", &["CH3", "CH2", "CH3"], vec![[0, 1], [0, 2]]),
+        ];
+        let report =
+            validate_chemical_records(&records, &segment_records(), &GroupDefinition::sauer2014())
+                .unwrap();
+        assert!(!report.is_valid());
+        let issues: Vec<_> = report.components.iter().map(|(_, i)| i.clone()).collect();
+        assert!(issues[0].is_empty());
+        assert_eq!(
+            issues[1],
+            vec![ChemicalRecordIssue::MissingSegment("CH4".into())]
+        );
+        assert_eq!(
+            issues[2],
+            vec![
+                ChemicalRecordIssue::InvalidBond([1, 2]),
+                ChemicalRecordIssue::InvalidBond([0, 0])
+            ]
+        );
+        assert_eq!(
+            issues[3],
+            vec![ChemicalRecordIssue::Disconnected(vec![
+                vec![0, 1],
+                vec![2, 3]
+            ])]
+        );
+        assert_eq!(
+            issues[4],
+            vec![
+                ChemicalRecordIssue::Valence {
+                    segment: 0,
+                    identifier: "CH3".into(),
+                    bonds: 2,
+                    expected: vec![1]
+                },
+                ChemicalRecordIssue::Valence {
+                    segment: 1,
+                    identifier: "CH2".into(),
+                    bonds: 1,
+                    expected: vec![2]
+                }
+            ]
+        );
+        assert!(report.into_result().is_err());
+    }
+}
@@ -7,6 +7,7 @@ use feos_gc_pcsaft::{
 use ndarray::arr1;
 use quantity::si::{KELVIN, MOL};
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[test]
 fn test_binary() -> EosResult<()> {
@@ -26,8 +27,8 @@ fn test_binary() -> EosResult<()> {
         IdentifierOption::Name,
     )
     .unwrap();
-    let eos = Rc::new(GcPcSaft::new(Rc::new(parameters)));
-    let func = Rc::new(GcPcSaftFunctional::new(Rc::new(parameters_func)));
+    let eos = Rc::new(GcPcSaft::new(Arc::new(parameters)));
+    let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters_func)));
     let moles = arr1(&[0.5, 0.5]) * MOL;
     let cp = State::critical_point(&eos, Some(&moles), None, Default::default())?;
     let cp_func = State::critical_point(&func, Some(&moles), None, Default::default())?;
@@ -45,3 +46,65 @@ fn test_binary() -> EosResult<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_shared_parameters() -> EosResult<()> {
+    let parameters = Arc::new(
+        GcPcSaftEosParameters::from_json_segments(
+            &["ethanol", "methanol"],
+            "parameters/gc_substances.json",
+            "parameters/sauer2014_hetero.json",
+            None,
+            IdentifierOption::Name,
+        )
+        .unwrap(),
+    );
+    let critical_temperature = |parameters| {
+        let eos = Rc::new(GcPcSaft::new(parameters));
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        State::critical_point(&eos, Some(&moles), None, Default::default())
+            .map(|cp| cp.temperature.to_reduced(KELVIN).unwrap())
+    };
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let parameters = parameters.clone();
+            std::thread::spawn(move || critical_temperature(parameters))
+        })
+        .collect();
+    let tc = critical_temperature(parameters)?;
+    for handle in handles {
+        assert_eq!(handle.join().unwrap()?, tc);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_shared_functional_parameters() -> EosResult<()> {
+    let parameters = Arc::new(
+        GcPcSaftFunctionalParameters::from_json_segments(
+            &["ethanol", "methanol"],
+            "parameters/gc_substances.json",
+            "parameters/sauer2014_hetero.json",
+            None,
+            IdentifierOption::Name,
+        )
+        .unwrap(),
+    );
+    let critical_temperature = |parameters| {
+        let func = Rc::new(GcPcSaftFunctional::new(parameters));
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        State::critical_point(&func, Some(&moles), None, Default::default())
+            .map(|cp| cp.temperature.to_reduced(KELVIN).unwrap())
+    };
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let parameters = parameters.clone();
+            std::thread::spawn(move || critical_temperature(parameters))
+        })
+        .collect();
+    let tc = critical_temperature(parameters)?;
+    for handle in handles {
+        assert_eq!(handle.join().unwrap()?, tc);
+    }
+    Ok(())
+}
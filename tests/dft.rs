@@ -1,17 +1,101 @@
 #![allow(clippy::excessive_precision)]
 use approx::assert_relative_eq;
-use feos_core::parameter::IdentifierOption;
+use feos_core::joback::JobackRecord;
+use feos_core::parameter::{ChemicalRecord, Identifier, IdentifierOption, SegmentRecord};
 use feos_core::{PhaseEquilibrium, State, StateBuilder, Verbosity};
 use feos_dft::adsorption::{ExternalPotential, Pore1D, PoreSpecification};
+use feos_dft::fundamental_measure_theory::FMTVersion;
 use feos_dft::interface::PlanarInterface;
-use feos_dft::{DFTSolver, Geometry};
+use feos_dft::{DFTSolver, Geometry, DFT};
 use feos_gc_pcsaft::{
-    GcPcSaft, GcPcSaftEosParameters, GcPcSaftFunctional, GcPcSaftFunctionalParameters,
+    AssociationRecord, AssociationSite, GcPcSaft, GcPcSaftEosParameters, GcPcSaftFunctional,
+    GcPcSaftFunctionalParameters, GcPcSaftOptions, GcPcSaftRecord, Permittivity, SiteInteraction,
 };
 use ndarray::arr1;
 use quantity::si::*;
 use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
 use std::rc::Rc;
+use std::sync::Arc;
+
+fn sauer2014_segments() -> Result<Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>, Box<dyn Error>>
+{
+    Ok(serde_json::from_reader(BufReader::new(File::open(
+        "parameters/sauer2014_hetero.json",
+    )?))?)
+}
+
+fn eos_and_functional(
+    chemical_records: Vec<ChemicalRecord>,
+    segment_records: Vec<SegmentRecord<GcPcSaftRecord, JobackRecord>>,
+) -> Result<(Rc<GcPcSaft>, Rc<DFT<GcPcSaftFunctional>>), Box<dyn Error>> {
+    let parameters = GcPcSaftEosParameters::from_segments(
+        chemical_records.clone(),
+        segment_records.clone(),
+        None,
+    )?;
+    let parameters_func =
+        GcPcSaftFunctionalParameters::from_segments(chemical_records, segment_records, None)?;
+    Ok((
+        Rc::new(GcPcSaft::new(Arc::new(parameters))),
+        Rc::new(GcPcSaftFunctional::new(Arc::new(parameters_func))),
+    ))
+}
+
+/// Names of the contributions of the equation of state together with the
+/// prefixes of the names of the corresponding functional contributions.
+const CONTRIBUTION_NAMES: [(&str, &str); 11] = [
+    ("Ideal gas (GC)", "Ideal gas"),
+    ("Hard Sphere (GC)", "FMT functional"),
+    ("Hard Chain (GC)", "Hard chain functional (GC)"),
+    ("Dispersion (GC)", "Attractive functional (GC)"),
+    ("Association", "Association functional"),
+    ("Cross-association", "Association functional"),
+    ("Dipole", "Dipole functional (GC)"),
+    ("Quadrupole", "Quadrupole functional (GC)"),
+    ("Dipole-Quadrupole", "Dipole-Quadrupole functional (GC)"),
+    ("Debye-Hückel", "Debye-Hückel functional (GC)"),
+    ("Born", "Born functional (GC)"),
+];
+
+/// Compare the pressure contributions of equation of state and functional
+/// by name. The only additional contribution of the functional is the ideal
+/// chain contribution, which vanishes in the bulk.
+fn assert_pressure_contributions(
+    eos: &Rc<GcPcSaft>,
+    func: &Rc<DFT<GcPcSaftFunctional>>,
+    temperature: SINumber,
+    volume: SINumber,
+    moles: &SIArray1,
+    contributions: usize,
+    max_relative: f64,
+) -> Result<(), Box<dyn Error>> {
+    let p_eos = State::new_nvt(eos, temperature, volume, moles)?.pressure_contributions();
+    let p_func = State::new_nvt(func, temperature, volume, moles)?.pressure_contributions();
+    assert_eq!(p_eos.len(), contributions);
+    assert_eq!(p_func.len(), contributions + 1);
+
+    let mut matched = vec![false; p_func.len()];
+    for (name_eos, p_e) in p_eos.iter() {
+        let name_func = CONTRIBUTION_NAMES
+            .iter()
+            .find(|(e, _)| e == name_eos)
+            .map(|&(_, f)| f)
+            .unwrap_or_else(|| panic!("unknown contribution {}", name_eos));
+        let k = p_func
+            .iter()
+            .position(|(name, _)| name.starts_with(name_func))
+            .unwrap_or_else(|| panic!("functional misses contribution {}", name_eos));
+        assert!(!matched[k]);
+        matched[k] = true;
+        assert_relative_eq!(*p_e, p_func[k].1, max_relative = max_relative);
+    }
+    for ((_, p_f), _) in p_func.iter().zip(matched).filter(|(_, m)| !m) {
+        assert!(p_f.to_reduced(p_eos[0].1)?.abs() < 1e-12);
+    }
+    Ok(())
+}
 
 #[test]
 #[allow(non_snake_case)]
@@ -38,8 +122,8 @@ fn test_bulk_implementation() -> Result<(), Box<dyn Error>> {
     )
     .unwrap();
 
-    let eos = Rc::new(GcPcSaft::new(Rc::new(parameters)));
-    let func = Rc::new(GcPcSaftFunctional::new(Rc::new(parameters_func)));
+    let eos = Rc::new(GcPcSaft::new(Arc::new(parameters)));
+    let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters_func)));
     let t = 200.0 * KELVIN;
     let v = 0.002 * METER.powi(3) * NAV / NAV_old;
     let n = arr1(&[1.5]) * MOL;
@@ -101,6 +185,212 @@ fn test_bulk_implementation() -> Result<(), Box<dyn Error>> {
         -763.2289230004602132 * KILO * PASCAL * KB / KB_old,
         max_relative = 1e-14,
     );
+
+    // polar component
+    let parameters = GcPcSaftEosParameters::from_json_segments(
+        &["butanone"],
+        "parameters/gc_substances.json",
+        "parameters/sauer2014_hetero.json",
+        None,
+        IdentifierOption::Name,
+    )
+    .unwrap();
+
+    let parameters_func = GcPcSaftFunctionalParameters::from_json_segments(
+        &["butanone"],
+        "parameters/gc_substances.json",
+        "parameters/sauer2014_hetero.json",
+        None,
+        IdentifierOption::Name,
+    )
+    .unwrap();
+
+    let eos = Rc::new(GcPcSaft::new(Arc::new(parameters)));
+    let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters_func)));
+    let t = 300.0 * KELVIN;
+    let v = 0.002 * METER.powi(3);
+    let n = arr1(&[1.5]) * MOL;
+    assert_pressure_contributions(&eos, &func, t, v, &n, 5, 1e-14)?;
+    Ok(())
+}
+
+#[test]
+fn test_bulk_implementation_quadrupole() -> Result<(), Box<dyn Error>> {
+    let mut segment_records = sauer2014_segments()?;
+    segment_records.push(SegmentRecord::new(
+        "CO2".into(),
+        44.0098,
+        GcPcSaftRecord {
+            m: 1.5131,
+            sigma: 3.1869,
+            epsilon_k: 163.33,
+            q: Some(4.4),
+            ..Default::default()
+        },
+        None,
+    ));
+    let acetone = ChemicalRecord::new(
+        Identifier::new("67-64-1", Some("acetone"), None, None, None, None),
+        vec!["CH3".into(), ">C=O".into(), "CH3".into()],
+        None,
+    );
+    let co2 = ChemicalRecord::new(
+        Identifier::new("124-38-9", Some("carbon dioxide"), None, None, None, None),
+        vec!["CO2".into()],
+        None,
+    );
+
+    let (eos, func) = eos_and_functional(vec![acetone, co2], segment_records)?;
+    let t = 300.0 * KELVIN;
+    let v = 0.002 * METER.powi(3);
+    let n = arr1(&[1.5, 0.5]) * MOL;
+    assert_pressure_contributions(&eos, &func, t, v, &n, 7, 1e-14)?;
+    Ok(())
+}
+
+#[test]
+fn test_bulk_implementation_induced_association() -> Result<(), Box<dyn Error>> {
+    let mut segment_records = sauer2014_segments()?;
+    for segment in segment_records.iter_mut() {
+        if segment.identifier == ">C=O" {
+            segment.model_record.nb = Some(2.0);
+        }
+    }
+    let ethanol = ChemicalRecord::new(
+        Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
+        vec!["CH3".into(), "CH2".into(), "OH".into()],
+        None,
+    );
+    let acetone = ChemicalRecord::new(
+        Identifier::new("67-64-1", Some("acetone"), None, None, None, None),
+        vec!["CH3".into(), ">C=O".into(), "CH3".into()],
+        None,
+    );
+
+    let (eos, func) = eos_and_functional(vec![ethanol, acetone], segment_records)?;
+    let t = 300.0 * KELVIN;
+    let v = 0.002 * METER.powi(3);
+    let n = arr1(&[1.5, 0.5]) * MOL;
+    assert_pressure_contributions(&eos, &func, t, v, &n, 6, 1e-14)?;
+    Ok(())
+}
+
+#[test]
+fn test_bulk_implementation_association_sites() -> Result<(), Box<dyn Error>> {
+    let mut segment_records = sauer2014_segments()?;
+    segment_records.push(SegmentRecord::new(
+        "COOH".into(),
+        45.0174,
+        GcPcSaftRecord {
+            m: 1.3,
+            sigma: 3.1,
+            epsilon_k: 320.0,
+            association_record: Some(AssociationRecord::new(
+                vec![
+                    AssociationSite::new("C".into(), 1.0),
+                    AssociationSite::new("A".into(), 1.0),
+                    AssociationSite::new("B".into(), 2.0),
+                ],
+                vec![
+                    SiteInteraction::new("C".into(), "C".into(), 0.01, 2500.0),
+                    SiteInteraction::new("A".into(), "B".into(), 0.005, 1500.0),
+                ],
+            )),
+            ..Default::default()
+        },
+        None,
+    ));
+    let ethanol = ChemicalRecord::new(
+        Identifier::new("64-17-5", Some("ethanol"), None, None, None, None),
+        vec!["CH3".into(), "CH2".into(), "OH".into()],
+        None,
+    );
+    let acid = ChemicalRecord::new(
+        Identifier::new("79-09-4", Some("propanoic acid"), None, None, None, None),
+        vec!["CH3".into(), "CH2".into(), "COOH".into()],
+        None,
+    );
+
+    let (eos, func) = eos_and_functional(vec![ethanol, acid], segment_records)?;
+    let t = 350.0 * KELVIN;
+    let v = 0.002 * METER.powi(3);
+    let n = arr1(&[1.5, 0.5]) * MOL;
+    assert_pressure_contributions(&eos, &func, t, v, &n, 5, 1e-12)?;
+    Ok(())
+}
+
+#[test]
+fn test_bulk_implementation_ions() -> Result<(), Box<dyn Error>> {
+    let segment = |identifier: &str, sigma, epsilon_k, z| {
+        SegmentRecord::new(
+            identifier.into(),
+            20.0,
+            GcPcSaftRecord {
+                m: 1.0,
+                sigma,
+                epsilon_k,
+                z,
+                ..Default::default()
+            },
+            None,
+        )
+    };
+    let segment_records = vec![
+        SegmentRecord::new(
+            "H2O".into(),
+            18.015,
+            GcPcSaftRecord {
+                m: 1.2047,
+                sigma: 2.7927,
+                epsilon_k: 353.95,
+                kappa_ab: Some(0.0451),
+                epsilon_k_ab: Some(2425.67),
+                ..Default::default()
+            },
+            None,
+        ),
+        segment("Na+", 2.8232, 230.0, Some(1.0)),
+        segment("Cl-", 2.7560, 170.0, Some(-1.0)),
+    ];
+    let water = ChemicalRecord::new(
+        Identifier::new("7732-18-5", Some("water"), None, None, None, None),
+        vec!["H2O".into()],
+        None,
+    );
+    let salt = ChemicalRecord::new(
+        Identifier::new("7647-14-5", Some("sodium chloride"), None, None, None, None),
+        vec!["Na+".into(), "Cl-".into()],
+        Some(vec![]),
+    );
+
+    let parameters = GcPcSaftEosParameters::from_segments(
+        vec![water.clone(), salt.clone()],
+        segment_records.clone(),
+        None,
+    )?;
+    let parameters_func =
+        GcPcSaftFunctionalParameters::from_segments(vec![water, salt], segment_records, None)?;
+    assert_eq!(parameters_func.ion_segment, arr1(&[1, 2]));
+
+    let options = GcPcSaftOptions {
+        born: true,
+        ..Default::default()
+    };
+    let permittivity = Permittivity::SaltFreeMixing(vec![78.4, 10.0]);
+    let eos = Rc::new(
+        GcPcSaft::with_options(Arc::new(parameters), options)
+            .with_permittivity(permittivity.clone())?,
+    );
+    let func = Rc::new(GcPcSaftFunctional::with_permittivity(
+        Arc::new(parameters_func),
+        FMTVersion::WhiteBear,
+        options,
+        permittivity,
+    )?);
+    let t = 298.15 * KELVIN;
+    let v = 0.001 * METER.powi(3);
+    let n = arr1(&[55.0, 1.0]) * MOL;
+    assert_pressure_contributions(&eos, &func, t, v, &n, 7, 1e-12)?;
     Ok(())
 }
 
@@ -120,7 +410,7 @@ fn test_dft() -> Result<(), Box<dyn Error>> {
     )
     .unwrap();
 
-    let func = Rc::new(GcPcSaftFunctional::new(Rc::new(parameters)));
+    let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters)));
     let t = 200.0 * KELVIN;
     let w = 150.0 * ANGSTROM;
     let points = 2048;
@@ -166,7 +456,7 @@ fn test_dft_assoc() -> Result<(), Box<dyn Error>> {
     )
     .unwrap();
 
-    let func = Rc::new(GcPcSaftFunctional::new(Rc::new(parameters)));
+    let func = Rc::new(GcPcSaftFunctional::new(Arc::new(parameters)));
     let t = 300.0 * KELVIN;
     let w = 100.0 * ANGSTROM;
     let points = 4096;